@@ -1,11 +1,16 @@
 // Copyright (C) 2024 SUSE LLC <petr.pavlu@suse.com>
 // SPDX-License-Identifier: GPL-2.0-or-later
 
-use suse_kabi_tools::sym::SymCorpus;
-use suse_kabi_tools::{debug, init_debug_level};
-use std::path::Path;
+use std::io::{IsTerminal, Write};
 use std::time::Instant;
 use std::{env, io, process};
+use suse_kabi_tools::diff::DiffOptions;
+use suse_kabi_tools::maintainers::Maintainers;
+use suse_kabi_tools::source::SourceIndex;
+use suse_kabi_tools::sym::{
+    read_module_imports, BinaryExports, CompareFormat, CompareOptions, StatsFormat, SymCorpus,
+};
+use suse_kabi_tools::{debug, init_debug_level};
 
 /// An elapsed timer to measure time of some operation.
 ///
@@ -40,6 +45,55 @@ impl Drop for Timing {
     }
 }
 
+/// A pager which displays the output of a command, such as `less`.
+struct Pager {
+    command: String,
+    child: process::Child,
+}
+
+impl Pager {
+    /// Starts the pager specified by the `PAGER` environment variable, or `less -FRX` if it is not
+    /// set.
+    ///
+    /// The pager is started only if the standard output is a terminal. [`None`] is returned if the
+    /// pager is not needed, it is disabled by an empty `PAGER` or `cat`, or it fails to start.
+    fn start() -> Option<Self> {
+        if !io::stdout().is_terminal() {
+            return None;
+        }
+
+        let command = env::var("PAGER").unwrap_or_else(|_| "less -FRX".to_string());
+        if command.trim().is_empty() || command.trim() == "cat" {
+            return None;
+        }
+
+        debug!("Starting pager '{}'", command);
+        match process::Command::new("sh")
+            .arg("-c")
+            .arg(&command)
+            .stdin(process::Stdio::piped())
+            .spawn()
+        {
+            Ok(child) => Some(Self { command, child }),
+            Err(err) => {
+                debug!("Failed to start pager '{}': {}", command, err);
+                None
+            }
+        }
+    }
+
+    /// Returns the input of the pager.
+    fn input(&mut self) -> &mut process::ChildStdin {
+        self.child.stdin.as_mut().unwrap()
+    }
+
+    /// Closes the input of the pager, waits for the user to quit it and returns its exit status.
+    fn finish(mut self) -> io::Result<process::ExitStatus> {
+        drop(self.child.stdin.take());
+        self.child.wait()
+    }
+}
+
 /// Prints the global usage message on the standard output.
 fn print_usage() {
     print!(concat!(
@@ -53,6 +107,12 @@ fn print_usage() {
         "Commands:\n",
         "  consolidate                   consolidate symtypes into a single file\n",
         "  compare                       show differences between two symtypes corpuses\n",
+        "  tree                          show the reference tree of an export\n",
+        "  stats                         show statistics about a symtypes corpus\n",
+        "  variants                      show all variants of a type\n",
+        "  lint                          check a symtypes corpus for conflicting types\n",
+        "  check-exports                 check exports of a symtypes corpus against binaries\n",
+        "  check-module                  check imports of a module against a reference corpus\n",
     ));
 }
 
@@ -70,6 +130,7 @@ fn print_consolidate_usage() {
         "Options:\n",
         "  -h, --help                    display this help and exit\n",
         "  -j NUM, --jobs=NUM            use NUM workers to perform the operation\n",
+        "  --lenient                     skip symtypes files with errors\n",
         "  -o FILE, --output=FILE        write the result in FILE, instead of stdout\n",
     ));
 }
@@ -81,8 +142,105 @@ fn print_compare_usage() {
         "Show differences between two symtypes corpuses.\n",
         "\n",
         "Options:\n",
+        "  --color=WHEN                  highlight the differences with colors: auto (default),\n",
+        "                                always, or never\n",
+        "  -f FORMAT, --format=FORMAT    use the specified output format: text (default),\n",
+        "                                sarif, junit, markdown, or html\n",
+        "  --group-by=KEY                group the changes by KEY: module\n",
+        "  -h, --help                    display this help and exit\n",
+        "  -j NUM, --jobs=NUM            use NUM workers to perform the operation\n",
+        "  --lenient                     skip symtypes files with errors\n",
+        "  --maintainers=FILE            group the changes by subsystems listed in FILE\n",
+        "  --no-pager                    do not pipe the output through a pager\n",
+        "  --source-tree=DIR             locate definitions of changed types in source tree DIR\n",
+        "  -U NUM, --unified=NUM         show NUM lines of context (default 3)\n",
+        "  --word-diff                   show changed words instead of changed lines\n",
+    ));
+}
+
+/// Prints the usage message for the `tree` command on the standard output.
+fn print_tree_usage() {
+    print!(concat!(
+        "Usage: ksymtypes tree [OPTION...] PATH EXPORT\n",
+        "Show the reference tree of an export.\n",
+        "\n",
+        "Options:\n",
+        "  -h, --help                    display this help and exit\n",
+        "  -j NUM, --jobs=NUM            use NUM workers to perform the operation\n",
+        "  --lenient                     skip symtypes files with errors\n",
+    ));
+}
+
+/// Prints the usage message for the `stats` command on the standard output.
+fn print_stats_usage() {
+    print!(concat!(
+        "Usage: ksymtypes stats [OPTION...] PATH\n",
+        "Show statistics about a symtypes corpus.\n",
+        "\n",
+        "Options:\n",
+        "  -f FORMAT, --format=FORMAT    use the specified output format: text (default), or json\n",
+        "  -h, --help                    display this help and exit\n",
+        "  -j NUM, --jobs=NUM            use NUM workers to perform the operation\n",
+        "  --lenient                     skip symtypes files with errors\n",
+    ));
+}
+
+/// Prints the usage message for the `variants` command on the standard output.
+fn print_variants_usage() {
+    print!(concat!(
+        "Usage: ksymtypes variants [OPTION...] PATH TYPE\n",
+        "Show all variants of a type.\n",
+        "\n",
+        "Options:\n",
+        "  --color=WHEN                  highlight the differences with colors: auto (default),\n",
+        "                                always, or never\n",
+        "  -h, --help                    display this help and exit\n",
+        "  -j NUM, --jobs=NUM            use NUM workers to perform the operation\n",
+        "  --lenient                     skip symtypes files with errors\n",
+    ));
+}
+
+/// Prints the usage message for the `lint` command on the standard output.
+fn print_lint_usage() {
+    print!(concat!(
+        "Usage: ksymtypes lint [OPTION...] PATH\n",
+        "Check a symtypes corpus for conflicting types.\n",
+        "\n",
+        "Options:\n",
+        "  --color=WHEN                  highlight the differences with colors: auto (default),\n",
+        "                                always, or never\n",
+        "  -h, --help                    display this help and exit\n",
+        "  -j NUM, --jobs=NUM            use NUM workers to perform the operation\n",
+        "  --lenient                     skip symtypes files with errors\n",
+        "  --strict                      exit with an error status if any problem is found\n",
+    ));
+}
+
+/// Prints the usage message for the `check-exports` command on the standard output.
+fn print_check_exports_usage() {
+    print!(concat!(
+        "Usage: ksymtypes check-exports [OPTION...] PATH BINARY-PATH\n",
+        "Check exports of a symtypes corpus against kernel binaries.\n",
+        "\n",
+        "Options:\n",
+        "  -h, --help                    display this help and exit\n",
+        "  -j NUM, --jobs=NUM            use NUM workers to perform the operation\n",
+        "  --lenient                     skip symtypes files with errors\n",
+        "  --strict                      exit with an error status if any problem is found\n",
+    ));
+}
+
+/// Prints the usage message for the `check-module` command on the standard output.
+fn print_check_module_usage() {
+    print!(concat!(
+        "Usage: ksymtypes check-module [OPTION...] REFERENCE NEW MODULE\n",
+        "Check imports of a module against a reference symtypes corpus.\n",
+        "\n",
+        "Options:\n",
         "  -h, --help                    display this help and exit\n",
         "  -j NUM, --jobs=NUM            use NUM workers to perform the operation\n",
+        "  --lenient                     skip symtypes files with errors\n",
+        "  --strict                      exit with an error status if any problem is found\n",
     ));
 }
 
@@ -90,7 +248,7 @@ fn print_compare_usage() {
 ///
 /// When the `arg` matches the `short` or `long` variant, the function returns [`Ok(Some(String))`]
 /// with the option value. Otherwise, [`Ok(None)`] is returned when the `arg` doesn't match, or
-/// [`Err`] in case of an error.
+/// [`Err`] in case of an error. An empty `short` indicates that the option has no short variant.
 fn handle_value_option<I: Iterator<Item = String>>(
     arg: &str,
     args: &mut I,
@@ -98,7 +256,7 @@ fn handle_value_option<I: Iterator<Item = String>>(
     long: &str,
 ) -> Result<Option<String>, ()> {
     // Handle '-<short> <value>' and '--<long> <value>'.
-    if arg == short || arg == long {
+    if (!short.is_empty() && arg == short) || arg == long {
         match args.next() {
             Some(value) => return Ok(Some(value.to_string())),
             None => {
@@ -109,8 +267,10 @@ fn handle_value_option<I: Iterator<Item = String>>(
     }
 
     // Handle '-<short><value>'.
-    if let Some(value) = arg.strip_prefix(short) {
-        return Ok(Some(value.to_string()));
+    if !short.is_empty() {
+        if let Some(value) = arg.strip_prefix(short) {
+            return Ok(Some(value.to_string()));
+        }
     }
 
     // Handle '--<long>=<value>'.
@@ -148,12 +308,83 @@ fn handle_jobs_option<I: Iterator<Item = String>>(
     Ok(None)
 }
 
+/// Handles the `--color` option which specifies when to highlight the output with colors.
+///
+/// The `auto` mode enables colors only if the standard output is a terminal and the `NO_COLOR`
+/// environment variable is not set to a non-empty value.
+fn handle_color_option<I: Iterator<Item = String>>(
+    arg: &str,
+    args: &mut I,
+) -> Result<Option<bool>, ()> {
+    if let Some(value) = handle_value_option(arg, args, "", "--color")? {
+        match value.as_str() {
+            "auto" => return Ok(Some(is_color_auto())),
+            "always" => return Ok(Some(true)),
+            "never" => return Ok(Some(false)),
+            _ => {
+                eprintln!("Invalid value for '{}': unknown mode '{}'", arg, value);
+                return Err(());
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// Returns whether colors should be used in the `auto` mode of the `--color` option.
+fn is_color_auto() -> bool {
+    io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+}
+
+/// Reads a symtypes corpus from a given path.
+///
+/// In the lenient mode, files which fail to load are skipped and a warning is printed for each of
+/// them on the standard error, followed by a summary.
+fn read_symtypes(
+    path: &str,
+    num_workers: i32,
+    is_lenient: bool,
+    do_timing: bool,
+) -> Result<SymCorpus, ()> {
+    let _timing = Timing::new(do_timing, &format!("Reading symtypes from '{}'", path));
+
+    let mut syms = SymCorpus::new();
+    if !is_lenient {
+        if let Err(err) = syms.load(path, num_workers) {
+            eprintln!("Failed to read symtypes from '{}': {}", path, err);
+            return Err(());
+        }
+        return Ok(syms);
+    }
+
+    match syms.load_lenient(path, num_workers) {
+        Ok(warnings) => {
+            for warning in &warnings {
+                eprintln!("Warning: {}", warning);
+            }
+            if !warnings.is_empty() {
+                eprintln!(
+                    "Skipped '{}' files with errors when reading symtypes from '{}'",
+                    warnings.len(),
+                    path
+                );
+            }
+            Ok(syms)
+        }
+        Err(err) => {
+            eprintln!("Failed to read symtypes from '{}': {}", path, err);
+            Err(())
+        }
+    }
+}
+
 /// Handles the `consolidate` command which consolidates symtypes into a single file.
 fn do_consolidate<I: IntoIterator<Item = String>>(do_timing: bool, args: I) -> Result<(), ()> {
     // Parse specific command options.
     let mut args = args.into_iter();
     let mut output = "-".to_string();
     let mut num_workers = 1;
+    let mut is_lenient = false;
     let mut past_dash_dash = false;
     let mut maybe_path = None;
 
@@ -167,6 +398,10 @@ fn do_consolidate<I: IntoIterator<Item = String>>(do_timing: bool, args: I) -> R
                 num_workers = value;
                 continue;
             }
+            if arg == "--lenient" {
+                is_lenient = true;
+                continue;
+            }
             if arg == "-h" || arg == "--help" {
                 print_consolidate_usage();
                 return Ok(());
@@ -194,16 +429,7 @@ fn do_consolidate<I: IntoIterator<Item = String>>(do_timing: bool, args: I) -> R
     })?;
 
     // Do the consolidation.
-    let mut syms = SymCorpus::new();
-
-    {
-        let _timing = Timing::new(do_timing, &format!("Reading symtypes from '{}'", path));
-
-        if let Err(err) = syms.load(&path, num_workers) {
-            eprintln!("Failed to read symtypes from '{}': {}", path, err);
-            return Err(());
-        }
-    }
+    let syms = read_symtypes(&path, num_workers, is_lenient, do_timing)?;
 
     {
         let _timing = Timing::new(
@@ -228,16 +454,80 @@ fn do_compare<I: IntoIterator<Item = String>>(do_timing: bool, args: I) -> Resul
     // Parse specific command options.
     let mut args = args.into_iter();
     let mut num_workers = 1;
+    let mut is_lenient = false;
+    let mut options = CompareOptions::default();
+    let mut maybe_maintainers_path = None;
+    let mut maybe_source_tree = None;
+    let mut maybe_color = None;
+    let mut use_pager = true;
     let mut past_dash_dash = false;
     let mut maybe_path = None;
     let mut maybe_path2 = None;
 
     while let Some(arg) = args.next() {
         if !past_dash_dash {
+            if let Some(value) = handle_color_option(&arg, &mut args)? {
+                maybe_color = Some(value);
+                continue;
+            }
+            if let Some(value) = handle_value_option(&arg, &mut args, "-f", "--format")? {
+                options.format = match value.as_str() {
+                    "text" => CompareFormat::Text,
+                    "sarif" => CompareFormat::Sarif,
+                    "junit" => CompareFormat::Junit,
+                    "markdown" => CompareFormat::Markdown,
+                    "html" => CompareFormat::Html,
+                    _ => {
+                        eprintln!("Invalid value for '{}': unknown format '{}'", arg, value);
+                        return Err(());
+                    }
+                };
+                continue;
+            }
+            if let Some(value) = handle_value_option(&arg, &mut args, "", "--group-by")? {
+                match value.as_str() {
+                    "module" => options.group_by_module = true,
+                    _ => {
+                        eprintln!("Invalid value for '{}': unknown key '{}'", arg, value);
+                        return Err(());
+                    }
+                }
+                continue;
+            }
             if let Some(value) = handle_jobs_option(&arg, &mut args)? {
                 num_workers = value;
                 continue;
             }
+            if arg == "--lenient" {
+                is_lenient = true;
+                continue;
+            }
+            if let Some(value) = handle_value_option(&arg, &mut args, "", "--maintainers")? {
+                maybe_maintainers_path = Some(value);
+                continue;
+            }
+            if arg == "--no-pager" {
+                use_pager = false;
+                continue;
+            }
+            if let Some(value) = handle_value_option(&arg, &mut args, "", "--source-tree")? {
+                maybe_source_tree = Some(value);
+                continue;
+            }
+            if let Some(value) = handle_value_option(&arg, &mut args, "-U", "--unified")? {
+                match value.parse::<usize>() {
+                    Ok(context_size) => options.diff.context_size = context_size,
+                    Err(err) => {
+                        eprintln!("Invalid value for '{}': {}", arg, err);
+                        return Err(());
+                    }
+                }
+                continue;
+            }
+            if arg == "--word-diff" {
+                options.diff.word_diff = true;
+                continue;
+            }
             if arg == "-h" || arg == "--help" {
                 print_compare_usage();
                 return Ok(());
@@ -270,42 +560,659 @@ fn do_compare<I: IntoIterator<Item = String>>(do_timing: bool, args: I) -> Resul
     let path2 = maybe_path2.ok_or_else(|| {
         eprintln!("The second compare source is missing");
     })?;
+    options.diff.color = maybe_color.unwrap_or_else(is_color_auto);
+    if options.group_by_module && maybe_maintainers_path.is_some() {
+        eprintln!("Options '--group-by' and '--maintainers' cannot be used together");
+        return Err(());
+    }
+    if options.format != CompareFormat::Text {
+        if options.group_by_module {
+            eprintln!("Option '--group-by' can be used only with the text format");
+            return Err(());
+        }
+        if maybe_maintainers_path.is_some() {
+            eprintln!("Option '--maintainers' can be used only with the text format");
+            return Err(());
+        }
+    }
 
-    // Do the comparison.
-    debug!("Compare '{}' and '{}'", path, path2);
-
-    let syms = {
-        let _timing = Timing::new(do_timing, &format!("Reading symtypes from '{}'", path));
+    // Read the subsystems to group the changes by.
+    if let Some(maintainers_path) = maybe_maintainers_path {
+        let _timing = Timing::new(
+            do_timing,
+            &format!("Reading subsystems from '{}'", maintainers_path),
+        );
 
-        let mut syms = SymCorpus::new();
-        if let Err(err) = syms.load(&path, num_workers) {
-            eprintln!("Failed to read symtypes from '{}': {}", path, err);
-            return Err(());
+        let mut maintainers = Maintainers::new();
+        match maintainers.load(&maintainers_path) {
+            Ok(warnings) => {
+                for warning in &warnings {
+                    eprintln!("Warning: {}", warning);
+                }
+            }
+            Err(err) => {
+                eprintln!(
+                    "Failed to read subsystems from '{}': {}",
+                    maintainers_path, err
+                );
+                return Err(());
+            }
         }
-        syms
-    };
+        options.maintainers = Some(maintainers);
+    }
 
-    let syms2 = {
-        let _timing = Timing::new(do_timing, &format!("Reading symtypes from '{}'", path2));
+    // Scan the source tree once to locate definitions of the changed types.
+    if let Some(source_tree) = maybe_source_tree {
+        let _timing = Timing::new(
+            do_timing,
+            &format!("Scanning source tree '{}'", source_tree),
+        );
 
-        let mut syms2 = SymCorpus::new();
-        if let Err(err) = syms2.load(&path2, num_workers) {
-            eprintln!("Failed to read symtypes from '{}': {}", path2, err);
+        let mut source_index = SourceIndex::new();
+        if let Err(err) = source_index.scan(&source_tree, num_workers) {
+            eprintln!("Failed to scan source tree '{}': {}", source_tree, err);
             return Err(());
         }
-        syms2
+        options.source_index = Some(source_index);
+    }
+
+    // Do the comparison.
+    debug!("Compare '{}' and '{}'", path, path2);
+
+    let syms = read_symtypes(&path, num_workers, is_lenient, do_timing)?;
+
+    let syms2 = read_symtypes(&path2, num_workers, is_lenient, do_timing)?;
+
+    // Convert the corpuses to the genksyms dialect if they differ, so they can be compared.
+    let (syms, syms2) = if syms.dialect() != syms2.dialect() {
+        let _timing = Timing::new(do_timing, "Conversion to the genksyms dialect");
+
+        debug!(
+            "Converting '{}' in the {} dialect and '{}' in the {} dialect to the genksyms dialect",
+            path,
+            syms.dialect(),
+            path2,
+            syms2.dialect()
+        );
+        (syms.into_genksyms(), syms2.into_genksyms())
+    } else {
+        (syms, syms2)
     };
 
-    {
+    // Pipe the report through a pager, if the output is a terminal.
+    let mut maybe_pager = if use_pager { Pager::start() } else { None };
+    let result = {
         let _timing = Timing::new(do_timing, "Comparison");
 
-        if let Err(err) = syms.compare_with(&syms2, io::stdout(), num_workers) {
+        let writer: Box<dyn Write> = match &mut maybe_pager {
+            Some(pager) => Box::new(pager.input()),
+            None => Box::new(io::stdout()),
+        };
+        syms.compare_with_options(&syms2, &options, writer, num_workers)
+    };
+
+    // Wait for the user to quit the pager. A pager which fails, for instance because it cannot be
+    // found, might not have shown the report, which must not go unnoticed.
+    let mut pager_failed = false;
+    if let Some(pager) = maybe_pager {
+        let command = pager.command.clone();
+        match pager.finish() {
+            Ok(status) if status.success() => {}
+            Ok(status) => {
+                eprintln!("Pager '{}' failed: {}", command, status);
+                pager_failed = true;
+            }
+            Err(err) => {
+                eprintln!("Failed to wait for pager '{}': {}", command, err);
+                pager_failed = true;
+            }
+        }
+    }
+
+    // A broken pipe means that the reader, such as the pager, is not interested in the rest of the
+    // report, which is not an error if the reader itself succeeded.
+    match result {
+        Err(err) if !err.is_broken_pipe() => {
             eprintln!(
                 "Failed to compare symtypes from '{}' and '{}': {}",
                 path, path2, err
             );
             return Err(());
         }
+        _ => {}
+    }
+    if pager_failed {
+        return Err(());
+    }
+
+    Ok(())
+}
+
+/// Handles the `tree` command which shows the reference tree of an export.
+fn do_tree<I: IntoIterator<Item = String>>(do_timing: bool, args: I) -> Result<(), ()> {
+    // Parse specific command options.
+    let mut args = args.into_iter();
+    let mut num_workers = 1;
+    let mut is_lenient = false;
+    let mut past_dash_dash = false;
+    let mut maybe_path = None;
+    let mut maybe_export = None;
+
+    while let Some(arg) = args.next() {
+        if !past_dash_dash {
+            if let Some(value) = handle_jobs_option(&arg, &mut args)? {
+                num_workers = value;
+                continue;
+            }
+            if arg == "--lenient" {
+                is_lenient = true;
+                continue;
+            }
+            if arg == "-h" || arg == "--help" {
+                print_tree_usage();
+                return Ok(());
+            }
+            if arg == "--" {
+                past_dash_dash = true;
+                continue;
+            }
+            if arg.starts_with('-') || arg.starts_with("--") {
+                eprintln!("Unrecognized tree option '{}'", arg);
+                return Err(());
+            }
+        }
+
+        if maybe_path.is_none() {
+            maybe_path = Some(arg);
+            continue;
+        }
+        if maybe_export.is_none() {
+            maybe_export = Some(arg);
+            continue;
+        }
+        eprintln!("Excess tree argument '{}' specified", arg);
+        return Err(());
+    }
+
+    let path = maybe_path.ok_or_else(|| {
+        eprintln!("The tree source is missing");
+    })?;
+    let export = maybe_export.ok_or_else(|| {
+        eprintln!("The tree export is missing");
+    })?;
+
+    // Show the tree.
+    let syms = read_symtypes(&path, num_workers, is_lenient, do_timing)?;
+
+    {
+        let _timing = Timing::new(do_timing, &format!("Writing the tree of '{}'", export));
+
+        if let Err(err) = syms.write_tree_buffer(&export, io::stdout()) {
+            eprintln!("Failed to write the tree of '{}': {}", export, err);
+            return Err(());
+        }
+    }
+
+    Ok(())
+}
+
+/// Handles the `stats` command which shows statistics about a symtypes corpus.
+fn do_stats<I: IntoIterator<Item = String>>(do_timing: bool, args: I) -> Result<(), ()> {
+    // Parse specific command options.
+    let mut args = args.into_iter();
+    let mut format = StatsFormat::Text;
+    let mut num_workers = 1;
+    let mut is_lenient = false;
+    let mut past_dash_dash = false;
+    let mut maybe_path = None;
+
+    while let Some(arg) = args.next() {
+        if !past_dash_dash {
+            if let Some(value) = handle_value_option(&arg, &mut args, "-f", "--format")? {
+                format = match value.as_str() {
+                    "text" => StatsFormat::Text,
+                    "json" => StatsFormat::Json,
+                    _ => {
+                        eprintln!("Invalid value for '{}': unknown format '{}'", arg, value);
+                        return Err(());
+                    }
+                };
+                continue;
+            }
+            if let Some(value) = handle_jobs_option(&arg, &mut args)? {
+                num_workers = value;
+                continue;
+            }
+            if arg == "--lenient" {
+                is_lenient = true;
+                continue;
+            }
+            if arg == "-h" || arg == "--help" {
+                print_stats_usage();
+                return Ok(());
+            }
+            if arg == "--" {
+                past_dash_dash = true;
+                continue;
+            }
+            if arg.starts_with('-') || arg.starts_with("--") {
+                eprintln!("Unrecognized stats option '{}'", arg);
+                return Err(());
+            }
+        }
+
+        if maybe_path.is_none() {
+            maybe_path = Some(arg);
+            continue;
+        }
+        eprintln!("Excess stats argument '{}' specified", arg);
+        return Err(());
+    }
+
+    let path = maybe_path.ok_or_else(|| {
+        eprintln!("The stats source is missing");
+    })?;
+
+    // Show the statistics.
+    let syms = read_symtypes(&path, num_workers, is_lenient, do_timing)?;
+
+    {
+        let _timing = Timing::new(do_timing, "Statistics");
+
+        if let Err(err) = syms.write_stats_buffer(format, io::stdout(), num_workers) {
+            eprintln!("Failed to write statistics about '{}': {}", path, err);
+            return Err(());
+        }
+    }
+
+    Ok(())
+}
+
+/// Handles the `variants` command which shows all variants of a type.
+fn do_variants<I: IntoIterator<Item = String>>(do_timing: bool, args: I) -> Result<(), ()> {
+    // Parse specific command options.
+    let mut args = args.into_iter();
+    let mut num_workers = 1;
+    let mut is_lenient = false;
+    let mut maybe_color = None;
+    let mut past_dash_dash = false;
+    let mut maybe_path = None;
+    let mut maybe_type_name = None;
+
+    while let Some(arg) = args.next() {
+        if !past_dash_dash {
+            if let Some(value) = handle_color_option(&arg, &mut args)? {
+                maybe_color = Some(value);
+                continue;
+            }
+            if let Some(value) = handle_jobs_option(&arg, &mut args)? {
+                num_workers = value;
+                continue;
+            }
+            if arg == "--lenient" {
+                is_lenient = true;
+                continue;
+            }
+            if arg == "-h" || arg == "--help" {
+                print_variants_usage();
+                return Ok(());
+            }
+            if arg == "--" {
+                past_dash_dash = true;
+                continue;
+            }
+            if arg.starts_with('-') || arg.starts_with("--") {
+                eprintln!("Unrecognized variants option '{}'", arg);
+                return Err(());
+            }
+        }
+
+        if maybe_path.is_none() {
+            maybe_path = Some(arg);
+            continue;
+        }
+        if maybe_type_name.is_none() {
+            maybe_type_name = Some(arg);
+            continue;
+        }
+        eprintln!("Excess variants argument '{}' specified", arg);
+        return Err(());
+    }
+
+    let path = maybe_path.ok_or_else(|| {
+        eprintln!("The variants source is missing");
+    })?;
+    let type_name = maybe_type_name.ok_or_else(|| {
+        eprintln!("The variants type is missing");
+    })?;
+    let diff_options = DiffOptions {
+        color: maybe_color.unwrap_or_else(is_color_auto),
+        ..Default::default()
+    };
+
+    // Show the variants.
+    let syms = read_symtypes(&path, num_workers, is_lenient, do_timing)?;
+
+    {
+        let _timing = Timing::new(
+            do_timing,
+            &format!("Writing the variants of '{}'", type_name),
+        );
+
+        if let Err(err) =
+            syms.write_variants_buffer_with_options(&type_name, &diff_options, io::stdout())
+        {
+            eprintln!("Failed to write the variants of '{}': {}", type_name, err);
+            return Err(());
+        }
+    }
+
+    Ok(())
+}
+
+/// Handles the `lint` command which checks a symtypes corpus for conflicting types.
+fn do_lint<I: IntoIterator<Item = String>>(do_timing: bool, args: I) -> Result<(), ()> {
+    // Parse specific command options.
+    let mut args = args.into_iter();
+    let mut num_workers = 1;
+    let mut is_lenient = false;
+    let mut strict = false;
+    let mut maybe_color = None;
+    let mut past_dash_dash = false;
+    let mut maybe_path = None;
+
+    while let Some(arg) = args.next() {
+        if !past_dash_dash {
+            if let Some(value) = handle_color_option(&arg, &mut args)? {
+                maybe_color = Some(value);
+                continue;
+            }
+            if let Some(value) = handle_jobs_option(&arg, &mut args)? {
+                num_workers = value;
+                continue;
+            }
+            if arg == "--lenient" {
+                is_lenient = true;
+                continue;
+            }
+            if arg == "--strict" {
+                strict = true;
+                continue;
+            }
+            if arg == "-h" || arg == "--help" {
+                print_lint_usage();
+                return Ok(());
+            }
+            if arg == "--" {
+                past_dash_dash = true;
+                continue;
+            }
+            if arg.starts_with('-') || arg.starts_with("--") {
+                eprintln!("Unrecognized lint option '{}'", arg);
+                return Err(());
+            }
+        }
+
+        if maybe_path.is_none() {
+            maybe_path = Some(arg);
+            continue;
+        }
+        eprintln!("Excess lint argument '{}' specified", arg);
+        return Err(());
+    }
+
+    let path = maybe_path.ok_or_else(|| {
+        eprintln!("The lint source is missing");
+    })?;
+    let diff_options = DiffOptions {
+        color: maybe_color.unwrap_or_else(is_color_auto),
+        ..Default::default()
+    };
+
+    // Do the checks.
+    let syms = read_symtypes(&path, num_workers, is_lenient, do_timing)?;
+
+    let num_problems = {
+        let _timing = Timing::new(do_timing, "Checking conflicting types");
+
+        match syms.write_odr_violations_buffer_with_options(&diff_options, io::stdout()) {
+            Ok(num_problems) => num_problems,
+            Err(err) => {
+                eprintln!("Failed to check symtypes from '{}': {}", path, err);
+                return Err(());
+            }
+        }
+    };
+
+    if strict && num_problems > 0 {
+        eprintln!(
+            "Found '{}' types with conflicting definitions in '{}'",
+            num_problems, path
+        );
+        return Err(());
+    }
+
+    Ok(())
+}
+
+/// Handles the `check-exports` command which checks exports of a symtypes corpus against kernel
+/// binaries.
+fn do_check_exports<I: IntoIterator<Item = String>>(do_timing: bool, args: I) -> Result<(), ()> {
+    // Parse specific command options.
+    let mut args = args.into_iter();
+    let mut num_workers = 1;
+    let mut is_lenient = false;
+    let mut strict = false;
+    let mut past_dash_dash = false;
+    let mut maybe_path = None;
+    let mut maybe_binary_path = None;
+
+    while let Some(arg) = args.next() {
+        if !past_dash_dash {
+            if let Some(value) = handle_jobs_option(&arg, &mut args)? {
+                num_workers = value;
+                continue;
+            }
+            if arg == "--lenient" {
+                is_lenient = true;
+                continue;
+            }
+            if arg == "--strict" {
+                strict = true;
+                continue;
+            }
+            if arg == "-h" || arg == "--help" {
+                print_check_exports_usage();
+                return Ok(());
+            }
+            if arg == "--" {
+                past_dash_dash = true;
+                continue;
+            }
+            if arg.starts_with('-') || arg.starts_with("--") {
+                eprintln!("Unrecognized check-exports option '{}'", arg);
+                return Err(());
+            }
+        }
+
+        if maybe_path.is_none() {
+            maybe_path = Some(arg);
+            continue;
+        }
+        if maybe_binary_path.is_none() {
+            maybe_binary_path = Some(arg);
+            continue;
+        }
+        eprintln!("Excess check-exports argument '{}' specified", arg);
+        return Err(());
+    }
+
+    let path = maybe_path.ok_or_else(|| {
+        eprintln!("The check-exports source is missing");
+    })?;
+    let binary_path = maybe_binary_path.ok_or_else(|| {
+        eprintln!("The check-exports binary path is missing");
+    })?;
+
+    // Read the exports from both sources.
+    let syms = read_symtypes(&path, num_workers, is_lenient, do_timing)?;
+
+    let binary_exports = {
+        let _timing = Timing::new(
+            do_timing,
+            &format!("Reading exports from '{}'", binary_path),
+        );
+
+        let mut binary_exports = BinaryExports::new();
+        if let Err(err) = binary_exports.load(&binary_path, num_workers) {
+            eprintln!("Failed to read exports from '{}': {}", binary_path, err);
+            return Err(());
+        }
+        binary_exports
+    };
+
+    // Do the check.
+    let num_problems = {
+        let _timing = Timing::new(do_timing, "Checking exports");
+
+        match syms.write_export_mismatches_buffer(&binary_exports, io::stdout()) {
+            Ok(num_problems) => num_problems,
+            Err(err) => {
+                eprintln!("Failed to check exports of '{}': {}", path, err);
+                return Err(());
+            }
+        }
+    };
+
+    if strict && num_problems > 0 {
+        eprintln!(
+            "Found '{}' mismatched exports between '{}' and '{}'",
+            num_problems, path, binary_path
+        );
+        return Err(());
+    }
+
+    Ok(())
+}
+
+/// Handles the `check-module` command which checks symbols imported by a module against a reference
+/// symtypes corpus.
+fn do_check_module<I: IntoIterator<Item = String>>(do_timing: bool, args: I) -> Result<(), ()> {
+    // Parse specific command options.
+    let mut args = args.into_iter();
+    let mut num_workers = 1;
+    let mut is_lenient = false;
+    let mut strict = false;
+    let mut past_dash_dash = false;
+    let mut maybe_ref_path = None;
+    let mut maybe_new_path = None;
+    let mut maybe_module_path = None;
+
+    while let Some(arg) = args.next() {
+        if !past_dash_dash {
+            if let Some(value) = handle_jobs_option(&arg, &mut args)? {
+                num_workers = value;
+                continue;
+            }
+            if arg == "--lenient" {
+                is_lenient = true;
+                continue;
+            }
+            if arg == "--strict" {
+                strict = true;
+                continue;
+            }
+            if arg == "-h" || arg == "--help" {
+                print_check_module_usage();
+                return Ok(());
+            }
+            if arg == "--" {
+                past_dash_dash = true;
+                continue;
+            }
+            if arg.starts_with('-') || arg.starts_with("--") {
+                eprintln!("Unrecognized check-module option '{}'", arg);
+                return Err(());
+            }
+        }
+
+        if maybe_ref_path.is_none() {
+            maybe_ref_path = Some(arg);
+            continue;
+        }
+        if maybe_new_path.is_none() {
+            maybe_new_path = Some(arg);
+            continue;
+        }
+        if maybe_module_path.is_none() {
+            maybe_module_path = Some(arg);
+            continue;
+        }
+        eprintln!("Excess check-module argument '{}' specified", arg);
+        return Err(());
+    }
+
+    let ref_path = maybe_ref_path.ok_or_else(|| {
+        eprintln!("The check-module reference source is missing");
+    })?;
+    let new_path = maybe_new_path.ok_or_else(|| {
+        eprintln!("The check-module new source is missing");
+    })?;
+    let module_path = maybe_module_path.ok_or_else(|| {
+        eprintln!("The check-module module is missing");
+    })?;
+
+    // Read the imports of the module and both corpuses.
+    let imports = {
+        let _timing = Timing::new(
+            do_timing,
+            &format!("Reading imports from '{}'", module_path),
+        );
+
+        match read_module_imports(&module_path) {
+            Ok(imports) => imports,
+            Err(err) => {
+                eprintln!("Failed to read imports from '{}': {}", module_path, err);
+                return Err(());
+            }
+        }
+    };
+
+    let syms = read_symtypes(&ref_path, num_workers, is_lenient, do_timing)?;
+
+    let syms2 = read_symtypes(&new_path, num_workers, is_lenient, do_timing)?;
+
+    // Convert the corpuses to the genksyms dialect if they differ, so they can be compared.
+    let (syms, syms2) = if syms.dialect() != syms2.dialect() {
+        let _timing = Timing::new(do_timing, "Conversion to the genksyms dialect");
+
+        (syms.into_genksyms(), syms2.into_genksyms())
+    } else {
+        (syms, syms2)
+    };
+
+    // Do the check.
+    let num_problems = {
+        let _timing = Timing::new(do_timing, "Checking the module");
+
+        match syms.check_module_with(&syms2, &imports, io::stdout(), num_workers) {
+            Ok(num_problems) => num_problems,
+            Err(err) => {
+                eprintln!(
+                    "Failed to check module '{}' against '{}' and '{}': {}",
+                    module_path, ref_path, new_path, err
+                );
+                return Err(());
+            }
+        }
+    };
+
+    if strict && num_problems > 0 {
+        eprintln!(
+            "Found '{}' removed or changed imports of '{}'",
+            num_problems, module_path
+        );
+        return Err(());
     }
 
     Ok(())
@@ -367,6 +1274,12 @@ fn main() {
     let result = match command.as_str() {
         "consolidate" => do_consolidate(do_timing, args),
         "compare" => do_compare(do_timing, args),
+        "tree" => do_tree(do_timing, args),
+        "stats" => do_stats(do_timing, args),
+        "variants" => do_variants(do_timing, args),
+        "lint" => do_lint(do_timing, args),
+        "check-exports" => do_check_exports(do_timing, args),
+        "check-module" => do_check_module(do_timing, args),
         _ => {
             eprintln!("Unrecognized command '{}'", command);
             Err(())
@@ -0,0 +1,203 @@
+// Copyright (C) 2025 SUSE LLC <petr.pavlu@suse.com>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use super::*;
+
+/// A helper to construct BTF data in tests.
+pub(crate) struct BtfBuilder {
+    str_start: usize,
+    strings: Vec<u8>,
+    next_id: u32,
+    types: Vec<u8>,
+}
+
+impl BtfBuilder {
+    /// Creates a new builder of base BTF.
+    pub(crate) fn new() -> Self {
+        Self {
+            str_start: 0,
+            strings: vec![0],
+            next_id: 1,
+            types: Vec::new(),
+        }
+    }
+
+    /// Creates a new builder of split BTF, extending the specified base.
+    pub(crate) fn new_split(base: &Btf) -> Self {
+        Self {
+            str_start: base.str_end(),
+            strings: vec![0],
+            next_id: base.end_id(),
+            types: Vec::new(),
+        }
+    }
+
+    /// Adds a string and returns its offset.
+    fn string(&mut self, s: &str) -> u32 {
+        if s.is_empty() {
+            return self.str_start as u32;
+        }
+        let offset = self.str_start + self.strings.len();
+        self.strings.extend(s.as_bytes());
+        self.strings.push(0);
+        offset as u32
+    }
+
+    /// Adds a type with the specified common fields followed by extra data and returns its ID.
+    fn add(
+        &mut self,
+        name: &str,
+        kind: u32,
+        kind_flag: bool,
+        vlen: usize,
+        size_or_type: u32,
+        extra: &[u32],
+    ) -> u32 {
+        let name_off = self.string(name);
+        let info = (kind_flag as u32) << 31 | kind << 24 | vlen as u32;
+        for value in [name_off, info, size_or_type].iter().chain(extra) {
+            self.types.extend(value.to_le_bytes());
+        }
+        self.next_id += 1;
+        self.next_id - 1
+    }
+
+    pub(crate) fn int(&mut self, name: &str, size: u32) -> u32 {
+        self.add(name, BTF_KIND_INT, false, 0, size, &[size * 8])
+    }
+
+    pub(crate) fn float(&mut self, name: &str, size: u32) -> u32 {
+        self.add(name, BTF_KIND_FLOAT, false, 0, size, &[])
+    }
+
+    pub(crate) fn pointer(&mut self, type_id: u32) -> u32 {
+        self.add("", BTF_KIND_PTR, false, 0, type_id, &[])
+    }
+
+    pub(crate) fn array(&mut self, type_id: u32, num_elems: u32) -> u32 {
+        self.add("", BTF_KIND_ARRAY, false, 0, 0, &[type_id, 0, num_elems])
+    }
+
+    /// Adds a structure or union. Each member is given by its name, type and bit-field size, with
+    /// 0 meaning a regular member.
+    fn aggregate(&mut self, kind: u32, name: &str, members: &[(&str, u32, u32)]) -> u32 {
+        let kind_flag = members.iter().any(|&(_, _, bits)| bits != 0);
+        let mut extra = Vec::new();
+        for &(member_name, type_id, bits) in members {
+            extra.extend([self.string(member_name), type_id, bits << 24]);
+        }
+        self.add(name, kind, kind_flag, members.len(), 0, &extra)
+    }
+
+    pub(crate) fn structure(&mut self, name: &str, members: &[(&str, u32, u32)]) -> u32 {
+        self.aggregate(BTF_KIND_STRUCT, name, members)
+    }
+
+    pub(crate) fn union(&mut self, name: &str, members: &[(&str, u32, u32)]) -> u32 {
+        self.aggregate(BTF_KIND_UNION, name, members)
+    }
+
+    pub(crate) fn enumeration(&mut self, name: &str, values: &[(&str, i32)]) -> u32 {
+        let kind_flag = values.iter().any(|&(_, value)| value < 0);
+        let mut extra = Vec::new();
+        for &(value_name, value) in values {
+            extra.extend([self.string(value_name), value as u32]);
+        }
+        self.add(name, BTF_KIND_ENUM, kind_flag, values.len(), 4, &extra)
+    }
+
+    pub(crate) fn enumeration64(&mut self, name: &str, values: &[(&str, u64)]) -> u32 {
+        let mut extra = Vec::new();
+        for &(value_name, value) in values {
+            extra.extend([self.string(value_name), value as u32, (value >> 32) as u32]);
+        }
+        self.add(name, BTF_KIND_ENUM64, false, values.len(), 8, &extra)
+    }
+
+    pub(crate) fn fwd(&mut self, name: &str, is_union: bool) -> u32 {
+        self.add(name, BTF_KIND_FWD, is_union, 0, 0, &[])
+    }
+
+    pub(crate) fn typedef(&mut self, name: &str, type_id: u32) -> u32 {
+        self.add(name, BTF_KIND_TYPEDEF, false, 0, type_id, &[])
+    }
+
+    pub(crate) fn volatile(&mut self, type_id: u32) -> u32 {
+        self.add("", BTF_KIND_VOLATILE, false, 0, type_id, &[])
+    }
+
+    pub(crate) fn constant(&mut self, type_id: u32) -> u32 {
+        self.add("", BTF_KIND_CONST, false, 0, type_id, &[])
+    }
+
+    pub(crate) fn restrict(&mut self, type_id: u32) -> u32 {
+        self.add("", BTF_KIND_RESTRICT, false, 0, type_id, &[])
+    }
+
+    pub(crate) fn type_tag(&mut self, name: &str, type_id: u32) -> u32 {
+        self.add(name, BTF_KIND_TYPE_TAG, false, 0, type_id, &[])
+    }
+
+    pub(crate) fn func(&mut self, name: &str, proto_id: u32, is_global: bool) -> u32 {
+        self.add(
+            name,
+            BTF_KIND_FUNC,
+            false,
+            is_global as usize,
+            proto_id,
+            &[],
+        )
+    }
+
+    /// Adds a function prototype. A last parameter with no name and the type 0 makes the function
+    /// variadic.
+    pub(crate) fn func_proto(&mut self, return_type_id: u32, params: &[(&str, u32)]) -> u32 {
+        let mut extra = Vec::new();
+        for &(param_name, type_id) in params {
+            extra.extend([self.string(param_name), type_id]);
+        }
+        self.add(
+            "",
+            BTF_KIND_FUNC_PROTO,
+            false,
+            params.len(),
+            return_type_id,
+            &extra,
+        )
+    }
+
+    pub(crate) fn var(&mut self, name: &str, type_id: u32, is_global: bool) -> u32 {
+        self.add(name, BTF_KIND_VAR, false, 0, type_id, &[is_global as u32])
+    }
+
+    pub(crate) fn decl_tag(&mut self, name: &str, type_id: u32) -> u32 {
+        self.add(name, BTF_KIND_DECL_TAG, false, 0, type_id, &[u32::MAX])
+    }
+
+    pub(crate) fn datasec(&mut self, name: &str, vars: &[u32]) -> u32 {
+        let mut extra = Vec::new();
+        for &var_id in vars {
+            extra.extend([var_id, 0, 0]);
+        }
+        self.add(name, BTF_KIND_DATASEC, false, vars.len(), 0, &extra)
+    }
+
+    /// Returns the BTF data in the little-endian byte order.
+    pub(crate) fn build(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend(BTF_MAGIC.to_le_bytes());
+        data.extend([1, 0]);
+        for value in [
+            24,
+            0,
+            self.types.len() as u32,
+            self.types.len() as u32,
+            self.strings.len() as u32,
+        ] {
+            data.extend(u32::to_le_bytes(value));
+        }
+        data.extend(&self.types);
+        data.extend(&self.strings);
+        data
+    }
+}
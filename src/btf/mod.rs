@@ -0,0 +1,448 @@
+// Copyright (C) 2025 SUSE LLC <petr.pavlu@suse.com>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use crate::elf::{read_c_string, DataReader};
+use std::collections::HashMap;
+use std::path::Path;
+
+#[cfg(test)]
+pub(crate) mod builder;
+#[cfg(test)]
+mod tests;
+
+/// The magic number at the start of BTF data.
+const BTF_MAGIC: u16 = 0xeb9f;
+
+// Kinds of BTF types.
+const BTF_KIND_INT: u32 = 1;
+const BTF_KIND_PTR: u32 = 2;
+const BTF_KIND_ARRAY: u32 = 3;
+const BTF_KIND_STRUCT: u32 = 4;
+const BTF_KIND_UNION: u32 = 5;
+const BTF_KIND_ENUM: u32 = 6;
+const BTF_KIND_FWD: u32 = 7;
+const BTF_KIND_TYPEDEF: u32 = 8;
+const BTF_KIND_VOLATILE: u32 = 9;
+const BTF_KIND_CONST: u32 = 10;
+const BTF_KIND_RESTRICT: u32 = 11;
+const BTF_KIND_FUNC: u32 = 12;
+const BTF_KIND_FUNC_PROTO: u32 = 13;
+const BTF_KIND_VAR: u32 = 14;
+const BTF_KIND_DATASEC: u32 = 15;
+const BTF_KIND_FLOAT: u32 = 16;
+const BTF_KIND_DECL_TAG: u32 = 17;
+const BTF_KIND_TYPE_TAG: u32 = 18;
+const BTF_KIND_ENUM64: u32 = 19;
+
+/// A member of a BTF structure or union.
+#[derive(Debug, PartialEq)]
+pub struct BtfMember {
+    pub name: Option<String>,
+    pub type_id: u32,
+    /// The size of the member in bits if it is a bit-field.
+    pub bitfield_size: Option<u32>,
+}
+
+/// A parameter of a BTF function prototype.
+#[derive(Debug, PartialEq)]
+pub struct BtfParam {
+    pub name: Option<String>,
+    /// The type of the parameter. The type ID 0 (void) as the last parameter indicates a variadic
+    /// function.
+    pub type_id: u32,
+}
+
+/// A type described by BTF.
+///
+/// Types reference each other by their IDs. The type ID 0 is reserved for `void`.
+#[derive(Debug, PartialEq)]
+pub enum BtfType {
+    Void,
+    Int {
+        name: String,
+    },
+    Float {
+        name: String,
+    },
+    Pointer {
+        type_id: u32,
+    },
+    Array {
+        type_id: u32,
+        num_elems: u32,
+    },
+    Struct {
+        name: Option<String>,
+        members: Vec<BtfMember>,
+    },
+    Union {
+        name: Option<String>,
+        members: Vec<BtfMember>,
+    },
+    /// An enumeration, described either by `BTF_KIND_ENUM` or `BTF_KIND_ENUM64`.
+    Enum {
+        name: Option<String>,
+        values: Vec<(String, i128)>,
+    },
+    /// A forward declaration of a structure or union.
+    Fwd {
+        name: String,
+        is_union: bool,
+    },
+    Typedef {
+        name: String,
+        type_id: u32,
+    },
+    Volatile {
+        type_id: u32,
+    },
+    Const {
+        type_id: u32,
+    },
+    Restrict {
+        type_id: u32,
+    },
+    /// A function, with `type_id` referencing its prototype.
+    Func {
+        name: String,
+        type_id: u32,
+        is_global: bool,
+    },
+    FuncProto {
+        return_type_id: u32,
+        params: Vec<BtfParam>,
+    },
+    Var {
+        name: String,
+        type_id: u32,
+        is_global: bool,
+    },
+    DataSec {
+        name: String,
+    },
+    DeclTag {
+        type_id: u32,
+    },
+    TypeTag {
+        type_id: u32,
+    },
+}
+
+/// BTF data of vmlinux or a kernel module.
+///
+/// BTF of a kernel module is split: it extends BTF of vmlinux, referred to as its base. Types of
+/// the split BTF are numbered after all base types and can reference them.
+pub struct Btf<'a> {
+    base: Option<&'a Btf<'a>>,
+    /// The ID of the first type in `types`.
+    start_id: u32,
+    types: Vec<BtfType>,
+    /// The offset of the first string in `strings`.
+    str_start: usize,
+    strings: Vec<u8>,
+    /// Complete named structures, unions and enumerations, indexed by their kind prefix (`s`, `u`
+    /// or `e`) and name.
+    complete_types: HashMap<(char, String), u32>,
+}
+
+impl<'a> Btf<'a> {
+    /// Parses BTF data, such as the content of the `.BTF` ELF section.
+    ///
+    /// The `path` indicates the origin of the data. The `base` should be specified when parsing
+    /// split BTF of a kernel module.
+    pub fn parse<P: AsRef<Path>>(
+        path: P,
+        data: &[u8],
+        base: Option<&'a Btf<'a>>,
+    ) -> Result<Self, crate::Error> {
+        let path = path.as_ref();
+        let parse_err = |desc: &str| crate::Error::new_parse(path, None, desc);
+
+        // Determine the byte order from the magic number.
+        let is_little_endian = match data.get(0..2) {
+            Some(magic) if magic == BTF_MAGIC.to_le_bytes() => true,
+            Some(magic) if magic == BTF_MAGIC.to_be_bytes() => false,
+            _ => return Err(parse_err("Invalid BTF magic number")),
+        };
+        let reader = DataReader::new(data, is_little_endian);
+
+        // Read the header.
+        let truncated_err = || parse_err("Truncated BTF header");
+        let hdr_len = reader.u32(4).ok_or_else(truncated_err)? as usize;
+        let type_off = reader.u32(8).ok_or_else(truncated_err)? as usize;
+        let type_len = reader.u32(12).ok_or_else(truncated_err)? as usize;
+        let str_off = reader.u32(16).ok_or_else(truncated_err)? as usize;
+        let str_len = reader.u32(20).ok_or_else(truncated_err)? as usize;
+
+        let section = |off: usize, len: usize, desc: &str| {
+            hdr_len
+                .checked_add(off)
+                .and_then(|start| Some(start..start.checked_add(len)?))
+                .and_then(|range| data.get(range))
+                .ok_or_else(|| parse_err(&format!("BTF {} section is out of bounds", desc)))
+        };
+        let type_data = section(type_off, type_len, "type")?;
+        let strings = section(str_off, str_len, "string")?;
+
+        // Resolve names. Split BTF continues the string offsets of its base.
+        let str_start = base.map_or(0, |base| base.str_end());
+        let name = |name_off: u32| -> Result<Option<String>, crate::Error> {
+            let name_off = name_off as usize;
+            let name = if name_off < str_start {
+                base.and_then(|base| base.string(name_off))
+            } else {
+                read_c_string(strings, name_off - str_start)
+            };
+            match name {
+                Some("") => Ok(None),
+                Some(name) => Ok(Some(name.to_string())),
+                None => Err(parse_err(&format!(
+                    "Invalid BTF string offset {}",
+                    name_off
+                ))),
+            }
+        };
+        let required_name = |name_off: u32| -> Result<String, crate::Error> {
+            name(name_off)?.ok_or_else(|| parse_err("Missing a name of a BTF type"))
+        };
+
+        // Read all types.
+        let type_reader = DataReader::new(type_data, is_little_endian);
+        let truncated_err = || parse_err("Truncated BTF type data");
+        let u32_at = |offset: usize| type_reader.u32(offset).ok_or_else(truncated_err);
+
+        let mut types = Vec::new();
+        let mut pos = 0;
+        while pos < type_data.len() {
+            let name_off = u32_at(pos)?;
+            let info = u32_at(pos + 4)?;
+            let size_or_type = u32_at(pos + 8)?;
+            pos += 12;
+
+            let kind = (info >> 24) & 0x1f;
+            let vlen = (info & 0xffff) as usize;
+            let kind_flag = info >> 31 != 0;
+
+            let ty = match kind {
+                BTF_KIND_INT => {
+                    pos += 4;
+                    BtfType::Int {
+                        name: required_name(name_off)?,
+                    }
+                }
+                BTF_KIND_FLOAT => BtfType::Float {
+                    name: required_name(name_off)?,
+                },
+                BTF_KIND_PTR => BtfType::Pointer {
+                    type_id: size_or_type,
+                },
+                BTF_KIND_ARRAY => {
+                    let ty = BtfType::Array {
+                        type_id: u32_at(pos)?,
+                        num_elems: u32_at(pos + 8)?,
+                    };
+                    pos += 12;
+                    ty
+                }
+                BTF_KIND_STRUCT | BTF_KIND_UNION => {
+                    let mut members = Vec::new();
+                    for _ in 0..vlen {
+                        let offset = u32_at(pos + 8)?;
+                        let bitfield_size = offset >> 24;
+                        members.push(BtfMember {
+                            name: name(u32_at(pos)?)?,
+                            type_id: u32_at(pos + 4)?,
+                            bitfield_size: if kind_flag && bitfield_size != 0 {
+                                Some(bitfield_size)
+                            } else {
+                                None
+                            },
+                        });
+                        pos += 12;
+                    }
+                    if kind == BTF_KIND_STRUCT {
+                        BtfType::Struct {
+                            name: name(name_off)?,
+                            members,
+                        }
+                    } else {
+                        BtfType::Union {
+                            name: name(name_off)?,
+                            members,
+                        }
+                    }
+                }
+                BTF_KIND_ENUM | BTF_KIND_ENUM64 => {
+                    // The kind flag indicates a signed enumeration.
+                    let mut values = Vec::new();
+                    for _ in 0..vlen {
+                        let value_name = required_name(u32_at(pos)?)?;
+                        let value = if kind == BTF_KIND_ENUM {
+                            let value = u32_at(pos + 4)?;
+                            pos += 8;
+                            if kind_flag {
+                                value as i32 as i128
+                            } else {
+                                value as i128
+                            }
+                        } else {
+                            let value = (u32_at(pos + 8)? as u64) << 32 | u32_at(pos + 4)? as u64;
+                            pos += 12;
+                            if kind_flag {
+                                value as i64 as i128
+                            } else {
+                                value as i128
+                            }
+                        };
+                        values.push((value_name, value));
+                    }
+                    BtfType::Enum {
+                        name: name(name_off)?,
+                        values,
+                    }
+                }
+                BTF_KIND_FWD => BtfType::Fwd {
+                    name: required_name(name_off)?,
+                    is_union: kind_flag,
+                },
+                BTF_KIND_TYPEDEF => BtfType::Typedef {
+                    name: required_name(name_off)?,
+                    type_id: size_or_type,
+                },
+                BTF_KIND_VOLATILE => BtfType::Volatile {
+                    type_id: size_or_type,
+                },
+                BTF_KIND_CONST => BtfType::Const {
+                    type_id: size_or_type,
+                },
+                BTF_KIND_RESTRICT => BtfType::Restrict {
+                    type_id: size_or_type,
+                },
+                BTF_KIND_FUNC => BtfType::Func {
+                    name: required_name(name_off)?,
+                    type_id: size_or_type,
+                    // The linkage is stored in vlen, 0 is static.
+                    is_global: vlen != 0,
+                },
+                BTF_KIND_FUNC_PROTO => {
+                    let mut params = Vec::new();
+                    for _ in 0..vlen {
+                        params.push(BtfParam {
+                            name: name(u32_at(pos)?)?,
+                            type_id: u32_at(pos + 4)?,
+                        });
+                        pos += 8;
+                    }
+                    BtfType::FuncProto {
+                        return_type_id: size_or_type,
+                        params,
+                    }
+                }
+                BTF_KIND_VAR => {
+                    // The linkage is stored after the type, 0 is static.
+                    let linkage = u32_at(pos)?;
+                    pos += 4;
+                    BtfType::Var {
+                        name: required_name(name_off)?,
+                        type_id: size_or_type,
+                        is_global: linkage != 0,
+                    }
+                }
+                BTF_KIND_DATASEC => {
+                    pos += 12 * vlen;
+                    BtfType::DataSec {
+                        name: required_name(name_off)?,
+                    }
+                }
+                BTF_KIND_DECL_TAG => {
+                    pos += 4;
+                    BtfType::DeclTag {
+                        type_id: size_or_type,
+                    }
+                }
+                BTF_KIND_TYPE_TAG => BtfType::TypeTag {
+                    type_id: size_or_type,
+                },
+                _ => return Err(parse_err(&format!("Unknown BTF type kind {}", kind))),
+            };
+            types.push(ty);
+        }
+        if pos != type_data.len() {
+            return Err(truncated_err());
+        }
+
+        // Index complete named types to allow resolving forward declarations.
+        let start_id = base.map_or(1, |base| base.end_id());
+        let mut complete_types = HashMap::new();
+        for (i, ty) in types.iter().enumerate() {
+            let key = match ty {
+                BtfType::Struct {
+                    name: Some(name), ..
+                } => ('s', name.clone()),
+                BtfType::Union {
+                    name: Some(name), ..
+                } => ('u', name.clone()),
+                BtfType::Enum {
+                    name: Some(name), ..
+                } => ('e', name.clone()),
+                _ => continue,
+            };
+            complete_types.entry(key).or_insert(start_id + i as u32);
+        }
+
+        Ok(Self {
+            base,
+            start_id,
+            types,
+            complete_types,
+            strings: strings.to_vec(),
+            str_start,
+        })
+    }
+
+    /// Returns the ID one past the last type, which is also the ID of the first type of any split
+    /// BTF based on this data.
+    pub fn end_id(&self) -> u32 {
+        self.start_id + self.types.len() as u32
+    }
+
+    /// Returns the offset one past the last string, where strings of split BTF start.
+    fn str_end(&self) -> usize {
+        self.str_start + self.strings.len()
+    }
+
+    /// Returns the string at the specified offset.
+    fn string(&self, offset: usize) -> Option<&str> {
+        if offset < self.str_start {
+            self.base?.string(offset)
+        } else {
+            read_c_string(&self.strings, offset - self.str_start)
+        }
+    }
+
+    /// Returns the type with the specified ID, looking also in the base BTF.
+    pub fn get(&self, type_id: u32) -> Option<&BtfType> {
+        if type_id == 0 {
+            return Some(&BtfType::Void);
+        }
+        if type_id < self.start_id {
+            return self.base?.get(type_id);
+        }
+        self.types.get((type_id - self.start_id) as usize)
+    }
+
+    /// Returns an iterator over the types of this BTF data, excluding the base, together with
+    /// their IDs.
+    pub fn types(&self) -> impl Iterator<Item = (u32, &BtfType)> {
+        (self.start_id..).zip(self.types.iter())
+    }
+
+    /// Looks up the ID of a complete structure, union or enumeration with the specified name,
+    /// looking also in the base BTF. The `kind` is the type prefix `s`, `u` or `e`.
+    pub fn find_complete(&self, kind: char, name: &str) -> Option<u32> {
+        match self.complete_types.get(&(kind, name.to_string())) {
+            Some(&type_id) => Some(type_id),
+            None => self.base?.find_complete(kind, name),
+        }
+    }
+}
@@ -0,0 +1,256 @@
+// Copyright (C) 2025 SUSE LLC <petr.pavlu@suse.com>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use super::builder::BtfBuilder;
+use super::*;
+
+#[test]
+fn read_basic_types() {
+    // Check reading of basic BTF types.
+    let mut builder = BtfBuilder::new();
+    let int_id = builder.int("int", 4);
+    let ptr_id = builder.pointer(int_id);
+    let const_id = builder.constant(ptr_id);
+    let array_id = builder.array(int_id, 4);
+    let typedef_id = builder.typedef("int_t", int_id);
+    let float_id = builder.float("double", 8);
+    let volatile_id = builder.volatile(float_id);
+    let restrict_id = builder.restrict(ptr_id);
+    let btf = Btf::parse("vmlinux", &builder.build(), None).unwrap();
+    assert_eq!(btf.get(0), Some(&BtfType::Void));
+    assert_eq!(
+        btf.get(int_id),
+        Some(&BtfType::Int {
+            name: "int".to_string()
+        })
+    );
+    assert_eq!(btf.get(ptr_id), Some(&BtfType::Pointer { type_id: int_id }));
+    assert_eq!(btf.get(const_id), Some(&BtfType::Const { type_id: ptr_id }));
+    assert_eq!(
+        btf.get(array_id),
+        Some(&BtfType::Array {
+            type_id: int_id,
+            num_elems: 4
+        })
+    );
+    assert_eq!(
+        btf.get(typedef_id),
+        Some(&BtfType::Typedef {
+            name: "int_t".to_string(),
+            type_id: int_id
+        })
+    );
+    assert_eq!(
+        btf.get(float_id),
+        Some(&BtfType::Float {
+            name: "double".to_string()
+        })
+    );
+    assert_eq!(
+        btf.get(volatile_id),
+        Some(&BtfType::Volatile { type_id: float_id })
+    );
+    assert_eq!(
+        btf.get(restrict_id),
+        Some(&BtfType::Restrict { type_id: ptr_id })
+    );
+    assert_eq!(btf.get(restrict_id + 1), None);
+    assert_eq!(btf.end_id(), restrict_id + 1);
+}
+
+#[test]
+fn read_aggregates() {
+    // Check reading of structures, unions and enumerations.
+    let mut builder = BtfBuilder::new();
+    let int_id = builder.int("int", 4);
+    let struct_id = builder.structure("foo", &[("a", int_id, 0), ("b", int_id, 3)]);
+    let union_id = builder.union("", &[("c", int_id, 0)]);
+    let enum_id = builder.enumeration("bar", &[("BAR_A", 0), ("BAR_B", -1)]);
+    let enum64_id = builder.enumeration64("baz", &[("BAZ_A", 1 << 40)]);
+    let btf = Btf::parse("vmlinux", &builder.build(), None).unwrap();
+    assert_eq!(
+        btf.get(struct_id),
+        Some(&BtfType::Struct {
+            name: Some("foo".to_string()),
+            members: vec![
+                BtfMember {
+                    name: Some("a".to_string()),
+                    type_id: int_id,
+                    bitfield_size: None
+                },
+                BtfMember {
+                    name: Some("b".to_string()),
+                    type_id: int_id,
+                    bitfield_size: Some(3)
+                },
+            ]
+        })
+    );
+    assert_eq!(
+        btf.get(union_id),
+        Some(&BtfType::Union {
+            name: None,
+            members: vec![BtfMember {
+                name: Some("c".to_string()),
+                type_id: int_id,
+                bitfield_size: None
+            }]
+        })
+    );
+    assert_eq!(
+        btf.get(enum_id),
+        Some(&BtfType::Enum {
+            name: Some("bar".to_string()),
+            values: vec![("BAR_A".to_string(), 0), ("BAR_B".to_string(), -1)]
+        })
+    );
+    assert_eq!(
+        btf.get(enum64_id),
+        Some(&BtfType::Enum {
+            name: Some("baz".to_string()),
+            values: vec![("BAZ_A".to_string(), 1 << 40)]
+        })
+    );
+    assert_eq!(btf.find_complete('s', "foo"), Some(struct_id));
+    assert_eq!(btf.find_complete('e', "bar"), Some(enum_id));
+    assert_eq!(btf.find_complete('u', "foo"), None);
+}
+
+#[test]
+fn read_functions() {
+    // Check reading of functions, variables and other kinds that can appear in BTF of the kernel.
+    let mut builder = BtfBuilder::new();
+    let int_id = builder.int("int", 4);
+    let proto_id = builder.func_proto(int_id, &[("a", int_id), ("", 0)]);
+    let func_id = builder.func("foo", proto_id, true);
+    let var_id = builder.var("bar", int_id, false);
+    let datasec_id = builder.datasec(".data", &[var_id]);
+    let decl_tag_id = builder.decl_tag("tag", func_id);
+    let type_tag_id = builder.type_tag("user", int_id);
+    let btf = Btf::parse("vmlinux", &builder.build(), None).unwrap();
+    assert_eq!(
+        btf.get(proto_id),
+        Some(&BtfType::FuncProto {
+            return_type_id: int_id,
+            params: vec![
+                BtfParam {
+                    name: Some("a".to_string()),
+                    type_id: int_id
+                },
+                BtfParam {
+                    name: None,
+                    type_id: 0
+                },
+            ]
+        })
+    );
+    assert_eq!(
+        btf.get(func_id),
+        Some(&BtfType::Func {
+            name: "foo".to_string(),
+            type_id: proto_id,
+            is_global: true
+        })
+    );
+    assert_eq!(
+        btf.get(var_id),
+        Some(&BtfType::Var {
+            name: "bar".to_string(),
+            type_id: int_id,
+            is_global: false
+        })
+    );
+    assert_eq!(
+        btf.get(datasec_id),
+        Some(&BtfType::DataSec {
+            name: ".data".to_string()
+        })
+    );
+    assert_eq!(
+        btf.get(decl_tag_id),
+        Some(&BtfType::DeclTag { type_id: func_id })
+    );
+    assert_eq!(
+        btf.get(type_tag_id),
+        Some(&BtfType::TypeTag { type_id: int_id })
+    );
+}
+
+#[test]
+fn read_split() {
+    // Check reading of split BTF which references types of its base.
+    let mut builder = BtfBuilder::new();
+    let int_id = builder.int("int", 4);
+    let struct_id = builder.structure("foo", &[("a", int_id, 0)]);
+    let base = Btf::parse("vmlinux", &builder.build(), None).unwrap();
+
+    let mut builder = BtfBuilder::new_split(&base);
+    let fwd_id = builder.fwd("foo", false);
+    let typedef_id = builder.typedef("int", int_id);
+    let btf = Btf::parse("foo.ko", &builder.build(), Some(&base)).unwrap();
+    assert_eq!(fwd_id, base.end_id());
+    assert_eq!(
+        btf.get(int_id),
+        Some(&BtfType::Int {
+            name: "int".to_string()
+        })
+    );
+    assert_eq!(
+        btf.get(fwd_id),
+        Some(&BtfType::Fwd {
+            name: "foo".to_string(),
+            is_union: false
+        })
+    );
+    assert_eq!(
+        btf.get(typedef_id),
+        Some(&BtfType::Typedef {
+            name: "int".to_string(),
+            type_id: int_id
+        })
+    );
+    assert_eq!(btf.find_complete('s', "foo"), Some(struct_id));
+    assert_eq!(
+        btf.types().map(|(type_id, _)| type_id).collect::<Vec<_>>(),
+        vec![fwd_id, typedef_id]
+    );
+}
+
+#[test]
+fn read_invalid_magic() {
+    // Check that data without the BTF magic number is rejected.
+    let result = Btf::parse("vmlinux", b"\x00\x00\x01\x00", None);
+    assert_eq!(
+        result.err().map(|err| err.to_string()),
+        Some("vmlinux: Invalid BTF magic number".to_string())
+    );
+}
+
+#[test]
+fn read_truncated_type() {
+    // Check that a type which extends past the type section is rejected.
+    let mut builder = BtfBuilder::new();
+    builder.structure("foo", &[]);
+    let mut data = builder.build();
+    // Claim one member in the vlen field of the structure.
+    data[24 + 4] = 1;
+    let result = Btf::parse("vmlinux", &data, None);
+    assert_eq!(
+        result.err().map(|err| err.to_string()),
+        Some("vmlinux: Truncated BTF type data".to_string())
+    );
+}
+
+#[test]
+fn read_unknown_kind() {
+    // Check that an unknown type kind is rejected.
+    let mut builder = BtfBuilder::new();
+    builder.pointer(0);
+    let mut data = builder.build();
+    data[24 + 7] = 31;
+    let result = Btf::parse("vmlinux", &data, None);
+    assert_eq!(
+        result.err().map(|err| err.to_string()),
+        Some("vmlinux: Unknown BTF type kind 31".to_string())
+    );
+}
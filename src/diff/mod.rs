@@ -4,11 +4,68 @@
 use crate::MapIOErr;
 use std::fmt::Display;
 use std::io::{prelude::*, BufWriter};
-use std::ops::{Index, IndexMut};
+use std::ops::{Index, IndexMut, Range};
 
 #[cfg(test)]
 mod tests;
 
+/// The ANSI escape sequence which resets all attributes.
+pub(crate) const ANSI_RESET: &str = "\x1b[m";
+/// The ANSI escape sequence for bold text, used for headers which precede a diff.
+pub(crate) const ANSI_BOLD: &str = "\x1b[1m";
+/// The ANSI escape sequence for red text, used for removed lines.
+pub(crate) const ANSI_RED: &str = "\x1b[31m";
+/// The ANSI escape sequence for green text, used for added lines.
+pub(crate) const ANSI_GREEN: &str = "\x1b[32m";
+/// The ANSI escape sequence for cyan text, used for hunk headers.
+pub(crate) const ANSI_CYAN: &str = "\x1b[36m";
+
+// Notes:
+// [1] A word diff is produced in the same way as `git diff --word-diff`. Hunks are determined by
+//     comparing lines, each removed line is then paired with the added line at the same position in
+//     the following block of added lines and the words of the pair are compared by the Myers
+//     algorithm. The words of a line are provided by [`DiffLine::word_ranges()`]. A line of
+//     a pretty-formatted type provides its tokens, so the changed tokens are marked exactly, other
+//     lines are split into runs of non-whitespace characters. The text between words is kept with
+//     the following word. In the plain form, removed words are shown as `[-old-]` and added words
+//     as `{+new+}`. In the colored form, they are shown in red and green without the markers. Lines
+//     are written without the leading ` `, `-` and `+` characters.
+
+/// A line of a diff input.
+pub trait DiffLine: AsRef<str> + PartialEq + Display {
+    /// Returns byte ranges of the words of the line, in order and without overlaps, which are
+    /// compared by a word diff. The default implementation returns runs of non-whitespace
+    /// characters.
+    fn word_ranges(&self) -> Vec<Range<usize>> {
+        split_words(self.as_ref())
+    }
+}
+
+impl DiffLine for String {}
+
+impl DiffLine for &str {}
+
+/// Options to control the output of a diff.
+#[derive(Clone, Debug)]
+pub struct DiffOptions {
+    /// The number of unchanged lines shown around each change.
+    pub context_size: usize,
+    /// Highlight the output with ANSI colors.
+    pub color: bool,
+    /// Show the changed words inline instead of whole removed and added lines.
+    pub word_diff: bool,
+}
+
+impl Default for DiffOptions {
+    fn default() -> Self {
+        Self {
+            context_size: 3,
+            color: false,
+            word_diff: false,
+        }
+    }
+}
+
 // Implementation of the Myers diff algorithm:
 // Myers, E.W. An O(ND) difference algorithm and its variations. Algorithmica 1, 251--266 (1986).
 // https://doi.org/10.1007/BF01840446
@@ -126,33 +183,256 @@ fn myers<T: AsRef<str> + PartialEq>(a: &[T], b: &[T]) -> EditScript {
     unreachable!();
 }
 
-/// Writes a single diff hunk to the provided output stream.
-fn write_hunk<W: Write>(
+/// Finds the innermost scope which encloses the line after the specified preceding lines and
+/// returns the line which opens it.
+///
+/// A scope is opened by a line ending with `{` or `(` and closed by a line starting with `}` or
+/// `)`, as in pretty-formatted C declarations. The result is shown in a hunk header, in the same
+/// way as `git diff` shows the name of the enclosing function.
+fn find_scope<T: AsRef<str>>(preceding: &[T]) -> Option<&str> {
+    let mut depth = 0;
+    for line in preceding.iter().rev() {
+        let line = line.as_ref().trim();
+        if line.starts_with(['}', ')']) {
+            depth += 1;
+        } else if line.ends_with(['{', '(']) {
+            if depth == 0 {
+                return Some(line);
+            }
+            depth -= 1;
+        }
+    }
+    None
+}
+
+/// Writes a single diff hunk to the provided output stream. The hunk header names the enclosing
+/// `scope`, if specified.
+#[allow(clippy::too_many_arguments)]
+fn write_hunk<T: DiffLine, W: Write>(
     hunk_pos_a: usize,
     hunk_len_a: usize,
     hunk_pos_b: usize,
     hunk_len_b: usize,
-    hunk_data: &[String],
+    hunk_data: &[(char, &T)],
+    scope: Option<&str>,
+    options: &DiffOptions,
     writer: &mut BufWriter<W>,
 ) -> Result<(), crate::Error> {
     let err_desc = "Failed to write a diff hunk";
 
-    writeln!(
-        writer,
+    // An empty range is identified by the line after which it is located, as in GNU diff.
+    let start_a = if hunk_len_a == 0 {
+        hunk_pos_a - 1
+    } else {
+        hunk_pos_a
+    };
+    let start_b = if hunk_len_b == 0 {
+        hunk_pos_b - 1
+    } else {
+        hunk_pos_b
+    };
+    let header = format!(
         "@@ -{},{} +{},{} @@",
-        hunk_pos_a, hunk_len_a, hunk_pos_b, hunk_len_b
-    )
-    .map_io_err(err_desc)?;
-    for hunk_str in hunk_data {
-        writeln!(writer, "{}", hunk_str).map_io_err(err_desc)?;
+        start_a, hunk_len_a, start_b, hunk_len_b
+    );
+    let scope = scope.map(|scope| format!(" {}", scope)).unwrap_or_default();
+    if options.color {
+        writeln!(writer, "{}{}{}{}", ANSI_CYAN, header, ANSI_RESET, scope).map_io_err(err_desc)?;
+    } else {
+        writeln!(writer, "{}{}", header, scope).map_io_err(err_desc)?;
+    }
+    if options.word_diff {
+        for line in word_diff_hunk(hunk_data, options.color) {
+            writeln!(writer, "{}", line).map_io_err(err_desc)?;
+        }
+        return Ok(());
+    }
+    for &(kind, line) in hunk_data {
+        let color = match kind {
+            '-' if options.color => ANSI_RED,
+            '+' if options.color => ANSI_GREEN,
+            _ => {
+                writeln!(writer, "{}{}", kind, line).map_io_err(err_desc)?;
+                continue;
+            }
+        };
+        writeln!(writer, "{}{}{}{}", color, kind, line, ANSI_RESET).map_io_err(err_desc)?;
     }
     Ok(())
 }
 
+/// Converts lines of a diff hunk to the word diff format [1].
+fn word_diff_hunk<T: DiffLine>(hunk_data: &[(char, &T)], color: bool) -> Vec<String> {
+    let mut lines = Vec::new();
+    let (mut removed, mut inserted) = (Vec::new(), Vec::new());
+
+    for &(kind, line) in hunk_data {
+        match kind {
+            '-' => {
+                if !inserted.is_empty() {
+                    push_word_diff_block(&mut lines, &mut removed, &mut inserted, color);
+                }
+                removed.push(line);
+            }
+            '+' => inserted.push(line),
+            _ => {
+                push_word_diff_block(&mut lines, &mut removed, &mut inserted, color);
+                lines.push(line.to_string());
+            }
+        }
+    }
+    push_word_diff_block(&mut lines, &mut removed, &mut inserted, color);
+    lines
+}
+
+/// Formats a block of removed lines and the following block of added lines in the word diff
+/// format and appends the result to `lines`. Both blocks are cleared afterwards.
+fn push_word_diff_block<T: DiffLine>(
+    lines: &mut Vec<String>,
+    removed: &mut Vec<&T>,
+    inserted: &mut Vec<&T>,
+    color: bool,
+) {
+    for i in 0..std::cmp::max(removed.len(), inserted.len()) {
+        let line = match (removed.get(i), inserted.get(i)) {
+            (Some(a), Some(b)) => word_diff_line(*a, *b, color),
+            (maybe_a, maybe_b) => {
+                // Show an unpaired line as a whole, keeping any text before its first word
+                // outside the change.
+                let line = maybe_a.or(maybe_b).unwrap();
+                let text = line.as_ref();
+                let start = line
+                    .word_ranges()
+                    .first()
+                    .map_or(text.len(), |range| range.start);
+                let mut res = text[..start].to_string();
+                if maybe_a.is_some() {
+                    push_word_change(&mut res, &text[start..], "", color);
+                } else {
+                    push_word_change(&mut res, "", &text[start..], color);
+                }
+                res
+            }
+        };
+        lines.push(line);
+    }
+    removed.clear();
+    inserted.clear();
+}
+
+/// Compares words of lines `a` and `b` and returns a single line which shows the removed and
+/// added words inline.
+fn word_diff_line<T: DiffLine>(a: &T, b: &T, color: bool) -> String {
+    let words_a = separated_words(a);
+    let words_b = separated_words(b);
+    let keys_a = words_a.iter().map(|(_, word)| *word).collect::<Vec<_>>();
+    let keys_b = words_b.iter().map(|(_, word)| *word).collect::<Vec<_>>();
+
+    // Write the text before a changed word outside the change if the word starts it, otherwise
+    // include the text in the change. Unchanged words are written with the text which precedes
+    // them in the new line.
+    let mut line = String::new();
+    let (mut removed, mut inserted) = (String::new(), String::new());
+    let mut pos_b = 0;
+    for edit in myers(&keys_a, &keys_b) {
+        let is_change_start = removed.is_empty() && inserted.is_empty();
+        let ((separator, word), change) = match edit {
+            Edit::KeepA(_) => {
+                push_word_change(&mut line, &removed, &inserted, color);
+                removed.clear();
+                inserted.clear();
+                let (separator, word) = words_b[pos_b];
+                line.push_str(separator);
+                line.push_str(word);
+                pos_b += 1;
+                continue;
+            }
+            Edit::RemoveA(index_a) => (words_a[index_a], &mut removed),
+            Edit::InsertB(index_b) => {
+                pos_b += 1;
+                (words_b[index_b], &mut inserted)
+            }
+        };
+        if !change.is_empty() {
+            change.push_str(separator);
+        } else if is_change_start {
+            line.push_str(separator);
+        }
+        change.push_str(word);
+    }
+    push_word_change(&mut line, &removed, &inserted, color);
+
+    // Keep any text after the last word of the new line.
+    let text_b = b.as_ref();
+    let end = b.word_ranges().last().map_or(0, |range| range.end);
+    line.push_str(&text_b[end..]);
+    line
+}
+
+/// Returns the words of a specified line, each together with the text which precedes it.
+fn separated_words<T: DiffLine>(line: &T) -> Vec<(&str, &str)> {
+    let text = line.as_ref();
+    let mut prev_end = 0;
+    line.word_ranges()
+        .into_iter()
+        .map(|range| {
+            let separator = &text[prev_end..range.start];
+            prev_end = range.end;
+            (separator, &text[range])
+        })
+        .collect()
+}
+
+/// Appends removed and added words to `line`, marked or colored as appropriate. Empty words are
+/// skipped.
+fn push_word_change(line: &mut String, removed: &str, inserted: &str, color: bool) {
+    if !removed.is_empty() {
+        if color {
+            line.push_str(&format!("{}{}{}", ANSI_RED, removed, ANSI_RESET));
+        } else {
+            line.push_str(&format!("[-{}-]", removed));
+        }
+    }
+    if !inserted.is_empty() {
+        if color {
+            line.push_str(&format!("{}{}{}", ANSI_GREEN, inserted, ANSI_RESET));
+        } else {
+            line.push_str(&format!("{{+{}+}}", inserted));
+        }
+    }
+}
+
+/// Splits a line into runs of non-whitespace characters and returns their byte ranges.
+fn split_words(line: &str) -> Vec<Range<usize>> {
+    let mut words = Vec::new();
+    let mut maybe_start = None;
+    for (i, ch) in line.char_indices() {
+        match (maybe_start, ch.is_whitespace()) {
+            (None, false) => maybe_start = Some(i),
+            (Some(start), true) => {
+                words.push(start..i);
+                maybe_start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(start) = maybe_start {
+        words.push(start..line.len());
+    }
+    words
+}
+
 /// Compares `a` with `b` and writes their unified diff to the provided output stream.
-pub fn unified<T: AsRef<str> + PartialEq + Display, W: Write>(
+pub fn unified<T: DiffLine, W: Write>(a: &[T], b: &[T], writer: W) -> Result<(), crate::Error> {
+    unified_with_options(a, b, &DiffOptions::default(), writer)
+}
+
+/// Compares `a` with `b` and writes their unified diff to the provided output stream, with the
+/// output controlled by the specified `options`.
+pub fn unified_with_options<T: DiffLine, W: Write>(
     a: &[T],
     b: &[T],
+    options: &DiffOptions,
     writer: W,
 ) -> Result<(), crate::Error> {
     let mut writer = BufWriter::new(writer);
@@ -161,7 +441,7 @@ pub fn unified<T: AsRef<str> + PartialEq + Display, W: Write>(
     let edit_script = myers(a, b);
 
     // Turn the edit script into hunks in the unified format.
-    const CONTEXT_SIZE: usize = 3;
+    let context_size = options.context_size;
     let (mut context_begin, mut context_end) = (0, 0);
     let (mut pos_a, mut pos_b) = (1, 1);
     let (mut hunk_pos_a, mut hunk_len_a, mut hunk_pos_b, mut hunk_len_b) = (0, 0, 0, 0);
@@ -183,19 +463,21 @@ pub fn unified<T: AsRef<str> + PartialEq + Display, W: Write>(
                 pos_b += 1;
 
                 // If handling a hunk, check if it should be closed off.
-                if !hunk_data.is_empty() && context_end - context_begin > 2 * CONTEXT_SIZE {
-                    for line in a.iter().skip(context_begin).take(CONTEXT_SIZE) {
-                        hunk_data.push(format!(" {}", line));
+                if !hunk_data.is_empty() && context_end - context_begin > 2 * context_size {
+                    for line in a.iter().skip(context_begin).take(context_size) {
+                        hunk_data.push((' ', line));
                     }
-                    hunk_len_a += CONTEXT_SIZE;
-                    hunk_len_b += CONTEXT_SIZE;
-                    context_begin += CONTEXT_SIZE;
+                    hunk_len_a += context_size;
+                    hunk_len_b += context_size;
+                    context_begin += context_size;
                     write_hunk(
                         hunk_pos_a,
                         hunk_len_a,
                         hunk_pos_b,
                         hunk_len_b,
                         &hunk_data,
+                        find_scope(&a[..hunk_pos_a - 1]),
+                        options,
                         &mut writer,
                     )?;
                     hunk_data.clear();
@@ -205,8 +487,8 @@ pub fn unified<T: AsRef<str> + PartialEq + Display, W: Write>(
             Edit::RemoveA(_) | Edit::InsertB(_) => {
                 // Open a new hunk if not already handling one.
                 if hunk_data.is_empty() {
-                    if context_end - context_begin > CONTEXT_SIZE {
-                        context_begin = context_end - CONTEXT_SIZE;
+                    if context_end - context_begin > context_size {
+                        context_begin = context_end - context_size;
                     }
                     hunk_pos_a = pos_a - (context_end - context_begin);
                     hunk_len_a = 0;
@@ -223,7 +505,7 @@ pub fn unified<T: AsRef<str> + PartialEq + Display, W: Write>(
 
                 // Add any accumulated context.
                 for line in a.iter().take(context_end).skip(context_begin) {
-                    hunk_data.push(format!(" {}", line));
+                    hunk_data.push((' ', line));
                 }
                 hunk_len_a += context_end - context_begin;
                 hunk_len_b += context_end - context_begin;
@@ -231,10 +513,10 @@ pub fn unified<T: AsRef<str> + PartialEq + Display, W: Write>(
 
                 // Record the removed/added string.
                 if let Edit::RemoveA(index_a) = edit {
-                    hunk_data.push(format!("-{}", a[index_a]));
+                    hunk_data.push(('-', &a[index_a]));
                     hunk_len_a += 1;
                 } else if let Edit::InsertB(index_b) = edit {
-                    hunk_data.push(format!("+{}", b[index_b]));
+                    hunk_data.push(('+', &b[index_b]));
                     hunk_len_b += 1;
                 }
             }
@@ -243,11 +525,11 @@ pub fn unified<T: AsRef<str> + PartialEq + Display, W: Write>(
 
     // Close off the last hunk, if one is open.
     if !hunk_data.is_empty() {
-        if context_end - context_begin > CONTEXT_SIZE {
-            context_end = context_begin + CONTEXT_SIZE;
+        if context_end - context_begin > context_size {
+            context_end = context_begin + context_size;
         }
         for line in a.iter().take(context_end).skip(context_begin) {
-            hunk_data.push(format!(" {}", line));
+            hunk_data.push((' ', line));
         }
         hunk_len_a += context_end - context_begin;
         hunk_len_b += context_end - context_begin;
@@ -257,9 +539,58 @@ pub fn unified<T: AsRef<str> + PartialEq + Display, W: Write>(
             hunk_pos_b,
             hunk_len_b,
             &hunk_data,
+            find_scope(&a[..hunk_pos_a - 1]),
+            options,
             &mut writer,
         )?;
     }
 
     Ok(())
 }
+
+/// A row of a side-by-side diff, holding indices of the lines from `a` and `b` shown next to each
+/// other. A changed row misses a line on one side if the numbers of removed and inserted lines
+/// differ.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SideBySideRow {
+    Same(usize, usize),
+    Changed(Option<usize>, Option<usize>),
+}
+
+/// Compares `a` with `b` and returns rows of their side-by-side diff, covering all lines of both
+/// inputs.
+pub fn side_by_side<T: AsRef<str> + PartialEq>(a: &[T], b: &[T]) -> Vec<SideBySideRow> {
+    let mut rows = Vec::new();
+    let (mut removed, mut inserted) = (Vec::new(), Vec::new());
+
+    // Pair each run of removed lines with the run of inserted lines which follows it.
+    let flush =
+        |rows: &mut Vec<SideBySideRow>, removed: &mut Vec<usize>, inserted: &mut Vec<usize>| {
+            for i in 0..std::cmp::max(removed.len(), inserted.len()) {
+                rows.push(SideBySideRow::Changed(
+                    removed.get(i).copied(),
+                    inserted.get(i).copied(),
+                ));
+            }
+            removed.clear();
+            inserted.clear();
+        };
+
+    let mut pos_b = 0;
+    for edit in myers(a, b) {
+        match edit {
+            Edit::KeepA(index_a) => {
+                flush(&mut rows, &mut removed, &mut inserted);
+                rows.push(SideBySideRow::Same(index_a, pos_b));
+                pos_b += 1;
+            }
+            Edit::RemoveA(index_a) => removed.push(index_a),
+            Edit::InsertB(index_b) => {
+                inserted.push(index_b);
+                pos_b += 1;
+            }
+        }
+    }
+    flush(&mut rows, &mut removed, &mut inserted);
+    rows
+}
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: GPL-2.0-or-later
 
 use super::*;
+use crate::assert_ok;
 
 #[test]
 fn diff_trivial_empty() {
@@ -160,3 +161,232 @@ fn diff_keep_subsequent() {
         ]
     );
 }
+
+#[test]
+fn side_by_side_rows() {
+    // Check that removed and inserted lines are paired in a side-by-side diff.
+    let a = ["X", "Y", "Z", "W"];
+    let b = ["X", "V", "W", "U"];
+    let rows = side_by_side(&a, &b);
+    assert_eq!(
+        rows,
+        [
+            SideBySideRow::Same(0, 0),
+            SideBySideRow::Changed(Some(1), Some(1)),
+            SideBySideRow::Changed(Some(2), None),
+            SideBySideRow::Same(3, 2),
+            SideBySideRow::Changed(None, Some(3)),
+        ]
+    );
+}
+
+#[test]
+fn unified_color() {
+    // Check that a unified diff can be highlighted with colors.
+    let a = ["X", "Y"];
+    let b = ["X", "Z"];
+    let options = DiffOptions {
+        color: true,
+        ..Default::default()
+    };
+    let mut out = Vec::new();
+    let result = unified_with_options(&a, &b, &options, &mut out);
+    assert_ok!(result);
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        concat!(
+            "\x1b[36m@@ -1,2 +1,2 @@\x1b[m\n",
+            " X\n",
+            "\x1b[31m-Y\x1b[m\n",
+            "\x1b[32m+Z\x1b[m\n", //
+        )
+    );
+}
+
+#[test]
+fn unified_context_size() {
+    // Check that the number of context lines around a change can be configured.
+    let a = ["A", "B", "C", "D", "E"];
+    let b = ["A", "B", "X", "D", "E"];
+    let options = DiffOptions {
+        context_size: 1,
+        ..Default::default()
+    };
+    let mut out = Vec::new();
+    let result = unified_with_options(&a, &b, &options, &mut out);
+    assert_ok!(result);
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        concat!(
+            "@@ -2,3 +2,3 @@\n",
+            " B\n",
+            "-C\n",
+            "+X\n",
+            " D\n", //
+        )
+    );
+
+    let options = DiffOptions {
+        context_size: 0,
+        ..Default::default()
+    };
+    let mut out = Vec::new();
+    let result = unified_with_options(&a, &b, &options, &mut out);
+    assert_ok!(result);
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        concat!(
+            "@@ -3,1 +3,1 @@\n",
+            "-C\n",
+            "+X\n", //
+        )
+    );
+}
+
+#[test]
+fn unified_no_context_insert() {
+    // Check that a hunk without context which only inserts lines identifies the empty old range by
+    // the line after which the lines are inserted.
+    let a = ["A", "B", "C"];
+    let b = ["A", "B", "X", "C"];
+    let options = DiffOptions {
+        context_size: 0,
+        ..Default::default()
+    };
+    let mut out = Vec::new();
+    let result = unified_with_options(&a, &b, &options, &mut out);
+    assert_ok!(result);
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        concat!(
+            "@@ -2,0 +3,1 @@\n",
+            "+X\n", //
+        )
+    );
+}
+
+#[test]
+fn unified_no_context_remove() {
+    // Check that a hunk without context which only removes lines identifies the empty new range by
+    // the line after which the lines are removed.
+    let a = ["A", "B", "X", "C"];
+    let b = ["A", "B", "C"];
+    let options = DiffOptions {
+        context_size: 0,
+        ..Default::default()
+    };
+    let mut out = Vec::new();
+    let result = unified_with_options(&a, &b, &options, &mut out);
+    assert_ok!(result);
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        concat!(
+            "@@ -3,1 +2,0 @@\n",
+            "-X\n", //
+        )
+    );
+}
+
+#[test]
+fn unified_scope() {
+    // Check that hunk headers name the innermost scope which encloses the hunk.
+    let a = [
+        "struct foo {",
+        "\tint a;",
+        "\tint b;",
+        "\tint c;",
+        "\tunion {",
+        "\t\tint d;",
+        "\t\tint e;",
+        "\t};",
+        "\tint f;",
+        "\tint g;",
+        "\tint h;",
+        "\tint i;",
+        "\tint j;",
+        "}",
+    ];
+    let mut b = a;
+    b[6] = "\t\tlong e;";
+    b[12] = "\tlong j;";
+    let options = DiffOptions {
+        context_size: 1,
+        ..Default::default()
+    };
+    let mut out = Vec::new();
+    let result = unified_with_options(&a, &b, &options, &mut out);
+    assert_ok!(result);
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        concat!(
+            "@@ -6,3 +6,3 @@ union {\n",
+            " \t\tint d;\n",
+            "-\t\tint e;\n",
+            "+\t\tlong e;\n",
+            " \t};\n",
+            "@@ -12,3 +12,3 @@ struct foo {\n",
+            " \tint i;\n",
+            "-\tint j;\n",
+            "+\tlong j;\n",
+            " }\n", //
+        )
+    );
+}
+
+#[test]
+fn split_words_whitespace() {
+    // Check that a line is split into runs of non-whitespace characters by default.
+    assert_eq!(split_words("\tunsigned long a;"), [1..9, 10..14, 15..17]);
+    assert_eq!(split_words("int  b, "), [0..3, 5..7]);
+    assert_eq!(split_words(""), []);
+}
+
+#[test]
+fn unified_word_diff() {
+    // Check that the word diff shows the changed tokens inline and unpaired lines as a whole.
+    let a = ["struct foo {", "\tint a;", "\tint b;", "}"];
+    let b = ["struct foo {", "\tlong a;", "\tint b;", "\tint c;", "}"];
+    let options = DiffOptions {
+        word_diff: true,
+        ..Default::default()
+    };
+    let mut out = Vec::new();
+    let result = unified_with_options(&a, &b, &options, &mut out);
+    assert_ok!(result);
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        concat!(
+            "@@ -1,4 +1,5 @@\n",
+            "struct foo {\n",
+            "\t[-int-]{+long+} a;\n",
+            "\tint b;\n",
+            "\t{+int c;+}\n",
+            "}\n", //
+        )
+    );
+}
+
+#[test]
+fn unified_word_diff_color() {
+    // Check that the colored word diff highlights the changed tokens without markers.
+    let a = ["void foo (", "\tint a,", "\tint b", ")"];
+    let b = ["void foo (", "\tint a,", "\tconst int b", ")"];
+    let options = DiffOptions {
+        color: true,
+        word_diff: true,
+        ..Default::default()
+    };
+    let mut out = Vec::new();
+    let result = unified_with_options(&a, &b, &options, &mut out);
+    assert_ok!(result);
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        concat!(
+            "\x1b[36m@@ -1,4 +1,4 @@\x1b[m\n",
+            "void foo (\n",
+            "\tint a,\n",
+            "\t\x1b[32mconst\x1b[m int b\n",
+            ")\n", //
+        )
+    );
+}
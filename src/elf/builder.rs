@@ -0,0 +1,160 @@
+// Copyright (C) 2025 SUSE LLC <petr.pavlu@suse.com>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use super::*;
+
+/// A helper to construct 64-bit ELF object files in tests.
+pub(crate) struct ElfBuilder {
+    is_little_endian: bool,
+    sections: Vec<(String, Vec<u8>)>,
+    symbols: Vec<(String, String)>,
+}
+
+/// A section of the built object, as a tuple of its name, type, link and data.
+type BuiltSection = (String, u32, u32, Vec<u8>);
+
+impl ElfBuilder {
+    /// Creates a new builder of a little-endian object.
+    pub(crate) fn new() -> Self {
+        Self {
+            is_little_endian: true,
+            sections: Vec::new(),
+            symbols: Vec::new(),
+        }
+    }
+
+    /// Switches the object to the big-endian byte order.
+    pub(crate) fn big_endian(mut self) -> Self {
+        self.is_little_endian = false;
+        self
+    }
+
+    /// Adds a section with the specified name and data.
+    pub(crate) fn section(mut self, name: &str, data: &[u8]) -> Self {
+        self.sections.push((name.to_string(), data.to_vec()));
+        self
+    }
+
+    /// Adds a symbol defined in the section with the specified name. Adding any symbol makes the
+    /// object contain a symbol table.
+    pub(crate) fn symbol(mut self, name: &str, section_name: &str) -> Self {
+        self.symbols
+            .push((name.to_string(), section_name.to_string()));
+        self
+    }
+
+    fn u16_bytes(&self, value: u16) -> [u8; 2] {
+        if self.is_little_endian {
+            value.to_le_bytes()
+        } else {
+            value.to_be_bytes()
+        }
+    }
+
+    fn u32_bytes(&self, value: u32) -> [u8; 4] {
+        if self.is_little_endian {
+            value.to_le_bytes()
+        } else {
+            value.to_be_bytes()
+        }
+    }
+
+    fn u64_bytes(&self, value: u64) -> [u8; 8] {
+        if self.is_little_endian {
+            value.to_le_bytes()
+        } else {
+            value.to_be_bytes()
+        }
+    }
+
+    /// Returns the content of the object file.
+    pub(crate) fn build(&self) -> Vec<u8> {
+        let mut sections: Vec<BuiltSection> = self
+            .sections
+            .iter()
+            .map(|(name, data)| (name.clone(), 1, 0, data.clone()))
+            .collect();
+
+        // Add the symbol table and its string table.
+        if !self.symbols.is_empty() {
+            let mut strtab = vec![0];
+            let mut symtab = vec![0; 24];
+            for (name, section_name) in &self.symbols {
+                let section_idx = self
+                    .sections
+                    .iter()
+                    .position(|(other_name, _)| other_name == section_name)
+                    .map_or(0, |i| i + 1);
+                symtab.extend(self.u32_bytes(strtab.len() as u32)); // st_name
+                symtab.extend([0, 0]); // st_info, st_other
+                symtab.extend(self.u16_bytes(section_idx as u16)); // st_shndx
+                symtab.extend(self.u64_bytes(0)); // st_value
+                symtab.extend(self.u64_bytes(0)); // st_size
+                strtab.extend(name.as_bytes());
+                strtab.push(0);
+            }
+            let strtab_idx = sections.len() as u32 + 1;
+            sections.push((".strtab".to_string(), 3, 0, strtab));
+            sections.push((".symtab".to_string(), SHT_SYMTAB, strtab_idx, symtab));
+        }
+
+        // Add the section name table.
+        let mut shstrtab = vec![0];
+        let mut name_offsets = Vec::new();
+        for name in sections
+            .iter()
+            .map(|(name, _, _, _)| name.as_str())
+            .chain([".shstrtab"])
+        {
+            name_offsets.push(shstrtab.len() as u32);
+            shstrtab.extend(name.as_bytes());
+            shstrtab.push(0);
+        }
+        sections.push((".shstrtab".to_string(), 3, 0, shstrtab));
+
+        // Place the section data after the ELF header, followed by the section headers.
+        let body_size = sections
+            .iter()
+            .map(|(_, _, _, data)| data.len())
+            .sum::<usize>();
+        let shoff = 64 + body_size;
+
+        let mut elf = Vec::new();
+        elf.extend(ELF_MAGIC);
+        elf.extend([2, if self.is_little_endian { 1 } else { 2 }, 1]);
+        elf.resize(16, 0);
+        elf.extend(self.u16_bytes(1)); // e_type
+        elf.extend(self.u16_bytes(62)); // e_machine
+        elf.extend(self.u32_bytes(1)); // e_version
+        elf.extend(self.u64_bytes(0)); // e_entry
+        elf.extend(self.u64_bytes(0)); // e_phoff
+        elf.extend(self.u64_bytes(shoff as u64)); // e_shoff
+        elf.extend(self.u32_bytes(0)); // e_flags
+        elf.extend(self.u16_bytes(64)); // e_ehsize
+        elf.extend(self.u16_bytes(0)); // e_phentsize
+        elf.extend(self.u16_bytes(0)); // e_phnum
+        elf.extend(self.u16_bytes(64)); // e_shentsize
+        elf.extend(self.u16_bytes(sections.len() as u16 + 1)); // e_shnum
+        elf.extend(self.u16_bytes(sections.len() as u16)); // e_shstrndx
+        for (_, _, _, data) in &sections {
+            elf.extend(data);
+        }
+
+        elf.extend([0; 64]);
+        let mut offset = 64;
+        for ((_, sh_type, link, data), name_offset) in sections.iter().zip(name_offsets) {
+            elf.extend(self.u32_bytes(name_offset)); // sh_name
+            elf.extend(self.u32_bytes(*sh_type)); // sh_type
+            elf.extend(self.u64_bytes(0)); // sh_flags
+            elf.extend(self.u64_bytes(0)); // sh_addr
+            elf.extend(self.u64_bytes(offset as u64)); // sh_offset
+            elf.extend(self.u64_bytes(data.len() as u64)); // sh_size
+            elf.extend(self.u32_bytes(*link)); // sh_link
+            elf.extend(self.u32_bytes(0)); // sh_info
+            elf.extend(self.u64_bytes(1)); // sh_addralign
+            elf.extend(self.u64_bytes(0)); // sh_entsize
+            offset += data.len();
+        }
+        elf
+    }
+}
@@ -0,0 +1,387 @@
+// Copyright (C) 2025 SUSE LLC <petr.pavlu@suse.com>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[cfg(test)]
+pub(crate) mod builder;
+#[cfg(test)]
+mod tests;
+
+/// The magic number at the start of each ELF file.
+const ELF_MAGIC: &[u8] = b"\x7fELF";
+
+/// Section type of the symbol table.
+const SHT_SYMTAB: u32 = 2;
+
+/// Section type of sections which occupy no space in the file, such as `.bss`.
+const SHT_NOBITS: u32 = 8;
+
+/// The prefix of symbols which describe exports of the kernel.
+const KSYMTAB_PREFIX: &str = "__ksymtab_";
+
+/// A reader of integers stored in a given byte order.
+pub(crate) struct DataReader<'a> {
+    data: &'a [u8],
+    is_little_endian: bool,
+}
+
+impl<'a> DataReader<'a> {
+    /// Creates a new reader of the specified data.
+    pub(crate) fn new(data: &'a [u8], is_little_endian: bool) -> Self {
+        Self {
+            data,
+            is_little_endian,
+        }
+    }
+
+    /// Returns `N` bytes at the specified offset, or [`None`] if the data is too short.
+    fn bytes<const N: usize>(&self, offset: usize) -> Option<[u8; N]> {
+        let end = offset.checked_add(N)?;
+        self.data.get(offset..end)?.try_into().ok()
+    }
+
+    /// Reads a 16-bit value at the specified offset.
+    pub(crate) fn u16(&self, offset: usize) -> Option<u16> {
+        let bytes = self.bytes(offset)?;
+        Some(if self.is_little_endian {
+            u16::from_le_bytes(bytes)
+        } else {
+            u16::from_be_bytes(bytes)
+        })
+    }
+
+    /// Reads a 32-bit value at the specified offset.
+    pub(crate) fn u32(&self, offset: usize) -> Option<u32> {
+        let bytes = self.bytes(offset)?;
+        Some(if self.is_little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    }
+
+    /// Reads a 64-bit value at the specified offset.
+    pub(crate) fn u64(&self, offset: usize) -> Option<u64> {
+        let bytes = self.bytes(offset)?;
+        Some(if self.is_little_endian {
+            u64::from_le_bytes(bytes)
+        } else {
+            u64::from_be_bytes(bytes)
+        })
+    }
+}
+
+/// A section of an ELF file.
+struct Section {
+    name: String,
+    sh_type: u32,
+    /// The index of a related section, for a symbol table its string table.
+    link: u32,
+    offset: usize,
+    size: usize,
+}
+
+/// A symbol from the symbol table of an ELF file.
+#[derive(Debug, PartialEq)]
+pub struct ElfSymbol {
+    pub name: String,
+    /// The index of the section in which the symbol is defined.
+    pub section_idx: u16,
+}
+
+/// An ELF object file, such as vmlinux or a kernel module.
+///
+/// The section headers are parsed when the object is created, data of individual sections is
+/// obtained on demand.
+pub struct ElfObject {
+    path: PathBuf,
+    data: Vec<u8>,
+    is_64: bool,
+    is_little_endian: bool,
+    sections: Vec<Section>,
+}
+
+impl ElfObject {
+    /// Returns whether the specified data starts with the ELF magic number.
+    pub fn is_elf(data: &[u8]) -> bool {
+        data.starts_with(ELF_MAGIC)
+    }
+
+    /// Reads an ELF object from a specified file.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, crate::Error> {
+        let path = path.as_ref();
+        let data = fs::read(path).map_err(|err| {
+            crate::Error::new_io(&format!("Failed to read file '{}'", path.display()), err)
+        })?;
+        Self::parse(path, data)
+    }
+
+    /// Parses an ELF object from the specified data.
+    ///
+    /// The `path` indicates the origin of the data.
+    pub fn parse<P: AsRef<Path>>(path: P, data: Vec<u8>) -> Result<Self, crate::Error> {
+        let path = path.as_ref();
+        let parse_err = |desc: &str| crate::Error::new_parse(path, None, desc);
+
+        if !Self::is_elf(&data) {
+            return Err(parse_err("Not an ELF file"));
+        }
+        let is_64 = match data.get(4) {
+            Some(1) => false,
+            Some(2) => true,
+            _ => return Err(parse_err("Unsupported ELF class")),
+        };
+        let is_little_endian = match data.get(5) {
+            Some(1) => true,
+            Some(2) => false,
+            _ => return Err(parse_err("Unsupported ELF data encoding")),
+        };
+
+        // Read the location of the section headers.
+        let reader = DataReader::new(&data, is_little_endian);
+        let truncated_err = || parse_err("Truncated ELF header");
+        let (shoff, shentsize, shnum, shstrndx) = if is_64 {
+            (
+                reader.u64(0x28).ok_or_else(truncated_err)?,
+                reader.u16(0x3a).ok_or_else(truncated_err)?,
+                reader.u16(0x3c).ok_or_else(truncated_err)?,
+                reader.u16(0x3e).ok_or_else(truncated_err)?,
+            )
+        } else {
+            (
+                reader.u32(0x20).ok_or_else(truncated_err)? as u64,
+                reader.u16(0x2e).ok_or_else(truncated_err)?,
+                reader.u16(0x30).ok_or_else(truncated_err)?,
+                reader.u16(0x32).ok_or_else(truncated_err)?,
+            )
+        };
+        let min_shentsize = if is_64 { 64 } else { 40 };
+        if shnum != 0 && (shentsize as usize) < min_shentsize {
+            return Err(parse_err("Invalid size of ELF section headers"));
+        }
+
+        // Read all section headers.
+        let truncated_err = || parse_err("Truncated ELF section header");
+        let mut headers = Vec::new();
+        for i in 0..shnum as usize {
+            let base = usize::try_from(shoff)
+                .ok()
+                .and_then(|shoff| shoff.checked_add(i * shentsize as usize))
+                .ok_or_else(truncated_err)?;
+            let (name_offset, sh_type, offset, size, link) = if is_64 {
+                (
+                    reader.u32(base).ok_or_else(truncated_err)?,
+                    reader.u32(base + 4).ok_or_else(truncated_err)?,
+                    reader.u64(base + 24).ok_or_else(truncated_err)?,
+                    reader.u64(base + 32).ok_or_else(truncated_err)?,
+                    reader.u32(base + 40).ok_or_else(truncated_err)?,
+                )
+            } else {
+                (
+                    reader.u32(base).ok_or_else(truncated_err)?,
+                    reader.u32(base + 4).ok_or_else(truncated_err)?,
+                    reader.u32(base + 16).ok_or_else(truncated_err)? as u64,
+                    reader.u32(base + 20).ok_or_else(truncated_err)? as u64,
+                    reader.u32(base + 24).ok_or_else(truncated_err)?,
+                )
+            };
+
+            // Check that the section data is within the file.
+            let offset = usize::try_from(offset).map_err(|_| truncated_err())?;
+            let size = if sh_type == SHT_NOBITS {
+                0
+            } else {
+                usize::try_from(size).map_err(|_| truncated_err())?
+            };
+            if offset.checked_add(size).is_none_or(|end| end > data.len()) {
+                return Err(parse_err(&format!(
+                    "Data of ELF section '{}' is out of bounds",
+                    i
+                )));
+            }
+
+            headers.push((name_offset, sh_type, link, offset, size));
+        }
+
+        // Resolve section names.
+        let shstrtab = match headers.get(shstrndx as usize) {
+            Some(&(_, _, _, offset, size)) => &data[offset..offset + size],
+            None if headers.is_empty() => &[],
+            None => return Err(parse_err("Invalid index of the ELF section name table")),
+        };
+        let mut sections = Vec::new();
+        for (name_offset, sh_type, link, offset, size) in headers {
+            let name = read_c_string(shstrtab, name_offset as usize)
+                .ok_or_else(|| parse_err("Invalid name of an ELF section"))?;
+            sections.push(Section {
+                name: name.to_string(),
+                sh_type,
+                link,
+                offset,
+                size,
+            });
+        }
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            data,
+            is_64,
+            is_little_endian,
+            sections,
+        })
+    }
+
+    /// Returns the path of the file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns whether the file uses the little-endian byte order.
+    pub fn is_little_endian(&self) -> bool {
+        self.is_little_endian
+    }
+
+    /// Returns data of the first section with the specified name, or [`None`] if no such section
+    /// exists.
+    pub fn section_data(&self, name: &str) -> Option<&[u8]> {
+        self.sections
+            .iter()
+            .find(|section| section.name == name)
+            .map(|section| self.data_of(section))
+    }
+
+    /// Returns the name of the section with the specified index, or [`None`] if no such section
+    /// exists.
+    pub fn section_name(&self, section_idx: usize) -> Option<&str> {
+        self.sections
+            .get(section_idx)
+            .map(|section| section.name.as_str())
+    }
+
+    /// Returns data of a specified section.
+    fn data_of(&self, section: &Section) -> &[u8] {
+        if section.sh_type == SHT_NOBITS {
+            &[]
+        } else {
+            &self.data[section.offset..section.offset + section.size]
+        }
+    }
+
+    /// Reads all symbols from the symbol table, or returns [`None`] if the file has no symbol
+    /// table.
+    pub fn symbols(&self) -> Result<Option<Vec<ElfSymbol>>, crate::Error> {
+        let parse_err = |desc: &str| crate::Error::new_parse(&self.path, None, desc);
+
+        let symtab = match self
+            .sections
+            .iter()
+            .find(|section| section.sh_type == SHT_SYMTAB)
+        {
+            Some(symtab) => symtab,
+            None => return Ok(None),
+        };
+        let strtab = self
+            .sections
+            .get(symtab.link as usize)
+            .map(|section| self.data_of(section))
+            .ok_or_else(|| parse_err("Invalid index of the ELF symbol string table"))?;
+
+        let reader = DataReader::new(self.data_of(symtab), self.is_little_endian);
+        let entsize = if self.is_64 { 24 } else { 16 };
+        let truncated_err = || parse_err("Truncated ELF symbol");
+        let mut symbols = Vec::new();
+        // Skip the initial null symbol.
+        for i in 1..symtab.size / entsize {
+            let base = i * entsize;
+            let name_offset = reader.u32(base).ok_or_else(truncated_err)?;
+            let shndx_offset = if self.is_64 { base + 6 } else { base + 14 };
+            let section_idx = reader.u16(shndx_offset).ok_or_else(truncated_err)?;
+            let name = read_c_string(strtab, name_offset as usize)
+                .ok_or_else(|| parse_err("Invalid name of an ELF symbol"))?;
+            symbols.push(ElfSymbol {
+                name: name.to_string(),
+                section_idx,
+            });
+        }
+        Ok(Some(symbols))
+    }
+
+    /// Returns names of all symbols exported by a kernel binary, such as vmlinux or a module.
+    ///
+    /// The exports are determined from `__ksymtab_<name>` symbols defined in the `__ksymtab` and
+    /// `__ksymtab_gpl` sections. If the file has no symbol table, the function falls back to
+    /// reading all names in the `__ksymtab_strings` section. Note that this section can contain
+    /// also names of symbol namespaces which cannot be told apart from the export names.
+    pub fn kernel_exports(&self) -> Result<Vec<String>, crate::Error> {
+        if let Some(symbols) = self.symbols()? {
+            let mut exports = Vec::new();
+            for symbol in symbols {
+                let name = match symbol.name.strip_prefix(KSYMTAB_PREFIX) {
+                    Some(name) => name,
+                    None => continue,
+                };
+                let section_name = self.section_name(symbol.section_idx as usize);
+                if section_name == Some("__ksymtab") || section_name == Some("__ksymtab_gpl") {
+                    exports.push(name.to_string());
+                }
+            }
+            return Ok(exports);
+        }
+
+        let mut exports = Vec::new();
+        if let Some(strtab) = self.section_data("__ksymtab_strings") {
+            let mut offset = 0;
+            while let Some(name) = read_c_string(strtab, offset) {
+                if !name.is_empty() {
+                    exports.push(name.to_string());
+                }
+                offset += name.len() + 1;
+            }
+        }
+        Ok(exports)
+    }
+
+    /// Returns names of all symbols imported by a kernel module, as recorded in its version table.
+    ///
+    /// The names are read from the `__versions` section, in which each entry is a CRC followed by
+    /// a name, for a total size of 64 bytes. Newer kernels can store the names instead in the
+    /// `__version_ext_names` section as a sequence of NUL-terminated strings, which is preferred
+    /// if present. A module without a version table imports nothing.
+    pub fn module_imports(&self) -> Result<Vec<String>, crate::Error> {
+        let mut imports = Vec::new();
+        if let Some(names) = self.section_data("__version_ext_names") {
+            let mut offset = 0;
+            while let Some(name) = read_c_string(names, offset) {
+                if !name.is_empty() {
+                    imports.push(name.to_string());
+                }
+                offset += name.len() + 1;
+            }
+            return Ok(imports);
+        }
+
+        if let Some(versions) = self.section_data("__versions") {
+            let name_offset = if self.is_64 { 8 } else { 4 };
+            for entry in versions.chunks(64) {
+                let name = read_c_string(entry, name_offset).ok_or_else(|| {
+                    crate::Error::new_parse(
+                        &self.path,
+                        None,
+                        "Invalid entry in the __versions section",
+                    )
+                })?;
+                imports.push(name.to_string());
+            }
+        }
+        Ok(imports)
+    }
+}
+
+/// Reads a NUL-terminated string at the specified offset in a string table.
+pub(crate) fn read_c_string(strtab: &[u8], offset: usize) -> Option<&str> {
+    let data = strtab.get(offset..)?;
+    let end = data.iter().position(|&byte| byte == 0)?;
+    std::str::from_utf8(&data[..end]).ok()
+}
@@ -0,0 +1,158 @@
+// Copyright (C) 2025 SUSE LLC <petr.pavlu@suse.com>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use super::builder::ElfBuilder;
+use super::*;
+
+#[test]
+fn read_sections() {
+    // Check basic reading of sections from a little-endian file.
+    let data = ElfBuilder::new()
+        .section(".text", b"\x90\x90")
+        .section(".BTF", b"btf")
+        .build();
+    assert!(ElfObject::is_elf(&data));
+    let elf = ElfObject::parse("test.o", data).unwrap();
+    assert_eq!(elf.path(), Path::new("test.o"));
+    assert!(elf.is_little_endian());
+    assert_eq!(elf.section_data(".text"), Some(&b"\x90\x90"[..]));
+    assert_eq!(elf.section_data(".BTF"), Some(&b"btf"[..]));
+    assert_eq!(elf.section_data(".data"), None);
+}
+
+#[test]
+fn read_big_endian() {
+    // Check reading of sections from a big-endian file.
+    let data = ElfBuilder::new()
+        .big_endian()
+        .section("__ksymtab_strings", b"foo\0")
+        .build();
+    let elf = ElfObject::parse("test.o", data).unwrap();
+    assert!(!elf.is_little_endian());
+    assert_eq!(elf.section_data("__ksymtab_strings"), Some(&b"foo\0"[..]));
+}
+
+#[test]
+fn read_not_elf() {
+    // Check that data without the ELF magic number is rejected.
+    let result = ElfObject::parse("test.o", b"!<arch>\n".to_vec());
+    assert_eq!(
+        result.err().map(|err| err.to_string()),
+        Some("test.o: Not an ELF file".to_string())
+    );
+}
+
+#[test]
+fn read_truncated() {
+    // Check that section headers outside of the file are rejected.
+    let mut data = ElfBuilder::new().section(".BTF", b"btf").build();
+    data.truncate(data.len() - 32);
+    let result = ElfObject::parse("test.o", data);
+    assert_eq!(
+        result.err().map(|err| err.to_string()),
+        Some("test.o: Truncated ELF section header".to_string())
+    );
+}
+
+#[test]
+fn read_section_out_of_bounds() {
+    // Check that section data outside of the file is rejected.
+    let mut data = ElfBuilder::new().section(".BTF", b"btf").build();
+    // Increase sh_size of the .BTF section, its header follows the ELF header, section data and
+    // the null section header.
+    let header = data.len() - 2 * 64;
+    data[header + 32] = 0xff;
+    let result = ElfObject::parse("test.o", data);
+    assert_eq!(
+        result.err().map(|err| err.to_string()),
+        Some("test.o: Data of ELF section '1' is out of bounds".to_string())
+    );
+}
+
+#[test]
+fn read_symbols() {
+    // Check reading of the symbol table.
+    let data = ElfBuilder::new()
+        .big_endian()
+        .section(".text", b"")
+        .symbol("foo", ".text")
+        .symbol("bar", "")
+        .build();
+    let elf = ElfObject::parse("test.o", data).unwrap();
+    assert_eq!(elf.section_name(1), Some(".text"));
+    assert_eq!(
+        elf.symbols().unwrap(),
+        Some(vec![
+            ElfSymbol {
+                name: "foo".to_string(),
+                section_idx: 1
+            },
+            ElfSymbol {
+                name: "bar".to_string(),
+                section_idx: 0
+            },
+        ])
+    );
+
+    let data = ElfBuilder::new().section(".text", b"").build();
+    let elf = ElfObject::parse("test.o", data).unwrap();
+    assert_eq!(elf.symbols().unwrap(), None);
+}
+
+#[test]
+fn read_kernel_exports() {
+    // Check that kernel exports are read from the __ksymtab_<name> symbols.
+    let data = ElfBuilder::new()
+        .section("__ksymtab", b"")
+        .section("__ksymtab_gpl", b"")
+        .section("__ksymtab_strings", b"foo\0bar\0baz\0")
+        .section(".text", b"")
+        .symbol("__ksymtab_foo", "__ksymtab")
+        .symbol("__ksymtab_bar", "__ksymtab_gpl")
+        .symbol("__ksymtab_baz", ".text")
+        .symbol("baz", ".text")
+        .build();
+    let elf = ElfObject::parse("test.ko", data).unwrap();
+    assert_eq!(elf.kernel_exports().unwrap(), vec!["foo", "bar"]);
+}
+
+#[test]
+fn read_kernel_exports_stripped() {
+    // Check that kernel exports are read from the __ksymtab_strings section if the file has no
+    // symbol table.
+    let data = ElfBuilder::new()
+        .section("__ksymtab_strings", b"foo\0bar\0")
+        .build();
+    let elf = ElfObject::parse("test.ko", data).unwrap();
+    assert_eq!(elf.kernel_exports().unwrap(), vec!["foo", "bar"]);
+}
+
+#[test]
+fn read_module_imports() {
+    // Check that imported symbols are read from the __versions section.
+    let mut versions = Vec::new();
+    for name in ["module_layout", "printk"] {
+        let mut entry = vec![0; 64];
+        entry[8..8 + name.len()].copy_from_slice(name.as_bytes());
+        versions.extend(entry);
+    }
+    let data = ElfBuilder::new().section("__versions", &versions).build();
+    let elf = ElfObject::parse("test.ko", data).unwrap();
+    assert_eq!(
+        elf.module_imports().unwrap(),
+        vec!["module_layout", "printk"]
+    );
+}
+
+#[test]
+fn read_module_imports_ext() {
+    // Check that imported symbols are read from the __version_ext_names section.
+    let data = ElfBuilder::new()
+        .section("__version_ext_names", b"module_layout\0printk\0")
+        .build();
+    let elf = ElfObject::parse("test.ko", data).unwrap();
+    assert_eq!(
+        elf.module_imports().unwrap(),
+        vec!["module_layout", "printk"]
+    );
+}
@@ -0,0 +1,122 @@
+// Copyright (C) 2025 SUSE LLC <petr.pavlu@suse.com>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use std::io;
+use std::io::prelude::*;
+
+#[cfg(test)]
+mod tests;
+
+/// A JSON value, used to produce machine-readable output.
+///
+/// Object members are stored as a [`Vec`] to preserve their order in the output.
+#[derive(Debug, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    /// Creates a new `Value::String`.
+    pub fn new_string<S: Into<String>>(value: S) -> Self {
+        Value::String(value.into())
+    }
+
+    /// Creates a new `Value::Object` from a list of members.
+    pub fn new_object<S: Into<String>, I: IntoIterator<Item = (S, Value)>>(members: I) -> Self {
+        Value::Object(
+            members
+                .into_iter()
+                .map(|(key, value)| (key.into(), value))
+                .collect(),
+        )
+    }
+
+    /// Writes the value in a pretty-printed form, using two spaces for each level of indentation,
+    /// and terminates it with a newline.
+    pub fn write_pretty<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        self.write_inner(&mut writer, 0)?;
+        writeln!(writer)
+    }
+
+    /// Writes the value at the given indentation level.
+    fn write_inner<W: Write>(&self, writer: &mut W, indent: usize) -> io::Result<()> {
+        match self {
+            Self::Null => write!(writer, "null"),
+            Self::Bool(value) => write!(writer, "{}", value),
+            Self::Int(value) => write!(writer, "{}", value),
+            Self::Float(value) => {
+                if value.is_finite() {
+                    write!(writer, "{:?}", value)
+                } else {
+                    write!(writer, "null")
+                }
+            }
+            Self::String(value) => write_string(writer, value),
+            Self::Array(items) => {
+                if items.is_empty() {
+                    return write!(writer, "[]");
+                }
+                writeln!(writer, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    write_indent(writer, indent + 1)?;
+                    item.write_inner(writer, indent + 1)?;
+                    if i + 1 < items.len() {
+                        write!(writer, ",")?;
+                    }
+                    writeln!(writer)?;
+                }
+                write_indent(writer, indent)?;
+                write!(writer, "]")
+            }
+            Self::Object(members) => {
+                if members.is_empty() {
+                    return write!(writer, "{{}}");
+                }
+                writeln!(writer, "{{")?;
+                for (i, (key, value)) in members.iter().enumerate() {
+                    write_indent(writer, indent + 1)?;
+                    write_string(writer, key)?;
+                    write!(writer, ": ")?;
+                    value.write_inner(writer, indent + 1)?;
+                    if i + 1 < members.len() {
+                        write!(writer, ",")?;
+                    }
+                    writeln!(writer)?;
+                }
+                write_indent(writer, indent)?;
+                write!(writer, "}}")
+            }
+        }
+    }
+}
+
+/// Writes indentation for the given level.
+fn write_indent<W: Write>(writer: &mut W, indent: usize) -> io::Result<()> {
+    for _ in 0..indent {
+        write!(writer, "  ")?;
+    }
+    Ok(())
+}
+
+/// Writes a quoted and escaped JSON string.
+fn write_string<W: Write>(writer: &mut W, value: &str) -> io::Result<()> {
+    write!(writer, "\"")?;
+    for ch in value.chars() {
+        match ch {
+            '"' => write!(writer, "\\\"")?,
+            '\\' => write!(writer, "\\\\")?,
+            '\n' => write!(writer, "\\n")?,
+            '\r' => write!(writer, "\\r")?,
+            '\t' => write!(writer, "\\t")?,
+            ch if (ch as u32) < 0x20 => write!(writer, "\\u{:04x}", ch as u32)?,
+            ch => write!(writer, "{}", ch)?,
+        }
+    }
+    write!(writer, "\"")
+}
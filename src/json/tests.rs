@@ -0,0 +1,53 @@
+// Copyright (C) 2025 SUSE LLC <petr.pavlu@suse.com>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use super::*;
+
+#[test]
+fn json_scalars() {
+    // Check the output of scalar values.
+    for (value, exp_out) in [
+        (Value::Null, "null\n"),
+        (Value::Bool(true), "true\n"),
+        (Value::Int(-42), "-42\n"),
+        (Value::Float(12.5), "12.5\n"),
+        (Value::Float(f64::NAN), "null\n"),
+        (
+            Value::new_string("a\"b\\c\nd\u{1}"),
+            "\"a\\\"b\\\\c\\nd\\u0001\"\n",
+        ),
+    ] {
+        let mut out = Vec::new();
+        value.write_pretty(&mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), exp_out);
+    }
+}
+
+#[test]
+fn json_nested() {
+    // Check the pretty-printed output of nested arrays and objects.
+    let value = Value::new_object([
+        ("name", Value::new_string("foo")),
+        ("items", Value::Array(vec![Value::Int(1), Value::Int(2)])),
+        ("empty", Value::Array(Vec::new())),
+        ("inner", Value::new_object([("flag", Value::Bool(false))])),
+    ]);
+    let mut out = Vec::new();
+    value.write_pretty(&mut out).unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        concat!(
+            "{\n",
+            "  \"name\": \"foo\",\n",
+            "  \"items\": [\n",
+            "    1,\n",
+            "    2\n",
+            "  ],\n",
+            "  \"empty\": [],\n",
+            "  \"inner\": {\n",
+            "    \"flag\": false\n",
+            "  }\n",
+            "}\n", //
+        )
+    );
+}
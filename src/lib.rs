@@ -6,18 +6,89 @@ use std::io;
 use std::io::prelude::*;
 use std::path::{Path, PathBuf};
 
+pub mod btf;
 pub mod diff;
+pub mod elf;
+pub mod json;
+pub mod maintainers;
+pub mod source;
 pub mod sym;
 
 /// An error type for the crate, annotating standard errors with contextual information and
 /// providing custom errors.
+///
+/// Errors which originate from specific input data record its path and, if applicable, a 1-based
+/// line number, which can be obtained with [`Error::path()`] and [`Error::line()`].
 #[derive(Debug)]
 pub enum Error {
+    /// An I/O operation failed.
     IO {
         desc: String,
         io_err: std::io::Error,
     },
-    Parse(String),
+    /// Input data is malformed in a way not covered by any other error kind.
+    Parse {
+        path: PathBuf,
+        line: Option<usize>,
+        desc: String,
+    },
+    /// A record is missing its name.
+    MissingRecordName { path: PathBuf, line: usize },
+    /// A record has the same name as a previous record in the same file.
+    DuplicateRecord {
+        name: String,
+        path: PathBuf,
+        line: usize,
+    },
+    /// An export is defined in multiple files.
+    DuplicateExport {
+        name: String,
+        path: PathBuf,
+        line: Option<usize>,
+        previous_path: PathBuf,
+    },
+    /// A type is referenced by input data but it is not defined.
+    UnknownType {
+        name: String,
+        path: PathBuf,
+        line: usize,
+    },
+    /// A type is implicitly referenced by a file record in a consolidated file but it has multiple
+    /// variants.
+    AmbiguousFileType {
+        name: String,
+        path: PathBuf,
+        file_path: PathBuf,
+    },
+    /// A type is referenced by a file record in a consolidated file but it is not defined.
+    UnknownFileType {
+        name: String,
+        path: PathBuf,
+        file_path: PathBuf,
+    },
+    /// A type needed by an export is not recorded in the file of the export.
+    MissingFileType {
+        name: String,
+        file_path: PathBuf,
+        export: String,
+    },
+    /// A type variant needed by an export is missing in the corpus.
+    MissingTypeVariant {
+        name: String,
+        variant_idx: usize,
+        file_path: PathBuf,
+        export: String,
+    },
+    /// A queried export is not present in the corpus.
+    NoSuchExport { name: String },
+    /// A queried type is not present in the corpus.
+    NoSuchType { name: String },
+    /// Multiple errors were found in input data. The `is_truncated` flag indicates that the
+    /// processing was stopped before all data was checked because too many errors were found.
+    Multiple {
+        errors: Vec<Error>,
+        is_truncated: bool,
+    },
 }
 
 impl Error {
@@ -30,12 +101,62 @@ impl Error {
     }
 
     /// Creates a new `Error::Parse`.
-    fn new_parse(desc: &str) -> Self {
-        Error::Parse(desc.to_string())
+    fn new_parse<P: AsRef<Path>>(path: P, line: Option<usize>, desc: &str) -> Self {
+        Error::Parse {
+            path: path.as_ref().to_path_buf(),
+            line,
+            desc: desc.to_string(),
+        }
+    }
+
+    /// Returns the path of the input data which the error relates to, if any.
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            Self::IO { .. }
+            | Self::NoSuchExport { .. }
+            | Self::NoSuchType { .. }
+            | Self::Multiple { .. } => None,
+            Self::Parse { path, .. }
+            | Self::MissingRecordName { path, .. }
+            | Self::DuplicateRecord { path, .. }
+            | Self::DuplicateExport { path, .. }
+            | Self::UnknownType { path, .. }
+            | Self::AmbiguousFileType { path, .. }
+            | Self::UnknownFileType { path, .. } => Some(path),
+            Self::MissingFileType { file_path, .. }
+            | Self::MissingTypeVariant { file_path, .. } => Some(file_path),
+        }
+    }
+
+    /// Returns whether the error is caused by writing to a pipe whose reading end has been closed,
+    /// for instance, by a pager which exited early.
+    pub fn is_broken_pipe(&self) -> bool {
+        match self {
+            Self::IO { io_err, .. } => io_err.kind() == io::ErrorKind::BrokenPipe,
+            _ => false,
+        }
+    }
+
+    /// Returns the 1-based line number in the input data which the error relates to, if any.
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            Self::Parse { line, .. } | Self::DuplicateExport { line, .. } => *line,
+            Self::MissingRecordName { line, .. }
+            | Self::DuplicateRecord { line, .. }
+            | Self::UnknownType { line, .. } => Some(*line),
+            _ => None,
+        }
     }
 }
 
-impl std::error::Error for Error {}
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::IO { io_err, .. } => Some(io_err),
+            _ => None,
+        }
+    }
+}
 
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -44,7 +165,98 @@ impl std::fmt::Display for Error {
                 write!(f, "{}: ", desc)?;
                 io_err.fmt(f)
             }
-            Self::Parse(desc) => write!(f, "{}", desc),
+            Self::Parse { path, line, desc } => match line {
+                Some(line) => write!(f, "{}:{}: {}", path.display(), line, desc),
+                None => write!(f, "{}: {}", path.display(), desc),
+            },
+            Self::MissingRecordName { path, line } => {
+                write!(f, "{}:{}: Expected a record name", path.display(), line)
+            }
+            Self::DuplicateRecord { name, path, line } => {
+                write!(f, "{}:{}: Duplicate record '{}'", path.display(), line, name)
+            }
+            Self::DuplicateExport {
+                name,
+                path,
+                line,
+                previous_path,
+            } => {
+                match line {
+                    Some(line) => write!(f, "{}:{}: ", path.display(), line)?,
+                    None => write!(f, "{}: ", path.display())?,
+                }
+                write!(
+                    f,
+                    "Export '{}' is duplicate. Previous occurrence found in '{}'.",
+                    name,
+                    previous_path.display()
+                )
+            }
+            Self::UnknownType { name, path, line } => {
+                write!(f, "{}:{}: Type '{}' is not known", path.display(), line, name)
+            }
+            Self::AmbiguousFileType {
+                name,
+                path,
+                file_path,
+            } => write!(
+                f,
+                "{}: Type '{}' is implicitly referenced by file '{}' but has multiple variants in the corpus",
+                path.display(),
+                name,
+                file_path.display()
+            ),
+            Self::UnknownFileType {
+                name,
+                path,
+                file_path,
+            } => write!(
+                f,
+                "{}: Type '{}' is referenced by file '{}' but is not known",
+                path.display(),
+                name,
+                file_path.display()
+            ),
+            Self::MissingFileType {
+                name,
+                file_path,
+                export,
+            } => write!(
+                f,
+                "Type '{}' is not known in file '{}', needed by export '{}'",
+                name,
+                file_path.display(),
+                export
+            ),
+            Self::MissingTypeVariant {
+                name,
+                variant_idx,
+                file_path,
+                export,
+            } => write!(
+                f,
+                "Type '{}@{}' has a missing declaration in file '{}', needed by export '{}'",
+                name,
+                variant_idx,
+                file_path.display(),
+                export
+            ),
+            Self::NoSuchExport { name } => write!(f, "Export '{}' is not known", name),
+            Self::NoSuchType { name } => write!(f, "Type '{}' is not known", name),
+            Self::Multiple {
+                errors,
+                is_truncated,
+            } => {
+                if *is_truncated {
+                    write!(f, "Too many errors, stopped after '{}':", errors.len())?;
+                } else {
+                    write!(f, "Found '{}' errors:", errors.len())?;
+                }
+                for err in errors {
+                    write!(f, "\n {}", err)?;
+                }
+                Ok(())
+            }
         }
     }
 }
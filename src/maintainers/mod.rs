@@ -0,0 +1,283 @@
+// Copyright (C) 2025 SUSE LLC <petr.pavlu@suse.com>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use crate::{debug, PathFile};
+use regex::Regex;
+use std::io::{prelude::*, BufReader};
+use std::path::Path;
+
+mod regex;
+
+#[cfg(test)]
+mod tests;
+
+// Notes:
+// [1] The MAINTAINERS file of the kernel describes each subsystem by a section which starts with
+//     its name and continues with lines in form `<tag>:<whitespace><value>`. The `M:` and `L:`
+//     tags list maintainers and mailing lists, the `F:` and `X:` tags list file patterns which are
+//     included and excluded, and the `N:` tag provides a regular expression matched against file
+//     names. The file starts with a preamble which describes the tags, its lines are indented and
+//     so not recognized as tags.
+// [2] The patterns are interpreted in the same way as by get_maintainer.pl. A file pattern can
+//     contain the `*` and `?` wildcards, which match also `/`. A pattern which ends with `/`
+//     matches all files under a directory, otherwise it must match a prefix of the file name and
+//     both must have the same number of path components. Unlike get_maintainer.pl, the
+//     implementation has no access to the source tree and so it additionally treats a pattern
+//     without wildcards which is followed by `/` in the file name as a directory.
+// [3] A subsystem whose `F:` pattern matches a file is ranked by the depth of the pattern, which is
+//     its number of path components, or -1 for a pattern starting with `*`. A match of an `N:`
+//     regular expression is ranked as 0. Only subsystems with the highest rank are considered
+//     responsible for the file, which selects the most specific ones and skips catch-all sections
+//     such as "THE REST". This differs from get_maintainer.pl, which by default reports all
+//     matching subsystems, ordered by their rank. Each change is reported in the group of every
+//     responsible subsystem, so limiting them to the most specific ones keeps the report short.
+// [4] The `N:` regular expressions are parsed by a matcher which supports only a subset of the Perl
+//     syntax. An expression which it cannot parse doesn't fail the whole file, the entry is skipped
+//     and the error is returned as a warning.
+
+/// A single subsystem described by the MAINTAINERS file.
+#[derive(Clone, Debug)]
+pub struct Subsystem {
+    name: String,
+    maintainers: Vec<String>,
+    lists: Vec<String>,
+    files: Vec<String>,
+    excludes: Vec<String>,
+    regexes: Vec<Regex>,
+}
+
+impl Subsystem {
+    /// Creates a new subsystem without any entries.
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            maintainers: Vec::new(),
+            lists: Vec::new(),
+            files: Vec::new(),
+            excludes: Vec::new(),
+            regexes: Vec::new(),
+        }
+    }
+
+    /// Returns the name of the subsystem.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the maintainers of the subsystem, as recorded by its `M:` entries.
+    pub fn maintainers(&self) -> &[String] {
+        &self.maintainers
+    }
+
+    /// Returns the mailing lists of the subsystem, as recorded by its `L:` entries.
+    pub fn lists(&self) -> &[String] {
+        &self.lists
+    }
+
+    /// Returns the rank of the subsystem for a specified file name, or [`None`] if the file is not
+    /// covered by the subsystem [3].
+    fn rank(&self, file_name: &str) -> Option<isize> {
+        if self
+            .excludes
+            .iter()
+            .any(|pattern| match_file_pattern(pattern, file_name))
+        {
+            return None;
+        }
+
+        let file_rank = self
+            .files
+            .iter()
+            .filter(|pattern| match_file_pattern(pattern, file_name))
+            .map(|pattern| pattern_depth(pattern))
+            .max();
+        let regex_rank = self
+            .regexes
+            .iter()
+            .any(|regex| regex.is_match(file_name))
+            .then_some(0);
+        file_rank.max(regex_rank)
+    }
+}
+
+/// Subsystems of the kernel, as described by the MAINTAINERS file.
+#[derive(Clone, Debug, Default)]
+pub struct Maintainers {
+    subsystems: Vec<Subsystem>,
+}
+
+impl Maintainers {
+    /// Creates a new empty collection of subsystems.
+    pub fn new() -> Self {
+        Self {
+            subsystems: Vec::new(),
+        }
+    }
+
+    /// Loads subsystems from a specified MAINTAINERS file.
+    ///
+    /// Entries which cannot be parsed and are skipped are returned as a [`Vec`] of warnings.
+    pub fn load<P: AsRef<Path>>(&mut self, path: P) -> Result<Vec<crate::Error>, crate::Error> {
+        let path = path.as_ref();
+
+        let file = PathFile::open(path).map_err(|err| {
+            crate::Error::new_io(&format!("Failed to open file '{}'", path.display()), err)
+        })?;
+        self.load_buffer(path, file)
+    }
+
+    /// Loads subsystems from a specified reader.
+    ///
+    /// The `path` should point to the MAINTAINERS file name, indicating the origin of the data.
+    /// Entries which cannot be parsed and are skipped are returned as a [`Vec`] of warnings.
+    pub fn load_buffer<P: AsRef<Path>, R: Read>(
+        &mut self,
+        path: P,
+        reader: R,
+    ) -> Result<Vec<crate::Error>, crate::Error> {
+        let path = path.as_ref();
+        debug!("Loading '{}'", path.display());
+
+        // Parse the sections of all subsystems [1].
+        let mut subsystems: Vec<Subsystem> = Vec::new();
+        let mut warnings = Vec::new();
+        let mut is_in_section = false;
+        for (line_idx, maybe_line) in BufReader::new(reader).lines().enumerate() {
+            let line = maybe_line.map_err(|err| {
+                crate::Error::new_io(&format!("Failed to read file '{}'", path.display()), err)
+            })?;
+
+            if line.trim().is_empty() {
+                is_in_section = false;
+                continue;
+            }
+
+            let (tag, value) = match parse_tag(&line) {
+                Some(tag_value) => tag_value,
+                None => {
+                    // A line which doesn't have a tag starts a new section.
+                    subsystems.push(Subsystem::new(line.trim()));
+                    is_in_section = true;
+                    continue;
+                }
+            };
+            let subsystem = match subsystems.last_mut() {
+                Some(subsystem) if is_in_section => subsystem,
+                _ => continue,
+            };
+            match tag {
+                'M' => subsystem.maintainers.push(value.to_string()),
+                'L' => subsystem.lists.push(value.to_string()),
+                'F' => subsystem.files.push(value.to_string()),
+                'X' => subsystem.excludes.push(value.to_string()),
+                'N' => match Regex::new(value) {
+                    Ok(regex) => subsystem.regexes.push(regex),
+                    Err(err) => {
+                        // Skip an expression which cannot be parsed [4].
+                        let warning = crate::Error::new_parse(
+                            path,
+                            Some(line_idx + 1),
+                            &format!("Invalid regular expression '{}': {}", value, err),
+                        );
+                        debug!("Skipping an entry: {}", warning);
+                        warnings.push(warning);
+                    }
+                },
+                _ => {}
+            }
+        }
+
+        // Keep only sections which describe some files, which skips also the preamble.
+        self.subsystems.extend(
+            subsystems
+                .into_iter()
+                .filter(|subsystem| !subsystem.files.is_empty() || !subsystem.regexes.is_empty()),
+        );
+        Ok(warnings)
+    }
+
+    /// Returns all subsystems.
+    pub fn subsystems(&self) -> &[Subsystem] {
+        &self.subsystems
+    }
+
+    /// Returns indices of the subsystems which are responsible for a specified source file.
+    ///
+    /// The file name should be relative to the root of the kernel source tree. The result is empty
+    /// if no subsystem covers the file.
+    pub fn find_subsystems(&self, file_name: &str) -> Vec<usize> {
+        let ranks = self
+            .subsystems
+            .iter()
+            .map(|subsystem| subsystem.rank(file_name))
+            .collect::<Vec<_>>();
+        let best_rank = match ranks.iter().flatten().max() {
+            Some(&best_rank) => best_rank,
+            None => return Vec::new(),
+        };
+        ranks
+            .iter()
+            .enumerate()
+            .filter(|(_, &rank)| rank == Some(best_rank))
+            .map(|(i, _)| i)
+            .collect()
+    }
+}
+
+/// Parses a line in form `<tag>:<whitespace><value>` and returns its tag and value.
+fn parse_tag(line: &str) -> Option<(char, &str)> {
+    let mut chars = line.chars();
+    let tag = chars.next()?;
+    if !tag.is_ascii_uppercase() || chars.next()? != ':' {
+        return None;
+    }
+    let value = chars.as_str();
+    if !value.starts_with(|ch: char| ch.is_ascii_whitespace()) {
+        return None;
+    }
+    Some((tag, value.trim()))
+}
+
+/// Returns whether a file pattern from an `F:` or `X:` entry matches a specified file name [2].
+fn match_file_pattern(pattern: &str, file_name: &str) -> bool {
+    if pattern.ends_with('/') {
+        return match_glob_prefix(pattern.as_bytes(), file_name.as_bytes());
+    }
+    if !pattern.contains(['*', '?'])
+        && file_name
+            .strip_prefix(pattern)
+            .is_some_and(|rest| rest.starts_with('/'))
+    {
+        return true;
+    }
+    match_glob_prefix(pattern.as_bytes(), file_name.as_bytes())
+        && pattern.matches('/').count() == file_name.matches('/').count()
+}
+
+/// Returns whether a glob pattern with the `*` and `?` wildcards matches a prefix of a specified
+/// file name.
+fn match_glob_prefix(pattern: &[u8], file_name: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => true,
+        Some((b'*', rest)) => {
+            (0..=file_name.len()).any(|i| match_glob_prefix(rest, &file_name[i..]))
+        }
+        Some((b'?', rest)) => !file_name.is_empty() && match_glob_prefix(rest, &file_name[1..]),
+        Some((ch, rest)) => {
+            file_name.first() == Some(ch) && match_glob_prefix(rest, &file_name[1..])
+        }
+    }
+}
+
+/// Returns the depth of a file pattern, which is used to rank matching subsystems [3].
+fn pattern_depth(pattern: &str) -> isize {
+    if pattern.starts_with('*') {
+        return -1;
+    }
+    let depth = pattern.matches('/').count() as isize;
+    if pattern.ends_with('/') {
+        depth
+    } else {
+        depth + 1
+    }
+}
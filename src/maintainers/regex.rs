@@ -0,0 +1,337 @@
+// Copyright (C) 2025 SUSE LLC <petr.pavlu@suse.com>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+// Notes:
+// [1] The `N:` patterns in the MAINTAINERS file are Perl regular expressions which
+//     get_maintainer.pl applies with the `x` modifier. The module implements a small backtracking
+//     matcher for the subset of the syntax that is used in practice: literals, `.`, character
+//     classes, groups, alternation, greedy and lazy quantifiers, and the `^`, `$` and `\b`
+//     assertions. Unescaped whitespace outside of character classes is ignored and `#` starts
+//     a comment, as required by the `x` modifier.
+
+/// A single element of a regular expression.
+#[derive(Clone, Debug)]
+enum Node {
+    Byte(u8),
+    Any,
+    Class {
+        ranges: Vec<(u8, u8)>,
+        negated: bool,
+    },
+    Start,
+    End,
+    WordBoundary,
+    Group(Vec<Vec<Node>>),
+    Repeat {
+        node: Box<Node>,
+        min: usize,
+        max: Option<usize>,
+    },
+}
+
+/// A compiled regular expression, as a list of alternatives.
+#[derive(Clone, Debug)]
+pub(crate) struct Regex {
+    alternatives: Vec<Vec<Node>>,
+}
+
+impl Regex {
+    /// Compiles a specified pattern in the extended syntax of Perl [1].
+    pub(crate) fn new(pattern: &str) -> Result<Self, String> {
+        let mut parser = Parser {
+            pattern: pattern.as_bytes(),
+            pos: 0,
+        };
+        let alternatives = parser.parse_alternatives()?;
+        if parser.pos < parser.pattern.len() {
+            return Err("unmatched ')'".to_string());
+        }
+        Ok(Self { alternatives })
+    }
+
+    /// Returns whether the regular expression matches any part of the specified text.
+    pub(crate) fn is_match(&self, text: &str) -> bool {
+        let text = text.as_bytes();
+        (0..=text.len()).any(|start| {
+            self.alternatives
+                .iter()
+                .any(|seq| match_seq(seq, text, start, &mut |_| true))
+        })
+    }
+}
+
+/// A recursive descent parser of a regular expression.
+struct Parser<'a> {
+    pattern: &'a [u8],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    /// Returns the next significant byte without consuming it, skipping whitespace and comments.
+    fn peek(&mut self) -> Option<u8> {
+        while let Some(&ch) = self.pattern.get(self.pos) {
+            if ch.is_ascii_whitespace() {
+                self.pos += 1;
+            } else if ch == b'#' {
+                while self.pattern.get(self.pos).is_some_and(|&ch| ch != b'\n') {
+                    self.pos += 1;
+                }
+            } else {
+                return Some(ch);
+            }
+        }
+        None
+    }
+
+    /// Consumes and returns the next raw byte.
+    fn next_raw(&mut self) -> Option<u8> {
+        let ch = self.pattern.get(self.pos).copied();
+        if ch.is_some() {
+            self.pos += 1;
+        }
+        ch
+    }
+
+    /// Parses alternatives separated by `|`, up to the end of the pattern or a closing `)`.
+    fn parse_alternatives(&mut self) -> Result<Vec<Vec<Node>>, String> {
+        let mut alternatives = vec![self.parse_seq()?];
+        while self.peek() == Some(b'|') {
+            self.pos += 1;
+            alternatives.push(self.parse_seq()?);
+        }
+        Ok(alternatives)
+    }
+
+    /// Parses a sequence of possibly quantified atoms.
+    fn parse_seq(&mut self) -> Result<Vec<Node>, String> {
+        let mut seq = Vec::new();
+        while let Some(ch) = self.peek() {
+            if ch == b'|' || ch == b')' {
+                break;
+            }
+            let atom = self.parse_atom()?;
+            seq.push(self.parse_quantifier(atom)?);
+        }
+        Ok(seq)
+    }
+
+    /// Parses a single atom.
+    fn parse_atom(&mut self) -> Result<Node, String> {
+        let ch = self.next_raw().unwrap();
+        match ch {
+            b'.' => Ok(Node::Any),
+            b'^' => Ok(Node::Start),
+            b'$' => Ok(Node::End),
+            b'[' => self.parse_class(),
+            b'(' => {
+                if self.pattern[self.pos..].starts_with(b"?:") {
+                    self.pos += 2;
+                } else if self.pattern.get(self.pos) == Some(&b'?') {
+                    return Err("unsupported group".to_string());
+                }
+                let alternatives = self.parse_alternatives()?;
+                if self.peek() != Some(b')') {
+                    return Err("missing ')'".to_string());
+                }
+                self.pos += 1;
+                Ok(Node::Group(alternatives))
+            }
+            b'\\' => match self.next_raw() {
+                Some(b'b') => Ok(Node::WordBoundary),
+                Some(ch) => Ok(escape_node(ch)),
+                None => Err("trailing '\\'".to_string()),
+            },
+            b'*' | b'+' | b'?' | b'{' => Err(format!("nothing to repeat before '{}'", ch as char)),
+            _ => Ok(Node::Byte(ch)),
+        }
+    }
+
+    /// Parses a character class, after its opening `[`.
+    fn parse_class(&mut self) -> Result<Node, String> {
+        let negated = self.pattern.get(self.pos) == Some(&b'^');
+        if negated {
+            self.pos += 1;
+        }
+
+        let mut ranges = Vec::new();
+        let mut is_first = true;
+        loop {
+            let ch = self.next_raw().ok_or("missing ']'")?;
+            if ch == b']' && !is_first {
+                break;
+            }
+            is_first = false;
+
+            let low = if ch == b'\\' {
+                let ch = self.next_raw().ok_or("missing ']'")?;
+                match escape_node(ch) {
+                    Node::Byte(ch) => ch,
+                    Node::Class {
+                        ranges: class_ranges,
+                        ..
+                    } => {
+                        ranges.extend(class_ranges);
+                        continue;
+                    }
+                    _ => unreachable!(),
+                }
+            } else {
+                ch
+            };
+
+            // Handle a range, a trailing `-` is a literal.
+            if self.pattern.get(self.pos) == Some(&b'-')
+                && self.pattern.get(self.pos + 1).is_some_and(|&ch| ch != b']')
+            {
+                self.pos += 1;
+                let high = match self.next_raw().unwrap() {
+                    b'\\' => self.next_raw().ok_or("missing ']'")?,
+                    ch => ch,
+                };
+                if high < low {
+                    return Err("invalid range in a character class".to_string());
+                }
+                ranges.push((low, high));
+            } else {
+                ranges.push((low, low));
+            }
+        }
+        Ok(Node::Class { ranges, negated })
+    }
+
+    /// Parses an optional quantifier which applies to a specified atom.
+    fn parse_quantifier(&mut self, atom: Node) -> Result<Node, String> {
+        let (min, max) = match self.peek() {
+            Some(b'*') => (0, None),
+            Some(b'+') => (1, None),
+            Some(b'?') => (0, Some(1)),
+            Some(b'{') => return self.parse_counted(atom),
+            _ => return Ok(atom),
+        };
+        self.pos += 1;
+        self.skip_lazy();
+        Ok(Node::Repeat {
+            node: Box::new(atom),
+            min,
+            max,
+        })
+    }
+
+    /// Parses a counted quantifier `{m}`, `{m,}` or `{m,n}`. A `{` which doesn't start a valid
+    /// quantifier is a literal, as in Perl.
+    fn parse_counted(&mut self, atom: Node) -> Result<Node, String> {
+        let rest = &self.pattern[self.pos + 1..];
+        let end = match rest.iter().position(|&ch| ch == b'}') {
+            Some(end) => end,
+            None => return Ok(atom),
+        };
+        let body = String::from_utf8_lossy(&rest[..end]);
+        let (min, max) = match body.split_once(',') {
+            Some((min, "")) => (min.parse().ok(), None),
+            Some((min, max)) => (min.parse().ok(), max.parse().ok()),
+            None => (body.parse().ok(), body.parse().ok()),
+        };
+        let min = match min {
+            Some(min) => min,
+            None => return Ok(atom),
+        };
+        self.pos += end + 2;
+        self.skip_lazy();
+        Ok(Node::Repeat {
+            node: Box::new(atom),
+            min,
+            max,
+        })
+    }
+
+    /// Skips the `?` modifier of a lazy quantifier, which doesn't change whether a text matches.
+    fn skip_lazy(&mut self) {
+        if self.pattern.get(self.pos) == Some(&b'?') {
+            self.pos += 1;
+        }
+    }
+}
+
+/// Returns a node for a character escaped by `\`.
+fn escape_node(ch: u8) -> Node {
+    let (ranges, negated) = match ch {
+        b'd' => (vec![(b'0', b'9')], false),
+        b'D' => (vec![(b'0', b'9')], true),
+        b'w' => (word_ranges(), false),
+        b'W' => (word_ranges(), true),
+        b's' => (vec![(b' ', b' '), (b'\t', b'\r')], false),
+        b'S' => (vec![(b' ', b' '), (b'\t', b'\r')], true),
+        b'n' => return Node::Byte(b'\n'),
+        b't' => return Node::Byte(b'\t'),
+        _ => return Node::Byte(ch),
+    };
+    Node::Class { ranges, negated }
+}
+
+/// Returns the ranges of word characters.
+fn word_ranges() -> Vec<(u8, u8)> {
+    vec![(b'0', b'9'), (b'A', b'Z'), (b'_', b'_'), (b'a', b'z')]
+}
+
+/// Returns whether a specified byte is a word character.
+fn is_word(ch: u8) -> bool {
+    ch.is_ascii_alphanumeric() || ch == b'_'
+}
+
+/// Matches a sequence of nodes at a specified position and calls the continuation `cont` with the
+/// end position of each possible match, until it returns `true`.
+fn match_seq(seq: &[Node], text: &[u8], pos: usize, cont: &mut dyn FnMut(usize) -> bool) -> bool {
+    match seq.split_first() {
+        None => cont(pos),
+        Some((node, rest)) => match_node(node, text, pos, &mut |next_pos| {
+            match_seq(rest, text, next_pos, cont)
+        }),
+    }
+}
+
+/// Matches a single node at a specified position, in the same way as [`match_seq()`].
+fn match_node(node: &Node, text: &[u8], pos: usize, cont: &mut dyn FnMut(usize) -> bool) -> bool {
+    match node {
+        Node::Byte(ch) => text.get(pos) == Some(ch) && cont(pos + 1),
+        Node::Any => text.get(pos).is_some_and(|&ch| ch != b'\n') && cont(pos + 1),
+        Node::Class { ranges, negated } => match text.get(pos) {
+            Some(&ch) => {
+                let is_in = ranges.iter().any(|&(low, high)| low <= ch && ch <= high);
+                is_in != *negated && cont(pos + 1)
+            }
+            None => false,
+        },
+        Node::Start => pos == 0 && cont(pos),
+        Node::End => pos == text.len() && cont(pos),
+        Node::WordBoundary => {
+            let before = pos > 0 && is_word(text[pos - 1]);
+            let after = pos < text.len() && is_word(text[pos]);
+            before != after && cont(pos)
+        }
+        Node::Group(alternatives) => alternatives
+            .iter()
+            .any(|seq| match_seq(seq, text, pos, cont)),
+        Node::Repeat { node, min, max } => match_repeat(node, *min, *max, 0, text, pos, cont),
+    }
+}
+
+/// Greedily matches a repeated node which has been matched `count` times so far.
+fn match_repeat(
+    node: &Node,
+    min: usize,
+    max: Option<usize>,
+    count: usize,
+    text: &[u8],
+    pos: usize,
+    cont: &mut dyn FnMut(usize) -> bool,
+) -> bool {
+    // Try one more repetition first. An empty repetition is not allowed to avoid an endless loop.
+    if max.is_none_or(|max| count < max)
+        && match_node(node, text, pos, &mut |next_pos| {
+            next_pos != pos && match_repeat(node, min, max, count + 1, text, next_pos, cont)
+        })
+    {
+        return true;
+    }
+    count >= min && cont(pos)
+}
@@ -0,0 +1,162 @@
+// Copyright (C) 2025 SUSE LLC <petr.pavlu@suse.com>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use super::*;
+
+/// Loads the MAINTAINERS data from a specified string and returns the result.
+fn load_maintainers(data: &str) -> Maintainers {
+    let mut maintainers = Maintainers::new();
+    let result = maintainers.load_buffer("MAINTAINERS", data.as_bytes());
+    assert!(result.is_ok_and(|warnings| warnings.is_empty()));
+    maintainers
+}
+
+/// Returns names of the subsystems which are responsible for a specified file.
+fn find_names<'a>(maintainers: &'a Maintainers, file_name: &str) -> Vec<&'a str> {
+    maintainers
+        .find_subsystems(file_name)
+        .into_iter()
+        .map(|i| maintainers.subsystems()[i].name())
+        .collect()
+}
+
+#[test]
+fn read_sections() {
+    // Check that subsystems are read from their sections and the preamble is skipped.
+    let maintainers = load_maintainers(concat!(
+        "List of maintainers\n",
+        "===================\n",
+        "\n",
+        "Descriptions of section entries and preferred order\n",
+        "\tM: *Mail* patches to: FullName <address@domain>\n",
+        "\tF: *Files* and directories wildcard patterns.\n",
+        "\n",
+        "FOO DRIVER\n",
+        "M:\tJane Doe <jane@example.com>\n",
+        "M:\tJohn Doe <john@example.com>\n",
+        "L:\tfoo@vger.kernel.org\n",
+        "S:\tMaintained\n",
+        "F:\tdrivers/foo/\n",
+        "\n",
+        "THE REST\n",
+        "M:\tLinus Torvalds <torvalds@linux-foundation.org>\n",
+        "L:\tlinux-kernel@vger.kernel.org\n",
+        "F:\t*\n",
+        "F:\t*/\n", //
+    ));
+    let subsystems = maintainers.subsystems();
+    assert_eq!(subsystems.len(), 2);
+    assert_eq!(subsystems[0].name(), "FOO DRIVER");
+    assert_eq!(
+        subsystems[0].maintainers(),
+        ["Jane Doe <jane@example.com>", "John Doe <john@example.com>"]
+    );
+    assert_eq!(subsystems[0].lists(), ["foo@vger.kernel.org"]);
+    assert_eq!(subsystems[1].name(), "THE REST");
+}
+
+#[test]
+fn find_file_patterns() {
+    // Check that files are matched by the most specific F: patterns and X: patterns exclude them.
+    let maintainers = load_maintainers(concat!(
+        "FOO DRIVER\n",
+        "F:\tdrivers/foo/\n",
+        "X:\tdrivers/foo/bar/\n",
+        "\n",
+        "FOO BAR DRIVER\n",
+        "F:\tdrivers/foo/bar\n",
+        "\n",
+        "FOO HEADERS\n",
+        "F:\tinclude/linux/foo*.h\n",
+        "\n",
+        "THE REST\n",
+        "F:\t*\n",
+        "F:\t*/\n", //
+    ));
+    assert_eq!(
+        find_names(&maintainers, "drivers/foo/main.c"),
+        ["FOO DRIVER"]
+    );
+    assert_eq!(
+        find_names(&maintainers, "drivers/foo/bar/main.c"),
+        ["FOO BAR DRIVER"]
+    );
+    assert_eq!(
+        find_names(&maintainers, "include/linux/foo_bar.h"),
+        ["FOO HEADERS"]
+    );
+    assert_eq!(
+        find_names(&maintainers, "include/linux/sub/foo.h"),
+        ["THE REST"]
+    );
+    assert_eq!(find_names(&maintainers, "Makefile"), ["THE REST"]);
+}
+
+#[test]
+fn find_regex_patterns() {
+    // Check that files are matched by N: regular expressions.
+    let maintainers = load_maintainers(concat!(
+        "ARM/FOO SOC\n",
+        "N:\t[^a-z]foo\n",
+        "N:\t\\bsun[0-9]+i\n",
+        "\n",
+        "BAR DRIVERS\n",
+        "N:\t(?:bar|baz)_(core | main)\\.c$\n",
+        "\n",
+        "THE REST\n",
+        "F:\t*/\n", //
+    ));
+    assert_eq!(
+        find_names(&maintainers, "drivers/clk/clk-foo.c"),
+        ["ARM/FOO SOC"]
+    );
+    assert_eq!(
+        find_names(&maintainers, "drivers/clk/clkfoo.c"),
+        ["THE REST"]
+    );
+    assert_eq!(
+        find_names(&maintainers, "drivers/clk/sun50i/ccu.c"),
+        ["ARM/FOO SOC"]
+    );
+    assert_eq!(
+        find_names(&maintainers, "drivers/net/baz_main.c"),
+        ["BAR DRIVERS"]
+    );
+    assert_eq!(
+        find_names(&maintainers, "drivers/net/baz_main.h"),
+        ["THE REST"]
+    );
+}
+
+#[test]
+fn read_invalid_regex() {
+    // Check that an invalid or unsupported N: regular expression is skipped with a warning and
+    // the other entries are still used.
+    let mut maintainers = Maintainers::new();
+    let result = maintainers.load_buffer(
+        "MAINTAINERS",
+        concat!(
+            "FOO DRIVER\n",
+            "N:\tfoo(\n",
+            "N:\t(?i)foo\n",
+            "F:\tdrivers/foo/\n", //
+        )
+        .as_bytes(),
+    );
+    let warnings = result.unwrap();
+    assert_eq!(
+        warnings
+            .iter()
+            .map(|warning| warning.to_string())
+            .collect::<Vec<_>>(),
+        [
+            "MAINTAINERS:2: Invalid regular expression 'foo(': missing ')'",
+            "MAINTAINERS:3: Invalid regular expression '(?i)foo': unsupported group",
+        ]
+    );
+    assert_eq!(
+        find_names(&maintainers, "drivers/foo/foo.c"),
+        ["FOO DRIVER"]
+    );
+    assert_eq!(find_names(&maintainers, "lib/foo.c"), [] as [&str; 0]);
+}
@@ -0,0 +1,412 @@
+// Copyright (C) 2025 SUSE LLC <petr.pavlu@suse.com>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use crate::debug;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::{fs, thread};
+
+#[cfg(test)]
+mod tests;
+
+// Notes:
+// [1] The scanner doesn't preprocess or fully parse C. It splits a file into identifiers and
+//     punctuation, skipping comments, literals and preprocessor directives, and then looks for
+//     patterns which start a definition. This is fast enough to scan the whole kernel tree and
+//     reliable for the usual coding style of the kernel.
+// [2] A structure, union or enumeration is defined by `struct|union|enum <name> {`, possibly with
+//     attributes such as `__packed` or `__attribute__((...))` before the name.
+// [3] The name of a typedef is the last identifier of its declaration, after removing any trailing
+//     attributes and array dimensions. A trailing identifier starting with `__` is considered an
+//     attribute only if it follows a name which is neither an attribute nor a base type, which
+//     keeps typedefs such as `typedef unsigned int __u32;` and `typedef __u32 __bitwise __be32;`.
+//     A declaration which contains `(*<name>)` declares a function pointer type and one which
+//     contains `<name>(` a function type.
+
+/// A location of a type definition, as a path relative to the root of the source tree and
+/// a 1-based line number.
+pub type SourceLocation = (PathBuf, usize);
+
+/// An index of type definitions in a kernel source tree.
+///
+/// Types are identified by their names in the symtypes format, such as `s#foo` for `struct foo`.
+/// The tree is scanned only once when the index is created and all queries are then answered from
+/// the index.
+#[derive(Clone, Debug, Default)]
+pub struct SourceIndex {
+    definitions: HashMap<String, Vec<SourceLocation>>,
+}
+
+impl SourceIndex {
+    /// Creates a new empty index.
+    pub fn new() -> Self {
+        Self {
+            definitions: HashMap::new(),
+        }
+    }
+
+    /// Scans all `.c` and `.h` files in a specified source tree and records type definitions found
+    /// in them.
+    pub fn scan<P: AsRef<Path>>(&mut self, root: P, num_workers: i32) -> Result<(), crate::Error> {
+        let root = root.as_ref();
+        debug!("Scanning source tree '{}'", root.display());
+
+        let mut files = Vec::new();
+        collect_sources(root, Path::new(""), &mut files)?;
+
+        // Scan the files in parallel and then merge the results in the order of the files to make
+        // the result deterministic.
+        let next_work_idx = AtomicUsize::new(0);
+        let results = Mutex::new(Vec::new());
+        thread::scope(|s| {
+            for _ in 0..num_workers {
+                s.spawn(|| loop {
+                    let work_idx = next_work_idx.fetch_add(1, Ordering::Relaxed);
+                    if work_idx >= files.len() {
+                        return;
+                    }
+                    let path = root.join(&files[work_idx]);
+                    let result =
+                        fs::read(&path)
+                            .map(|data| scan_definitions(&data))
+                            .map_err(|err| {
+                                crate::Error::new_io(
+                                    &format!("Failed to read file '{}'", path.display()),
+                                    err,
+                                )
+                            });
+                    results.lock().unwrap().push((work_idx, result));
+                });
+            }
+        });
+
+        let mut results = results.into_inner().unwrap();
+        results.sort_by_key(|(work_idx, _)| *work_idx);
+        for (work_idx, result) in results {
+            for (name, line) in result? {
+                self.definitions
+                    .entry(name)
+                    .or_default()
+                    .push((files[work_idx].clone(), line));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns all known locations of the definition of a specified type, sorted by their path and
+    /// line.
+    pub fn find(&self, type_name: &str) -> &[SourceLocation] {
+        self.definitions
+            .get(type_name)
+            .map_or(&[], |locations| locations.as_slice())
+    }
+}
+
+/// Collects recursively all `.c` and `.h` files under the given root path and its subpath. Hidden
+/// directories, such as `.git`, and symbolic links are skipped.
+fn collect_sources(
+    root: &Path,
+    sub_path: &Path,
+    files: &mut Vec<PathBuf>,
+) -> Result<(), crate::Error> {
+    let path = root.join(sub_path);
+
+    let dir_iter = fs::read_dir(&path).map_err(|err| {
+        crate::Error::new_io(
+            &format!("Failed to read directory '{}'", path.display()),
+            err,
+        )
+    })?;
+
+    let mut entries = Vec::new();
+    for maybe_entry in dir_iter {
+        let entry = maybe_entry.map_err(|err| {
+            crate::Error::new_io(
+                &format!("Failed to read directory '{}'", path.display()),
+                err,
+            )
+        })?;
+        entries.push(entry);
+    }
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        let file_name = entry.file_name();
+        let file_name = match file_name.to_str() {
+            Some(file_name) if !file_name.starts_with('.') => file_name,
+            _ => continue,
+        };
+
+        let entry_path = entry.path();
+        let md = fs::symlink_metadata(&entry_path).map_err(|err| {
+            crate::Error::new_io(
+                &format!("Failed to query path '{}'", entry_path.display()),
+                err,
+            )
+        })?;
+
+        let entry_sub_path = sub_path.join(file_name);
+        if md.is_dir() {
+            collect_sources(root, &entry_sub_path, files)?;
+        } else if md.is_file() && (file_name.ends_with(".c") || file_name.ends_with(".h")) {
+            files.push(entry_sub_path);
+        }
+    }
+    Ok(())
+}
+
+/// A token of C source code, recording its 1-based line number.
+#[derive(Debug, PartialEq)]
+enum CToken<'a> {
+    Ident(&'a str, usize),
+    Punct(u8),
+}
+
+/// Splits C source code into identifiers and punctuation, skipping whitespace, comments, literals
+/// and preprocessor directives [1].
+fn tokenize(data: &[u8]) -> Vec<CToken<'_>> {
+    let mut tokens = Vec::new();
+    let mut line = 1;
+    let mut is_line_start = true;
+    let mut i = 0;
+    while i < data.len() {
+        let ch = data[i];
+        if ch == b'\n' {
+            line += 1;
+            is_line_start = true;
+            i += 1;
+            continue;
+        }
+        if ch.is_ascii_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        if ch == b'#' && is_line_start {
+            // Skip a preprocessor directive, including its continuation lines.
+            while i < data.len() && data[i] != b'\n' {
+                if data[i] == b'\\' && data.get(i + 1) == Some(&b'\n') {
+                    line += 1;
+                    i += 1;
+                }
+                i += 1;
+            }
+            continue;
+        }
+        is_line_start = false;
+
+        if data[i..].starts_with(b"//") {
+            while i < data.len() && data[i] != b'\n' {
+                i += 1;
+            }
+        } else if data[i..].starts_with(b"/*") {
+            i += 2;
+            while i < data.len() && !data[i..].starts_with(b"*/") {
+                if data[i] == b'\n' {
+                    line += 1;
+                }
+                i += 1;
+            }
+            i += 2;
+        } else if ch == b'"' || ch == b'\'' {
+            i += 1;
+            while i < data.len() && data[i] != ch && data[i] != b'\n' {
+                if data[i] == b'\\' {
+                    i += 1;
+                }
+                i += 1;
+            }
+            i += 1;
+        } else if ch.is_ascii_alphanumeric() || ch == b'_' {
+            let start = i;
+            while i < data.len() && (data[i].is_ascii_alphanumeric() || data[i] == b'_') {
+                i += 1;
+            }
+            // Numbers are not needed, the identifiers are ASCII and so valid UTF-8.
+            if !ch.is_ascii_digit() {
+                let word = std::str::from_utf8(&data[start..i]).unwrap();
+                tokens.push(CToken::Ident(word, line));
+            }
+        } else {
+            tokens.push(CToken::Punct(ch));
+            i += 1;
+        }
+    }
+    tokens
+}
+
+/// Returns whether a token is a specific punctuation character.
+fn is_punct(token: Option<&CToken>, ch: u8) -> bool {
+    token == Some(&CToken::Punct(ch))
+}
+
+/// Returns the index after a balanced group which starts with `open` at a specified index, or the
+/// same index if no such group starts there.
+fn skip_group(tokens: &[CToken], start: usize, open: u8, close: u8) -> usize {
+    if !is_punct(tokens.get(start), open) {
+        return start;
+    }
+    let mut depth = 0;
+    for (i, token) in tokens.iter().enumerate().skip(start) {
+        if *token == CToken::Punct(open) {
+            depth += 1;
+        } else if *token == CToken::Punct(close) {
+            depth -= 1;
+            if depth == 0 {
+                return i + 1;
+            }
+        }
+    }
+    tokens.len()
+}
+
+/// Returns whether an identifier is an attribute or another annotation reserved by the kernel,
+/// such as `__packed` or `__attribute__`.
+fn is_attribute(name: &str) -> bool {
+    name.starts_with("__")
+}
+
+/// Returns whether an identifier is a keyword of a C base type, such as `int` or `unsigned`.
+fn is_base_type(name: &str) -> bool {
+    matches!(
+        name,
+        "char"
+            | "short"
+            | "int"
+            | "long"
+            | "signed"
+            | "unsigned"
+            | "float"
+            | "double"
+            | "void"
+            | "_Bool"
+    )
+}
+
+/// Finds definitions of structures, unions, enumerations and typedefs in C source code and
+/// returns their symtypes names with 1-based line numbers.
+fn scan_definitions(data: &[u8]) -> Vec<(String, usize)> {
+    let tokens = tokenize(data);
+    let mut definitions = Vec::new();
+    for (i, token) in tokens.iter().enumerate() {
+        let keyword = match token {
+            CToken::Ident(keyword, _) => *keyword,
+            CToken::Punct(_) => continue,
+        };
+        let prefix = match keyword {
+            "struct" => "s#",
+            "union" => "u#",
+            "enum" => "e#",
+            "typedef" => {
+                if let Some((name, line)) = scan_typedef(&tokens[i + 1..]) {
+                    definitions.push((format!("t#{}", name), line));
+                }
+                continue;
+            }
+            _ => continue,
+        };
+
+        // Skip any attributes before the name [2].
+        let mut j = i + 1;
+        while let Some(CToken::Ident(name, _)) = tokens.get(j) {
+            if !is_attribute(name) || !is_punct(tokens.get(j + 1), b'(') {
+                break;
+            }
+            j = skip_group(&tokens, j + 1, b'(', b')');
+        }
+        while let Some(CToken::Ident(name, _)) = tokens.get(j) {
+            if !is_attribute(name) || !matches!(tokens.get(j + 1), Some(CToken::Ident(..))) {
+                break;
+            }
+            j += 1;
+        }
+
+        if let (Some(CToken::Ident(name, line)), true) =
+            (tokens.get(j), is_punct(tokens.get(j + 1), b'{'))
+        {
+            definitions.push((format!("{}{}", prefix, name), *line));
+        }
+    }
+    definitions
+}
+
+/// Determines the name of a typedef from the tokens which follow the `typedef` keyword [3].
+fn scan_typedef<'a>(tokens: &[CToken<'a>]) -> Option<(&'a str, usize)> {
+    // Collect the tokens of the declaration which are outside any braces.
+    let mut decl = Vec::new();
+    let mut i = 0;
+    loop {
+        match tokens.get(i)? {
+            CToken::Punct(b';') => break,
+            CToken::Punct(b'{') => i = skip_group(tokens, i, b'{', b'}'),
+            CToken::Punct(b'}') => return None,
+            token => {
+                decl.push(token);
+                i += 1;
+            }
+        }
+    }
+
+    // Remove trailing attributes and array dimensions.
+    loop {
+        match decl.as_slice() {
+            [.., CToken::Punct(b']')] => {
+                let open = decl
+                    .iter()
+                    .rposition(|token| **token == CToken::Punct(b'['))?;
+                decl.truncate(open);
+            }
+            [.., CToken::Ident(prev, _), CToken::Ident(name, _)]
+                if is_attribute(name) && !is_attribute(prev) && !is_base_type(prev) =>
+            {
+                decl.pop();
+            }
+            [.., CToken::Punct(b')')] => {
+                let mut depth = 0;
+                let mut open = None;
+                for (k, token) in decl.iter().enumerate().rev() {
+                    match token {
+                        CToken::Punct(b')') => depth += 1,
+                        CToken::Punct(b'(') => {
+                            depth -= 1;
+                            if depth == 0 {
+                                open = Some(k);
+                                break;
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                let open = open?;
+                match decl.get(open.wrapping_sub(1)) {
+                    Some(CToken::Ident(name, _)) if is_attribute(name) => decl.truncate(open - 1),
+                    _ => break,
+                }
+            }
+            _ => break,
+        }
+    }
+
+    // Handle function pointer and function types.
+    if let Some(open) = decl.iter().position(|token| **token == CToken::Punct(b'(')) {
+        let mut k = open + 1;
+        while is_punct(decl.get(k).copied(), b'*') {
+            k += 1;
+        }
+        if k > open + 1 {
+            if let Some(CToken::Ident(name, line)) = decl.get(k) {
+                return Some((name, *line));
+            }
+        }
+        if let Some(CToken::Ident(name, line)) = open.checked_sub(1).and_then(|k| decl.get(k)) {
+            return Some((name, *line));
+        }
+    }
+
+    match decl.last()? {
+        CToken::Ident(name, line) => Some((name, *line)),
+        CToken::Punct(_) => None,
+    }
+}
@@ -0,0 +1,82 @@
+// Copyright (C) 2025 SUSE LLC <petr.pavlu@suse.com>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use super::*;
+
+/// Scans definitions in the specified C source code.
+fn scan(data: &str) -> Vec<(String, usize)> {
+    scan_definitions(data.as_bytes())
+}
+
+#[test]
+fn scan_structs() {
+    // Check that definitions of structures, unions and enumerations are found.
+    let definitions = scan(concat!(
+        "struct foo;\n",
+        "struct foo {\n",
+        "\tint a;\n",
+        "\tunion bar {\n",
+        "\t\tint b;\n",
+        "\t} bar;\n",
+        "\tstruct baz *baz;\n",
+        "};\n",
+        "\n",
+        "enum qux { A, B };\n",
+        "struct __packed packed { char c; };\n",
+        "struct __attribute__((aligned(8))) aligned { char c; };\n",
+        "static int get(struct foo *foo) { return foo->a; }\n", //
+    ));
+    assert_eq!(
+        definitions,
+        [
+            ("s#foo".to_string(), 2),
+            ("u#bar".to_string(), 4),
+            ("e#qux".to_string(), 10),
+            ("s#packed".to_string(), 11),
+            ("s#aligned".to_string(), 12),
+        ]
+    );
+}
+
+#[test]
+fn scan_typedefs() {
+    // Check that definitions of typedefs are found.
+    let definitions = scan(concat!(
+        "typedef unsigned int __u32;\n",
+        "typedef __u32 __bitwise __be32;\n",
+        "typedef struct {\n",
+        "\tint a;\n",
+        "} foo_t;\n",
+        "typedef int (*callback_t)(void *data);\n",
+        "typedef void handler_t(int irq);\n",
+        "typedef u64 aligned_t __aligned(8);\n",
+        "typedef char buf_t[16];\n", //
+    ));
+    assert_eq!(
+        definitions,
+        [
+            ("t#__u32".to_string(), 1),
+            ("t#__be32".to_string(), 2),
+            ("t#foo_t".to_string(), 5),
+            ("t#callback_t".to_string(), 6),
+            ("t#handler_t".to_string(), 7),
+            ("t#aligned_t".to_string(), 8),
+            ("t#buf_t".to_string(), 9),
+        ]
+    );
+}
+
+#[test]
+fn scan_skipped() {
+    // Check that comments, literals and preprocessor directives are skipped.
+    let definitions = scan(concat!(
+        "/* struct comment {\n",
+        " * }; */\n",
+        "// struct line_comment { };\n",
+        "#define DEFINE_FOO(name) \\\n",
+        "\tstruct name { int a; }\n",
+        "static const char *s = \"struct literal { }\";\n",
+        "struct real { int a; };\n", //
+    ));
+    assert_eq!(definitions, [("s#real".to_string(), 7)]);
+}
@@ -0,0 +1,420 @@
+// Copyright (C) 2025 SUSE LLC <petr.pavlu@suse.com>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use super::ctype::{base_type_tokens, push_enumerator, render, CType};
+use super::{
+    Dialect, LoadContext, ParsedFile, ParsedRecords, ParsedSymtypes, SymCorpus, Token, Tokens,
+};
+use crate::btf::{Btf, BtfMember, BtfType};
+use crate::debug;
+use crate::elf::ElfObject;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs;
+use std::path::Path;
+
+// Notes:
+// [1] BTF describes types of the kernel in a compact form which is available even in binary-only
+//     builds. The module converts the types of exported functions and variables to the same form
+//     that genksyms produces. Named structures, unions, enumerations and typedefs become separate
+//     `s#`, `u#`, `e#` and `t#` records, anonymous types are expanded in place. Names of base types
+//     are converted to the same canonical spelling as in types described by gendwarfksyms, because
+//     BTF records them as the compiler names them, for instance `long unsigned int`.
+// [2] BTF of a kernel module is split BTF which extends BTF of vmlinux. Modules can therefore be
+//     loaded only together with their vmlinux.
+
+impl SymCorpus {
+    /// Loads BTF data from vmlinux in a specified directory and from all specified kernel modules,
+    /// given by their paths relative to the directory.
+    ///
+    /// vmlinux is loaded first as it provides the base for BTF of all modules. Any error in it is
+    /// returned immediately, even in the lenient mode. Modules are then loaded in the same way as
+    /// `.symtypes` files.
+    pub(super) fn load_btf_dir<Q: AsRef<Path> + Sync>(
+        &mut self,
+        root: &Path,
+        modules: &[Q],
+        num_workers: i32,
+        is_lenient: bool,
+    ) -> Result<Vec<crate::Error>, crate::Error> {
+        let sub_path = Path::new("vmlinux");
+        let elf = read_elf(root, sub_path)?;
+        let vmlinux_btf = read_btf(&elf, None)?;
+        Self::load_btf(&elf, &vmlinux_btf, &LoadContext::new(self))?;
+
+        self.load_files(
+            modules.len(),
+            num_workers,
+            is_lenient,
+            |work_idx, load_context| {
+                let elf = read_elf(root, modules[work_idx].as_ref())?;
+                let btf = read_btf(&elf, Some(&vmlinux_btf))?;
+                Self::load_btf(&elf, &btf, load_context)
+            },
+        )
+    }
+
+    /// Loads BTF data from a single vmlinux file.
+    pub(super) fn load_btf_file(
+        &mut self,
+        path: &Path,
+        is_lenient: bool,
+    ) -> Result<Vec<crate::Error>, crate::Error> {
+        self.load_files(1, 1, is_lenient, |_, load_context| {
+            let elf = read_elf("", path)?;
+            if elf.section_data(".modinfo").is_some() {
+                return Err(crate::Error::new_parse(
+                    path,
+                    None,
+                    "BTF of a kernel module can be loaded only from a directory which contains also vmlinux",
+                ));
+            }
+            let btf = read_btf(&elf, None)?;
+            Self::load_btf(&elf, &btf, load_context)
+        })
+    }
+
+    /// Converts BTF data of a specified ELF file to symtypes data and adds it to the corpus.
+    pub(super) fn load_btf(
+        elf: &ElfObject,
+        btf: &Btf,
+        load_context: &LoadContext,
+    ) -> Result<(), crate::Error> {
+        let path = elf.path();
+        debug!("Loading BTF from '{}'", path.display());
+
+        let exports = elf.kernel_exports()?;
+        let exports = exports.iter().map(String::as_str).collect::<Vec<_>>();
+
+        let parsed = convert_btf(path, btf, &exports)?;
+        Self::merge_parsed(path, parsed, load_context)
+    }
+}
+
+/// Reads an ELF file, given by its path relative to a root directory.
+fn read_elf<P: AsRef<Path>>(root: P, sub_path: &Path) -> Result<ElfObject, crate::Error> {
+    let path = root.as_ref().join(sub_path);
+    let data = fs::read(&path).map_err(|err| {
+        crate::Error::new_io(&format!("Failed to read file '{}'", path.display()), err)
+    })?;
+    ElfObject::parse(sub_path, data)
+}
+
+/// Parses the `.BTF` section of a specified ELF file.
+fn read_btf<'a>(elf: &ElfObject, base: Option<&'a Btf<'a>>) -> Result<Btf<'a>, crate::Error> {
+    let data = elf
+        .section_data(".BTF")
+        .ok_or_else(|| crate::Error::new_parse(elf.path(), None, "Missing the .BTF section"))?;
+    Btf::parse(elf.path(), data, base)
+}
+
+/// Converts types of the specified exports described by BTF to symtypes data of a single file.
+///
+/// Exports which are not described by BTF are skipped.
+fn convert_btf(path: &Path, btf: &Btf, exports: &[&str]) -> Result<ParsedSymtypes, crate::Error> {
+    // Find functions and variables which can be exported. A global symbol is preferred over
+    // a static one with the same name.
+    let mut symbols = HashMap::new();
+    for (type_id, ty) in btf.types() {
+        let (name, is_global) = match ty {
+            BtfType::Func {
+                name, is_global, ..
+            }
+            | BtfType::Var {
+                name, is_global, ..
+            } => (name.as_str(), *is_global),
+            _ => continue,
+        };
+        if is_global || !symbols.contains_key(name) {
+            symbols.insert(name, type_id);
+        }
+    }
+
+    let mut converter = Converter::new(btf);
+    let mut parsed_exports = Vec::new();
+    for &name in exports {
+        let type_id = match symbols.get(name) {
+            Some(&type_id) => type_id,
+            None => {
+                debug!("Export '{}' is not described by BTF", name);
+                continue;
+            }
+        };
+        converter.convert_export(name, type_id).map_err(|err| {
+            crate::Error::new_parse(
+                path,
+                None,
+                &format!("Failed to convert the type of export '{}': {}", name, err),
+            )
+        })?;
+        parsed_exports.push((name.to_string(), None));
+    }
+
+    let file = ParsedFile {
+        path: path.to_path_buf(),
+        module: Some(binary_module_name(path)),
+        records: converter.records,
+        exports: parsed_exports,
+    };
+    Ok(ParsedSymtypes {
+        types: converter.types,
+        files: vec![file],
+        dialect: Some(Dialect::Genksyms),
+    })
+}
+
+/// A converter of BTF types to symtypes records.
+struct Converter<'a> {
+    btf: &'a Btf<'a>,
+    /// All created records, as a tuple of the record name and its tokens.
+    types: Vec<(String, Tokens)>,
+    /// A mapping from a record name to an index in `types`.
+    records: ParsedRecords,
+    /// Records which are referenced but not yet defined, with the BTF type which describes them.
+    pending: VecDeque<(usize, u32)>,
+    /// BTF types which are currently being converted, to detect types which reference themselves.
+    active: HashSet<u32>,
+}
+
+impl<'a> Converter<'a> {
+    /// Creates a new converter of the specified BTF data.
+    fn new(btf: &'a Btf<'a>) -> Self {
+        Self {
+            btf,
+            types: Vec::new(),
+            records: ParsedRecords::new(),
+            pending: VecDeque::new(),
+            active: HashSet::new(),
+        }
+    }
+
+    /// Adds a record for an export, described by a BTF function or variable, and records for all
+    /// named types that it references.
+    ///
+    /// The referenced records are defined iteratively, rather than recursively, to avoid a deep
+    /// recursion on long chains of types.
+    fn convert_export(&mut self, name: &str, type_id: u32) -> Result<(), String> {
+        let ty = match self.get(type_id)? {
+            BtfType::Func { type_id, .. } | BtfType::Var { type_id, .. } => {
+                self.convert(*type_id)?
+            }
+            _ => return Err(format!("Type {} is not a function or variable", type_id)),
+        };
+        self.add_record(name, render(ty, vec![Token::new_atom(name)]));
+
+        while let Some((type_idx, type_id)) = self.pending.pop_front() {
+            let tokens = self.convert_record(type_id)?;
+            self.types[type_idx].1 = tokens;
+        }
+        Ok(())
+    }
+
+    /// Adds a new record and returns its index in `types`.
+    fn add_record(&mut self, name: &str, tokens: Tokens) -> usize {
+        self.records.insert(name.to_string(), self.types.len());
+        self.types.push((name.to_string(), tokens));
+        self.types.len() - 1
+    }
+
+    /// Looks up a BTF type by its ID.
+    fn get(&self, type_id: u32) -> Result<&'a BtfType, String> {
+        self.btf
+            .get(type_id)
+            .ok_or_else(|| format!("Unknown BTF type {}", type_id))
+    }
+
+    /// Returns a reference to a named record, scheduling its definition if it is not yet known.
+    fn reference(&mut self, name: String, type_id: u32) -> CType {
+        if !self.records.contains_key(&name) {
+            let type_idx = self.add_record(&name, Tokens::new());
+            self.pending.push_back((type_idx, type_id));
+        }
+        CType::Named(vec![Token::new_typeref(name)])
+    }
+
+    /// Converts a BTF type as it is used in a declaration.
+    ///
+    /// Named types are referenced by their records, so only anonymous types are converted
+    /// recursively. Valid BTF cannot describe an anonymous type which references itself, such
+    /// a cycle is reported as an error instead of recursing endlessly.
+    fn convert(&mut self, type_id: u32) -> Result<CType, String> {
+        if !self.active.insert(type_id) {
+            return Err(format!("Type {} references itself", type_id));
+        }
+        let res = self.convert_unchecked(type_id);
+        self.active.remove(&type_id);
+        res
+    }
+
+    /// Converts a BTF type as it is used in a declaration, without checking for cycles.
+    fn convert_unchecked(&mut self, type_id: u32) -> Result<CType, String> {
+        let ty = match self.get(type_id)? {
+            BtfType::Void => CType::Named(vec![Token::new_atom("void")]),
+            BtfType::Int { name } | BtfType::Float { name } => CType::Named(base_type_tokens(name)),
+            BtfType::Pointer { type_id } => CType::Pointer(Box::new(self.convert(*type_id)?)),
+            BtfType::Array { type_id, num_elems } => {
+                // A flexible array member is recorded with zero elements.
+                let count = if *num_elems == 0 {
+                    None
+                } else {
+                    Some(num_elems.to_string())
+                };
+                CType::Array(Box::new(self.convert(*type_id)?), count)
+            }
+            BtfType::Struct {
+                name: Some(name), ..
+            } => self.reference(format!("s#{}", name), type_id),
+            BtfType::Union {
+                name: Some(name), ..
+            } => self.reference(format!("u#{}", name), type_id),
+            BtfType::Enum {
+                name: Some(name), ..
+            } => self.reference(format!("e#{}", name), type_id),
+            BtfType::Struct {
+                name: None,
+                members,
+            } => CType::Named(self.convert_aggregate("struct", None, members)?),
+            BtfType::Union {
+                name: None,
+                members,
+            } => CType::Named(self.convert_aggregate("union", None, members)?),
+            BtfType::Enum { name: None, values } => CType::Named(convert_enum(None, values)),
+            BtfType::Fwd { name, is_union } => {
+                // Refer to the complete type if it is known, which is the same record.
+                let kind = if *is_union { 'u' } else { 's' };
+                let complete_id = self.btf.find_complete(kind, name).unwrap_or(type_id);
+                self.reference(format!("{}#{}", kind, name), complete_id)
+            }
+            BtfType::Typedef { name, .. } => self.reference(format!("t#{}", name), type_id),
+            BtfType::Volatile { type_id } => {
+                CType::Qualified("volatile", Box::new(self.convert(*type_id)?))
+            }
+            BtfType::Const { type_id } => {
+                CType::Qualified("const", Box::new(self.convert(*type_id)?))
+            }
+            BtfType::Restrict { type_id } => {
+                CType::Qualified("restrict", Box::new(self.convert(*type_id)?))
+            }
+            // Type tags, such as `__user`, are not visible to genksyms.
+            BtfType::TypeTag { type_id } => self.convert(*type_id)?,
+            BtfType::FuncProto {
+                return_type_id,
+                params,
+            } => {
+                let ret = self.convert(*return_type_id)?;
+                let mut is_variadic = false;
+                let mut converted_params = Vec::new();
+                for (i, param) in params.iter().enumerate() {
+                    if param.type_id == 0 && i == params.len() - 1 {
+                        is_variadic = true;
+                        continue;
+                    }
+                    converted_params.push((self.convert(param.type_id)?, param.name.clone()));
+                }
+                CType::Function(Box::new(ret), converted_params, is_variadic)
+            }
+            BtfType::Func { .. }
+            | BtfType::Var { .. }
+            | BtfType::DataSec { .. }
+            | BtfType::DeclTag { .. } => {
+                return Err(format!("Type {} cannot be used in a declaration", type_id))
+            }
+        };
+        Ok(ty)
+    }
+
+    /// Converts a BTF type which is described by a named record to the tokens of the record.
+    fn convert_record(&mut self, type_id: u32) -> Result<Tokens, String> {
+        match self.get(type_id)? {
+            BtfType::Struct { name, members } => {
+                self.convert_aggregate("struct", name.as_deref(), members)
+            }
+            BtfType::Union { name, members } => {
+                self.convert_aggregate("union", name.as_deref(), members)
+            }
+            BtfType::Enum { name, values } => Ok(convert_enum(name.as_deref(), values)),
+            BtfType::Fwd { name, is_union } => {
+                // The complete type is not known, record an opaque declaration.
+                let keyword = if *is_union { "union" } else { "struct" };
+                Ok(vec![
+                    Token::new_atom(keyword),
+                    Token::new_atom(name),
+                    Token::new_atom("{"),
+                    Token::new_atom("UNKNOWN"),
+                    Token::new_atom("}"),
+                ])
+            }
+            BtfType::Typedef { name, type_id } => {
+                let ty = self.convert(*type_id)?;
+                let mut res = vec![Token::new_atom("typedef")];
+                res.extend(render(ty, vec![Token::new_atom(name)]));
+                Ok(res)
+            }
+            _ => Err(format!("Type {} cannot be described by a record", type_id)),
+        }
+    }
+
+    /// Converts a BTF structure or union to its C definition.
+    fn convert_aggregate(
+        &mut self,
+        keyword: &str,
+        name: Option<&str>,
+        members: &[BtfMember],
+    ) -> Result<Tokens, String> {
+        let mut res = vec![Token::new_atom(keyword)];
+        if let Some(name) = name {
+            res.push(Token::new_atom(name));
+        }
+        res.push(Token::new_atom("{"));
+        for member in members {
+            let ty = self.convert(member.type_id)?;
+            let declarator = member.name.iter().map(Token::new_atom).collect();
+            res.extend(render(ty, declarator));
+            if let Some(bitfield_size) = member.bitfield_size {
+                res.push(Token::new_atom(":"));
+                res.push(Token::new_atom(bitfield_size.to_string()));
+            }
+            res.push(Token::new_atom(";"));
+        }
+        res.push(Token::new_atom("}"));
+        Ok(res)
+    }
+}
+
+/// Converts a BTF enumeration to its C definition.
+fn convert_enum(name: Option<&str>, values: &[(String, i128)]) -> Tokens {
+    let mut res = vec![Token::new_atom("enum")];
+    if let Some(name) = name {
+        res.push(Token::new_atom(name));
+    }
+    res.push(Token::new_atom("{"));
+    if values.is_empty() {
+        // An enumeration without any values is opaque.
+        res.push(Token::new_atom("UNKNOWN"));
+    } else {
+        let mut enumerators = Tokens::new();
+        let mut next_value = Some(0i128);
+        for (name, value) in values {
+            push_enumerator(
+                &mut enumerators,
+                name,
+                Some(&value.to_string()),
+                &mut next_value,
+            );
+        }
+        res.extend(enumerators);
+    }
+    res.push(Token::new_atom("}"));
+    res
+}
+
+/// Returns the name of the module provided by a kernel binary, or `vmlinux` for the kernel itself.
+fn binary_module_name(path: &Path) -> String {
+    match path
+        .file_name()
+        .and_then(|file_name| file_name.to_str())
+        .and_then(|file_name| file_name.strip_suffix(".ko"))
+    {
+        Some(module) => module.to_string(),
+        None => "vmlinux".to_string(),
+    }
+}
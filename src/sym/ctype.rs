@@ -0,0 +1,143 @@
+// Copyright (C) 2025 SUSE LLC <petr.pavlu@suse.com>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use super::{Token, Tokens};
+
+/// A C type, as reconstructed from debugging information.
+pub(super) enum CType {
+    /// A type given directly by its tokens, such as a base type, a type reference or a complete
+    /// structure definition.
+    Named(Tokens),
+    /// A qualified type, with the qualifier being `const`, `volatile`, `restrict` or `_Atomic`.
+    Qualified(&'static str, Box<CType>),
+    Pointer(Box<CType>),
+    /// An array type, with its number of elements if known.
+    Array(Box<CType>, Option<String>),
+    /// A function type, with its return type, parameters and whether it is variadic.
+    Function(Box<CType>, Vec<(CType, Option<String>)>, bool),
+}
+
+/// Renders a C type together with its declarator, which is typically the declared name.
+pub(super) fn render(ty: CType, declarator: Tokens) -> Tokens {
+    match ty {
+        CType::Named(mut tokens) => {
+            tokens.extend(declarator);
+            tokens
+        }
+        CType::Qualified(qualifier, inner) => {
+            if let CType::Named(tokens) = *inner {
+                let mut res = vec![Token::new_atom(qualifier)];
+                res.extend(tokens);
+                res.extend(declarator);
+                return res;
+            }
+            let mut new_declarator = vec![Token::new_atom(qualifier)];
+            new_declarator.extend(declarator);
+            render(*inner, new_declarator)
+        }
+        CType::Pointer(inner) => {
+            let needs_parens = matches!(*inner, CType::Array(..) | CType::Function(..));
+            let mut new_declarator = Tokens::new();
+            if needs_parens {
+                new_declarator.push(Token::new_atom("("));
+            }
+            new_declarator.push(Token::new_atom("*"));
+            new_declarator.extend(declarator);
+            if needs_parens {
+                new_declarator.push(Token::new_atom(")"));
+            }
+            render(*inner, new_declarator)
+        }
+        CType::Array(inner, count) => {
+            let mut new_declarator = declarator;
+            new_declarator.push(Token::new_atom("["));
+            if let Some(count) = count {
+                new_declarator.push(Token::new_atom(count));
+            }
+            new_declarator.push(Token::new_atom("]"));
+            render(*inner, new_declarator)
+        }
+        CType::Function(ret, params, is_variadic) => {
+            let mut new_declarator = declarator;
+            new_declarator.push(Token::new_atom("("));
+            let num_params = params.len();
+            for (i, (param_ty, param_name)) in params.into_iter().enumerate() {
+                if i != 0 {
+                    new_declarator.push(Token::new_atom(","));
+                }
+                let param_declarator = param_name.map(Token::new_atom).into_iter().collect();
+                new_declarator.extend(render(param_ty, param_declarator));
+            }
+            if is_variadic {
+                if num_params != 0 {
+                    new_declarator.push(Token::new_atom(","));
+                }
+                new_declarator.push(Token::new_atom("..."));
+            } else if num_params == 0 {
+                new_declarator.push(Token::new_atom("void"));
+            }
+            new_declarator.push(Token::new_atom(")"));
+            render(*ret, new_declarator)
+        }
+    }
+}
+
+/// Adds an enumerator to the tokens of an enumeration body.
+///
+/// The `value` is omitted if it matches the implicit value, as it typically is in the source code.
+/// The `next_value` tracks the implicit value of the next enumerator, it should be initially set
+/// to `Some(0)`.
+pub(super) fn push_enumerator(
+    tokens: &mut Tokens,
+    name: &str,
+    value: Option<&str>,
+    next_value: &mut Option<i128>,
+) {
+    if !tokens.is_empty() {
+        tokens.push(Token::new_atom(","));
+    }
+    tokens.push(Token::new_atom(name));
+    if let Some(value) = value {
+        let parsed_value = value.parse::<i128>().ok();
+        if parsed_value.is_none() || parsed_value != *next_value {
+            tokens.push(Token::new_atom("="));
+            tokens.push(Token::new_atom(value));
+        }
+        *next_value = parsed_value;
+    }
+    *next_value = next_value.map(|value| value + 1);
+}
+
+/// Returns the tokens of a base type name, as reported by a compiler in debugging information,
+/// in the canonical spelling commonly used in the source code.
+///
+/// Compilers name base types in their own way, for instance GCC uses `long int` and
+/// `long unsigned int` for what the source code typically spells as `long` and `unsigned long`,
+/// and genksyms records as such. The canonical spelling puts the sign first, followed by the size
+/// modifiers and the remaining words. The word `int` is omitted if a size modifier is present, and
+/// the sign `signed` is kept only for `char`, where it makes a distinct type.
+pub(super) fn base_type_tokens(name: &str) -> Tokens {
+    let mut sign = None;
+    let mut sizes = Vec::new();
+    let mut others = Vec::new();
+    for word in name.split_whitespace() {
+        match word {
+            "signed" | "unsigned" => sign = Some(word),
+            "short" | "long" => sizes.push(word),
+            _ => others.push(word),
+        }
+    }
+    if !sizes.is_empty() {
+        others.retain(|&word| word != "int");
+    } else if others.is_empty() {
+        others.push("int");
+    }
+    if sign == Some("signed") && others != ["char"] {
+        sign = None;
+    }
+    sign.into_iter()
+        .chain(sizes)
+        .chain(others)
+        .map(Token::new_atom)
+        .collect()
+}
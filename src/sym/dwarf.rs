@@ -0,0 +1,468 @@
+// Copyright (C) 2025 SUSE LLC <petr.pavlu@suse.com>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use super::ctype::{base_type_tokens, push_enumerator, render, CType};
+use super::{Dialect, FileRecords, SymCorpus, Token, Tokens, TypeVariants, Types};
+use crate::debug;
+use std::collections::HashMap;
+
+// Notes:
+// [1] The gendwarfksyms tool describes each type by recursively expanding its DWARF debugging
+//     information entries. A type description consists of DWARF tags, such as `structure_type` or
+//     `pointer_type`, names, nested descriptions enclosed in `{` and `}`, and attributes in form
+//     `<name>(<value>)`, for instance:
+//
+//     s#foo structure_type foo {
+//       member pointer_type { base_type char byte_size(1) encoding(6) } byte_size(8) name
+//         data_member_location(0) ,
+//       member base_type unsigned int byte_size(4) encoding(7) len data_member_location(8)
+//     } byte_size(16)
+//     bar subprogram ( formal_parameter pointer_type { s#foo } byte_size(8) p ) -> base_type int
+//       byte_size(4) encoding(5)
+//
+//     References to other named types use the same `<x>#<name>` form as genksyms.
+// [2] Interpreting kABI rule annotations of gendwarfksyms and handling its file layout specially
+//     is out of scope, because neither is visible in the symtypes data. The rules, set by the
+//     `KABI_RULE` macros such as `declonly` or `enumerator_ignore`, and the members reserved or
+//     ignored by the `KABI_RESERVE` and `KABI_IGNORE` macros are applied by gendwarfksyms when it
+//     writes the data. They only change the written descriptions, for instance a `declonly` type is
+//     written as `structure_type foo { }`, which is converted as an opaque declaration, and
+//     a reserved member as an ordinary member. Similarly, gendwarfksyms writes the types of all
+//     exports of an object into one file in the order it processes them, so an export can precede
+//     the types which it references. Records in a file are loaded independently of their order,
+//     so this needs no special handling either.
+
+/// DWARF tags which can start a type description produced by gendwarfksyms.
+const DWARF_TAGS: &[&str] = &[
+    "array_type",
+    "atomic_type",
+    "base_type",
+    "class_type",
+    "const_type",
+    "enumeration_type",
+    "pointer_type",
+    "restrict_type",
+    "structure_type",
+    "subprogram",
+    "subroutine_type",
+    "union_type",
+    "variable",
+    "volatile_type",
+];
+
+/// Returns whether the specified type description was produced by gendwarfksyms.
+pub(super) fn is_gendwarfksyms_type(tokens: &Tokens) -> bool {
+    match tokens.first() {
+        Some(Token::Atom(word)) => DWARF_TAGS.contains(&word.as_str()),
+        _ => false,
+    }
+}
+
+/// Returns whether the specified type description produced by gendwarfksyms is an opaque
+/// declaration, which is a structure, union, class or enumeration without any members and, except
+/// for an enumeration, without a size, for instance `structure_type foo { }`.
+pub(super) fn is_gendwarfksyms_opaque_type(tokens: &Tokens) -> bool {
+    let mut parser = Parser::new(tokens);
+    let tag = match parser.next() {
+        Some(Token::Atom(word)) => word.as_str(),
+        _ => return false,
+    };
+    if !matches!(
+        tag,
+        "structure_type" | "union_type" | "class_type" | "enumeration_type"
+    ) {
+        return false;
+    }
+    parser.parse_name();
+    if parser.expect("{").is_err() || parser.expect("}").is_err() {
+        return false;
+    }
+    let attributes = parser.skip_attributes();
+    parser.peek().is_none()
+        && (tag == "enumeration_type"
+            || !attributes
+                .iter()
+                .any(|attribute| attribute_value(attribute, "byte_size").is_some()))
+}
+
+impl SymCorpus {
+    /// Converts the corpus to the genksyms dialect.
+    ///
+    /// Types described by gendwarfksyms are translated to the C declarations that genksyms would
+    /// produce for them. This allows to compare a corpus produced by gendwarfksyms with one
+    /// produced by genksyms. The conversion is best-effort: DWARF doesn't record everything that
+    /// genksyms sees in the source code, and vice versa, so some differences can remain. Names of
+    /// base types are converted to their spelling commonly used in the source code, for instance
+    /// `long unsigned int` becomes `unsigned long`. A type which cannot be converted is kept
+    /// unchanged.
+    ///
+    /// A corpus which is already in the genksyms dialect is returned as is.
+    pub fn into_genksyms(self) -> SymCorpus {
+        if self.dialect() == Dialect::Genksyms {
+            return self;
+        }
+
+        // Convert all types. Different variants can become the same after the conversion because
+        // some DWARF attributes are dropped, merge such variants and record how they got remapped.
+        let mut types = Types::new();
+        let mut remaps = HashMap::new();
+        for (name, variants) in self.types {
+            let mut new_variants = TypeVariants::new();
+            let mut remap = Vec::new();
+            for tokens in variants {
+                let new_tokens = match convert_type(&name, &tokens) {
+                    Ok(new_tokens) => new_tokens,
+                    Err(err) => {
+                        debug!("Failed to convert type '{}': {}", name, err);
+                        tokens
+                    }
+                };
+                let variant_idx = match new_variants.iter().position(|other| *other == new_tokens) {
+                    Some(variant_idx) => variant_idx,
+                    None => {
+                        new_variants.push(new_tokens);
+                        new_variants.len() - 1
+                    }
+                };
+                remap.push(variant_idx);
+            }
+            remaps.insert(name.clone(), remap);
+            types.insert(name, new_variants);
+        }
+
+        // Update the file records.
+        let mut files = self.files;
+        for file in &mut files {
+            file.records = file
+                .records
+                .iter()
+                .map(|(name, &variant_idx)| (name.clone(), remaps[name][variant_idx]))
+                .collect::<FileRecords>();
+        }
+
+        SymCorpus {
+            types,
+            exports: self.exports,
+            files,
+            dialect: Some(Dialect::Genksyms),
+            consumers: self.consumers,
+        }
+    }
+}
+
+/// Converts a gendwarfksyms description of a specified type to its genksyms form [2].
+fn convert_type(name: &str, tokens: &Tokens) -> Result<Tokens, String> {
+    let mut parser = Parser::new(tokens);
+    let res = match parser.peek() {
+        Some("subprogram") | Some("variable") => {
+            // An export, render it as a declaration with its name.
+            let ty = parser.parse_type()?;
+            render(ty, vec![Token::new_atom(name)])
+        }
+        Some("typedef") => {
+            parser.next();
+            let typedef_name = parser.parse_name();
+            parser.expect("{")?;
+            let ty = parser.parse_type()?;
+            parser.expect("}")?;
+            let typedef_name = typedef_name.ok_or("Missing a typedef name")?;
+            let mut res = vec![Token::new_atom("typedef")];
+            res.extend(render(ty, vec![Token::new_atom(typedef_name)]));
+            res
+        }
+        _ => render(parser.parse_type()?, Tokens::new()),
+    };
+    parser.skip_attributes();
+    match parser.peek() {
+        Some(word) => Err(format!("Unexpected token '{}'", word)),
+        None => Ok(res),
+    }
+}
+
+/// Returns whether the specified word is a DWARF attribute in form `<name>(<value>)`.
+fn is_attribute(word: &str) -> bool {
+    match word.find('(') {
+        Some(i) => i > 0 && word.ends_with(')'),
+        None => false,
+    }
+}
+
+/// Returns the value of a specified attribute in form `<name>(<value>)`, if the word is such an
+/// attribute.
+fn attribute_value<'a>(word: &'a str, name: &str) -> Option<&'a str> {
+    word.strip_prefix(name)
+        .and_then(|rem| rem.strip_prefix('('))
+        .and_then(|rem| rem.strip_suffix(')'))
+}
+
+/// A recursive descent parser of gendwarfksyms type descriptions.
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    /// Creates a new parser of the specified tokens.
+    fn new(tokens: &'a [Token]) -> Self {
+        Self { tokens, pos: 0 }
+    }
+
+    /// Returns the data of the next token without consuming it.
+    fn peek(&self) -> Option<&'a str> {
+        self.tokens.get(self.pos).map(|token| token.as_str())
+    }
+
+    /// Consumes and returns the next token.
+    fn next(&mut self) -> Option<&'a Token> {
+        let token = self.tokens.get(self.pos);
+        if token.is_some() {
+            self.pos += 1;
+        }
+        token
+    }
+
+    /// Consumes the next token, which must be the specified word.
+    fn expect(&mut self, word: &str) -> Result<(), String> {
+        match self.next() {
+            Some(Token::Atom(next)) if next == word => Ok(()),
+            Some(token) => Err(format!(
+                "Expected '{}' but found '{}'",
+                word,
+                token.as_str()
+            )),
+            None => Err(format!("Expected '{}' but found the end of data", word)),
+        }
+    }
+
+    /// Consumes all following attributes and returns them.
+    fn skip_attributes(&mut self) -> Vec<&'a str> {
+        let mut attributes = Vec::new();
+        while let Some(word) = self.peek() {
+            if !is_attribute(word) {
+                break;
+            }
+            attributes.push(word);
+            self.pos += 1;
+        }
+        attributes
+    }
+
+    /// Consumes a name, which can consist of multiple words, such as `unsigned int`.
+    fn parse_name(&mut self) -> Option<String> {
+        let mut words = Vec::new();
+        while let Some(Token::Atom(word)) = self.tokens.get(self.pos) {
+            if is_attribute(word)
+                || matches!(
+                    word.as_str(),
+                    "{" | "}" | "(" | ")" | "," | "=" | "->" | "member" | "enumerator"
+                )
+                || word.starts_with('[')
+            {
+                break;
+            }
+            words.push(word.as_str());
+            self.pos += 1;
+        }
+        if words.is_empty() {
+            None
+        } else {
+            Some(words.join(" "))
+        }
+    }
+
+    /// Parses a type description.
+    fn parse_type(&mut self) -> Result<CType, String> {
+        let tag = match self.next() {
+            Some(Token::TypeRef(ref_name)) => {
+                return Ok(CType::Named(vec![Token::new_typeref(ref_name.as_str())]))
+            }
+            Some(Token::Atom(word)) => word.as_str(),
+            None => return Err("Expected a type but found the end of data".to_string()),
+        };
+
+        let ty = match tag {
+            "base_type" => {
+                let name = self.parse_name().ok_or("Missing a base type name")?;
+                CType::Named(base_type_tokens(&name))
+            }
+            "pointer_type" => CType::Pointer(Box::new(self.parse_nested_type()?)),
+            "const_type" => CType::Qualified("const", Box::new(self.parse_nested_type()?)),
+            "volatile_type" => CType::Qualified("volatile", Box::new(self.parse_nested_type()?)),
+            "restrict_type" => CType::Qualified("restrict", Box::new(self.parse_nested_type()?)),
+            "atomic_type" => CType::Qualified("_Atomic", Box::new(self.parse_nested_type()?)),
+            "typedef" => {
+                // An inline typedef is represented just by its name.
+                let name = self.parse_name().ok_or("Missing a typedef name")?;
+                self.parse_nested_type()?;
+                CType::Named(vec![Token::new_atom(name)])
+            }
+            "array_type" => {
+                let mut counts = Vec::new();
+                while let Some(word) = self.peek() {
+                    let count = match word.strip_prefix('[').and_then(|w| w.strip_suffix(']')) {
+                        Some(count) => count,
+                        None => break,
+                    };
+                    counts.push(if count.is_empty() {
+                        None
+                    } else {
+                        Some(count.to_string())
+                    });
+                    self.pos += 1;
+                }
+                let mut ty = self.parse_nested_type()?;
+                for count in counts.into_iter().rev() {
+                    ty = CType::Array(Box::new(ty), count);
+                }
+                ty
+            }
+            "structure_type" | "union_type" | "class_type" => self.parse_aggregate(tag)?,
+            "enumeration_type" => self.parse_enumeration()?,
+            "subprogram" | "subroutine_type" => self.parse_function()?,
+            "variable" => self.parse_type()?,
+            _ => return Err(format!("Unexpected DWARF tag '{}'", tag)),
+        };
+        self.skip_attributes();
+        Ok(ty)
+    }
+
+    /// Parses a type description enclosed in `{` and `}`. An empty description stands for `void`.
+    fn parse_nested_type(&mut self) -> Result<CType, String> {
+        self.expect("{")?;
+        if self.peek() == Some("}") {
+            self.pos += 1;
+            return Ok(CType::Named(vec![Token::new_atom("void")]));
+        }
+        let ty = self.parse_type()?;
+        self.expect("}")?;
+        Ok(ty)
+    }
+
+    /// Parses the rest of a structure, union or class description, after its DWARF tag.
+    fn parse_aggregate(&mut self, tag: &str) -> Result<CType, String> {
+        let keyword = match tag {
+            "structure_type" => "struct",
+            "union_type" => "union",
+            _ => "class",
+        };
+        let mut res = vec![Token::new_atom(keyword)];
+        if let Some(name) = self.parse_name() {
+            res.push(Token::new_atom(name));
+        }
+
+        self.expect("{")?;
+        let mut members = Tokens::new();
+        while self.peek() != Some("}") {
+            self.expect("member")?;
+            let ty = self.parse_type()?;
+            let name = self.parse_name();
+            let mut member = render(ty, name.map(Token::new_atom).into_iter().collect());
+            for attribute in self.skip_attributes() {
+                if let Some(bit_size) = attribute_value(attribute, "bit_size") {
+                    member.push(Token::new_atom(":"));
+                    member.push(Token::new_atom(bit_size));
+                }
+            }
+            member.push(Token::new_atom(";"));
+            members.extend(member);
+            if self.peek() == Some(",") {
+                self.pos += 1;
+            }
+        }
+        self.expect("}")?;
+
+        // A declaration without any members and size is opaque.
+        let attributes = self.skip_attributes();
+        let is_opaque = members.is_empty()
+            && !attributes
+                .iter()
+                .any(|attribute| attribute_value(attribute, "byte_size").is_some());
+
+        res.push(Token::new_atom("{"));
+        if is_opaque {
+            res.push(Token::new_atom("UNKNOWN"));
+        } else {
+            res.extend(members);
+        }
+        res.push(Token::new_atom("}"));
+        Ok(CType::Named(res))
+    }
+
+    /// Parses the rest of an enumeration description, after its DWARF tag.
+    fn parse_enumeration(&mut self) -> Result<CType, String> {
+        let mut res = vec![Token::new_atom("enum")];
+        if let Some(name) = self.parse_name() {
+            res.push(Token::new_atom(name));
+        }
+
+        self.expect("{")?;
+        let mut enumerators = Tokens::new();
+        let mut is_opaque = true;
+        let mut next_value = Some(0i128);
+        while self.peek() != Some("}") {
+            is_opaque = false;
+            self.expect("enumerator")?;
+            let name = self.parse_name().ok_or("Missing an enumerator name")?;
+            let value = if self.peek() == Some("=") {
+                self.pos += 1;
+                match self.next() {
+                    Some(Token::Atom(value)) => Some(value.as_str()),
+                    _ => return Err("Missing an enumerator value".to_string()),
+                }
+            } else {
+                None
+            };
+            push_enumerator(&mut enumerators, &name, value, &mut next_value);
+            if self.peek() == Some(",") {
+                self.pos += 1;
+            }
+        }
+        self.expect("}")?;
+
+        res.push(Token::new_atom("{"));
+        if is_opaque {
+            res.push(Token::new_atom("UNKNOWN"));
+        } else {
+            res.extend(enumerators);
+        }
+        res.push(Token::new_atom("}"));
+        Ok(CType::Named(res))
+    }
+
+    /// Parses the rest of a function description, after its DWARF tag.
+    fn parse_function(&mut self) -> Result<CType, String> {
+        self.expect("(")?;
+        let mut params = Vec::new();
+        let mut is_variadic = false;
+        while self.peek() != Some(")") {
+            match self.peek() {
+                Some("formal_parameter") => {
+                    self.pos += 1;
+                    let ty = self.parse_type()?;
+                    let name = self.parse_name();
+                    self.skip_attributes();
+                    params.push((ty, name));
+                }
+                Some("unspecified_parameters") => {
+                    self.pos += 1;
+                    is_variadic = true;
+                }
+                Some(word) => return Err(format!("Unexpected function parameter '{}'", word)),
+                None => return Err("Expected ')' but found the end of data".to_string()),
+            }
+            if self.peek() == Some(",") {
+                self.pos += 1;
+            }
+        }
+        self.expect(")")?;
+
+        // A missing return type stands for `void`.
+        let ret = if self.peek() == Some("->") {
+            self.pos += 1;
+            self.parse_type()?
+        } else {
+            CType::Named(vec![Token::new_atom("void")])
+        };
+        Ok(CType::Function(Box::new(ret), params, is_variadic))
+    }
+}
@@ -0,0 +1,171 @@
+// Copyright (C) 2025 SUSE LLC <petr.pavlu@suse.com>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use super::SymCorpus;
+use crate::elf::ElfObject;
+use crate::{debug, MapIOErr};
+use std::collections::HashMap;
+use std::io::{prelude::*, BufWriter};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::{fs, thread};
+
+/// Exports of kernel binaries, mapping each export name to the path of the binary which provides
+/// it.
+#[derive(Default)]
+pub struct BinaryExports {
+    exports: HashMap<String, PathBuf>,
+}
+
+impl BinaryExports {
+    /// Creates a new empty collection of exports.
+    pub fn new() -> Self {
+        Self {
+            exports: HashMap::new(),
+        }
+    }
+
+    /// Loads exports of kernel binaries from a given location.
+    ///
+    /// The `path` can point to a single vmlinux or `.ko` file, or to a directory. In the latter
+    /// case, the function reads `vmlinux` in the directory, if present, and recursively all `.ko`
+    /// files in it.
+    pub fn load<P: AsRef<Path>>(&mut self, path: P, num_workers: i32) -> Result<(), crate::Error> {
+        let path = path.as_ref();
+
+        let md = fs::metadata(path).map_err(|err| {
+            crate::Error::new_io(&format!("Failed to query path '{}'", path.display()), err)
+        })?;
+
+        let (root, binaries) = if md.is_dir() {
+            let mut files = [Vec::new()];
+            SymCorpus::collect_files(path, "", &[".ko"], &mut files)?;
+            let [modules] = files;
+            let mut binaries = Vec::new();
+            if path.join("vmlinux").is_file() {
+                binaries.push(PathBuf::from("vmlinux"));
+            }
+            binaries.extend(modules);
+            (path, binaries)
+        } else {
+            (Path::new(""), vec![path.to_path_buf()])
+        };
+
+        // Read the exports of all binaries in parallel and then merge them in the order of the
+        // binaries to make the result deterministic.
+        let next_work_idx = AtomicUsize::new(0);
+        let results = Mutex::new(Vec::new());
+        thread::scope(|s| {
+            for _ in 0..num_workers {
+                s.spawn(|| loop {
+                    let work_idx = next_work_idx.fetch_add(1, Ordering::Relaxed);
+                    if work_idx >= binaries.len() {
+                        return;
+                    }
+                    let sub_path = &binaries[work_idx];
+                    let result = read_exports(root, sub_path);
+                    results.lock().unwrap().push((work_idx, result));
+                });
+            }
+        });
+
+        let mut results = results.into_inner().unwrap();
+        results.sort_by_key(|(work_idx, _)| *work_idx);
+        for (work_idx, result) in results {
+            self.add(&binaries[work_idx], result?)?;
+        }
+        Ok(())
+    }
+
+    /// Adds exports of a specified binary.
+    pub(super) fn add(&mut self, path: &Path, exports: Vec<String>) -> Result<(), crate::Error> {
+        for name in exports {
+            if let Some(previous_path) = self.exports.get(&name) {
+                return Err(crate::Error::DuplicateExport {
+                    name,
+                    path: path.to_path_buf(),
+                    line: None,
+                    previous_path: previous_path.clone(),
+                });
+            }
+            self.exports.insert(name, path.to_path_buf());
+        }
+        Ok(())
+    }
+}
+
+/// Reads exports of a kernel binary, given by its path relative to a root directory.
+fn read_exports(root: &Path, sub_path: &Path) -> Result<Vec<String>, crate::Error> {
+    let path = root.join(sub_path);
+    debug!("Reading exports from '{}'", path.display());
+
+    let data = fs::read(&path).map_err(|err| {
+        crate::Error::new_io(&format!("Failed to read file '{}'", path.display()), err)
+    })?;
+    ElfObject::parse(sub_path, data)?.kernel_exports()
+}
+
+impl SymCorpus {
+    /// Compares the exports of the corpus with exports of kernel binaries and writes a report of
+    /// all exports found only on one side to the provided output stream.
+    ///
+    /// The function returns the number of reported exports.
+    pub fn write_export_mismatches_buffer<W: Write>(
+        &self,
+        binary_exports: &BinaryExports,
+        writer: W,
+    ) -> Result<usize, crate::Error> {
+        let mut writer = BufWriter::new(writer);
+        let err_desc = "Failed to write an export mismatch";
+
+        let mut missing_in_corpus = binary_exports
+            .exports
+            .iter()
+            .filter(|(name, _)| !self.exports.contains_key(*name))
+            .map(|(name, path)| (name.as_str(), path.as_path()))
+            .collect::<Vec<_>>();
+        missing_in_corpus.sort();
+
+        let mut missing_in_binaries = self
+            .exports
+            .iter()
+            .filter(|(name, _)| !binary_exports.exports.contains_key(*name))
+            .map(|(name, &file_idx)| (name.as_str(), self.files[file_idx].path.as_path()))
+            .collect::<Vec<_>>();
+        missing_in_binaries.sort();
+
+        let mut is_first = true;
+        for (missing, desc) in [
+            (
+                &missing_in_corpus,
+                "present in binaries but missing in the symtypes corpus",
+            ),
+            (
+                &missing_in_binaries,
+                "present in the symtypes corpus but missing in binaries",
+            ),
+        ] {
+            if missing.is_empty() {
+                continue;
+            }
+            if !is_first {
+                writeln!(writer).map_io_err(err_desc)?;
+            }
+            is_first = false;
+
+            writeln!(
+                writer,
+                "The following '{}' exports are {}:",
+                missing.len(),
+                desc
+            )
+            .map_io_err(err_desc)?;
+            for (name, path) in missing {
+                writeln!(writer, " {} ({})", name, path.display()).map_io_err(err_desc)?;
+            }
+        }
+
+        Ok(missing_in_corpus.len() + missing_in_binaries.len())
+    }
+}
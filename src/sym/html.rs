@@ -0,0 +1,223 @@
+// Copyright (C) 2025 SUSE LLC <petr.pavlu@suse.com>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use super::junit::escape_xml;
+use super::{format_consumers, pretty_format_type, CompareChange, CompareOptions, SymCorpus};
+use crate::diff::{self, SideBySideRow};
+use crate::MapIOErr;
+use std::collections::BTreeMap;
+use std::io::prelude::*;
+
+// Notes:
+// [1] The report is a single static HTML file which can be archived as a build artifact. It embeds
+//     its style sheet and script and doesn't refer to any external assets. It starts with
+//     a summary, followed by a list of all affected exports, which can be filtered by a search
+//     field, and by a side-by-side diff of each changed type. Each changed export links to the
+//     types which caused the change and each type links back to the affected exports.
+// [2] Element IDs are derived from type and export names. The `#` character in a type name is
+//     replaced with `-`, which cannot appear in a C identifier, so the IDs are unique.
+
+/// The style sheet embedded in the report.
+const HTML_STYLE: &str = concat!(
+    "body { font-family: sans-serif; margin: 2em; }\n",
+    "table { border-collapse: collapse; }\n",
+    "th, td { border: 1px solid #ccc; padding: 0.2em 0.5em; text-align: left; }\n",
+    "ul.exports li.hidden { display: none; }\n",
+    "table.diff { width: 100%; table-layout: fixed; font-family: monospace; }\n",
+    "table.diff td { white-space: pre-wrap; vertical-align: top; }\n",
+    "td.removed { background: #ffebe9; }\n",
+    "td.added { background: #e6ffec; }\n",
+    "td.empty { background: #f6f8fa; }\n",
+);
+
+/// The script embedded in the report, which filters the list of exports by the search field.
+const HTML_SCRIPT: &str = concat!(
+    "document.getElementById('search').addEventListener('input', function () {\n",
+    "  var text = this.value.toLowerCase();\n",
+    "  document.querySelectorAll('ul.exports li').forEach(function (item) {\n",
+    "    var name = item.getAttribute('data-name').toLowerCase();\n",
+    "    item.classList.toggle('hidden', name.indexOf(text) < 0);\n",
+    "  });\n",
+    "});\n",
+);
+
+impl SymCorpus {
+    /// Writes an HTML report about removed, added and changed exports to the provided output
+    /// stream [1].
+    pub(super) fn write_html_report<W: Write>(
+        &self,
+        other_corpus: &SymCorpus,
+        removed: &[&str],
+        added: &[&str],
+        changes: &[CompareChange],
+        options: &CompareOptions,
+        mut writer: W,
+    ) -> Result<(), crate::Error> {
+        let err_desc = "Failed to write a comparison result";
+
+        // Collect the type changes which affect each export.
+        let mut export_changes: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+        for ((name, _, _), exports) in changes {
+            for &export in exports {
+                export_changes.entry(export).or_default().push(name);
+            }
+        }
+
+        // Write the header and the summary.
+        writeln!(writer, "<!DOCTYPE html>").map_io_err(err_desc)?;
+        writeln!(writer, "<html lang=\"en\">").map_io_err(err_desc)?;
+        writeln!(writer, "<head>").map_io_err(err_desc)?;
+        writeln!(writer, "<meta charset=\"utf-8\">").map_io_err(err_desc)?;
+        writeln!(writer, "<title>kABI comparison</title>").map_io_err(err_desc)?;
+        write!(writer, "<style>\n{}</style>\n", HTML_STYLE).map_io_err(err_desc)?;
+        writeln!(writer, "</head>").map_io_err(err_desc)?;
+        writeln!(writer, "<body>").map_io_err(err_desc)?;
+        writeln!(writer, "<h1>kABI comparison</h1>").map_io_err(err_desc)?;
+
+        writeln!(writer, "<h2>Summary</h2>").map_io_err(err_desc)?;
+        writeln!(writer, "<table class=\"summary\">").map_io_err(err_desc)?;
+        for (desc, count) in [
+            ("Removed exports", removed.len()),
+            ("Added exports", added.len()),
+            ("Changed exports", export_changes.len()),
+            ("Changed types", changes.len()),
+        ] {
+            writeln!(writer, "<tr><th>{}</th><td>{}</td></tr>", desc, count)
+                .map_io_err(err_desc)?;
+        }
+        writeln!(writer, "</table>").map_io_err(err_desc)?;
+
+        // Write the list of affected exports.
+        let mut exports = Vec::new();
+        for &name in removed {
+            let consumers = self.get_consumers(other_corpus, name);
+            let desc = format!(
+                "has been removed{}",
+                escape_xml(&format_consumers(&consumers))
+            );
+            exports.push((name, "removed", desc));
+        }
+        for &name in added {
+            exports.push((name, "added", "has been added".to_string()));
+        }
+        for (&name, type_names) in &export_changes {
+            let consumers = self.get_consumers(other_corpus, name);
+            let links = type_names
+                .iter()
+                .map(|type_name| {
+                    format!(
+                        "<a href=\"#{}\"><code>{}</code></a>",
+                        type_id(type_name),
+                        escape_xml(type_name)
+                    )
+                })
+                .collect::<Vec<_>>();
+            let desc = format!(
+                "is different because of a changed {}{}",
+                links.join(", "),
+                escape_xml(&format_consumers(&consumers))
+            );
+            exports.push((name, "changed", desc));
+        }
+        exports.sort_by_key(|(name, _, _)| *name);
+
+        writeln!(writer, "<h2>Affected exports</h2>").map_io_err(err_desc)?;
+        writeln!(
+            writer,
+            "<input id=\"search\" type=\"search\" placeholder=\"Filter exports\">"
+        )
+        .map_io_err(err_desc)?;
+        writeln!(writer, "<ul class=\"exports\">").map_io_err(err_desc)?;
+        for (name, class, desc) in &exports {
+            writeln!(
+                writer,
+                "<li id=\"{}\" class=\"{}\" data-name=\"{}\"><code>{}</code> {}</li>",
+                export_id(name),
+                class,
+                escape_xml(name),
+                escape_xml(name),
+                desc
+            )
+            .map_io_err(err_desc)?;
+        }
+        writeln!(writer, "</ul>").map_io_err(err_desc)?;
+
+        // Write a side-by-side diff of each changed type.
+        if !changes.is_empty() {
+            writeln!(writer, "<h2>Changed types</h2>").map_io_err(err_desc)?;
+        }
+        for ((name, tokens, other_tokens), exports) in changes {
+            writeln!(writer, "<section id=\"{}\">", type_id(name)).map_io_err(err_desc)?;
+            writeln!(writer, "<h3><code>{}</code></h3>", escape_xml(name)).map_io_err(err_desc)?;
+            let links = exports
+                .iter()
+                .map(|export| {
+                    format!(
+                        "<a href=\"#{}\"><code>{}</code></a>",
+                        export_id(export),
+                        escape_xml(export)
+                    )
+                })
+                .collect::<Vec<_>>();
+            writeln!(writer, "<p>Affected exports: {}</p>", links.join(", "))
+                .map_io_err(err_desc)?;
+            let locations = options
+                .type_locations(name)
+                .iter()
+                .map(|location| format!("<code>{}</code>", escape_xml(location)))
+                .collect::<Vec<_>>();
+            if !locations.is_empty() {
+                writeln!(writer, "<p>Defined at: {}</p>", locations.join(", "))
+                    .map_io_err(err_desc)?;
+            }
+
+            let pretty = pretty_format_type(tokens);
+            let other_pretty = pretty_format_type(other_tokens);
+            writeln!(writer, "<table class=\"diff\">").map_io_err(err_desc)?;
+            writeln!(writer, "<tr><th>Old</th><th>New</th></tr>").map_io_err(err_desc)?;
+            for row in diff::side_by_side(&pretty, &other_pretty) {
+                let (old, new) = match row {
+                    SideBySideRow::Same(index, other_index) => (
+                        diff_cell("", Some(&pretty[index])),
+                        diff_cell("", Some(&other_pretty[other_index])),
+                    ),
+                    SideBySideRow::Changed(maybe_index, maybe_other_index) => (
+                        diff_cell("removed", maybe_index.map(|index| &pretty[index])),
+                        diff_cell(
+                            "added",
+                            maybe_other_index.map(|other_index| &other_pretty[other_index]),
+                        ),
+                    ),
+                };
+                writeln!(writer, "<tr>{}{}</tr>", old, new).map_io_err(err_desc)?;
+            }
+            writeln!(writer, "</table>").map_io_err(err_desc)?;
+            writeln!(writer, "</section>").map_io_err(err_desc)?;
+        }
+
+        write!(writer, "<script>\n{}</script>\n", HTML_SCRIPT).map_io_err(err_desc)?;
+        writeln!(writer, "</body>").map_io_err(err_desc)?;
+        writeln!(writer, "</html>").map_io_err(err_desc)?;
+
+        Ok(())
+    }
+}
+
+/// Returns the element ID of a changed type [2].
+fn type_id(name: &str) -> String {
+    format!("type-{}", escape_xml(&name.replace('#', "-")))
+}
+
+/// Returns the element ID of an export.
+fn export_id(name: &str) -> String {
+    format!("export-{}", escape_xml(name))
+}
+
+/// Formats a cell of a side-by-side diff, using the specified class for a present line.
+fn diff_cell(class: &str, maybe_line: Option<&String>) -> String {
+    match maybe_line {
+        Some(line) if class.is_empty() => format!("<td>{}</td>", escape_xml(line)),
+        Some(line) => format!("<td class=\"{}\">{}</td>", class, escape_xml(line)),
+        None => "<td class=\"empty\"></td>".to_string(),
+    }
+}
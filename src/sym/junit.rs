@@ -0,0 +1,187 @@
+// Copyright (C) 2025 SUSE LLC <petr.pavlu@suse.com>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use super::{
+    format_consumers, format_locations, write_type_diff_with_options, CompareChange,
+    CompareOptions, SymCorpus,
+};
+use crate::MapIOErr;
+use std::collections::HashMap;
+use std::io::prelude::*;
+
+// Notes:
+// [1] The report uses the JUnit XML format as understood by common CI systems. Each export is
+//     a test case in a single test suite. An export which is unchanged passes, one which has been
+//     removed or changed fails, and one which has been added is skipped because the addition is
+//     tolerated by the kABI. The class name of a test case is the module which provides the
+//     export, if it is known.
+
+/// The outcome of a single test case.
+enum TestOutcome {
+    Passed,
+    Failed { message: String, body: String },
+    Skipped { message: String },
+}
+
+impl SymCorpus {
+    /// Writes a JUnit XML report about all exports to the provided output stream [1].
+    pub(super) fn write_junit_report<W: Write>(
+        &self,
+        other_corpus: &SymCorpus,
+        removed: &[&str],
+        added: &[&str],
+        changes: &[CompareChange],
+        options: &CompareOptions,
+        mut writer: W,
+    ) -> Result<(), crate::Error> {
+        let err_desc = "Failed to write a comparison result";
+
+        // Determine the outcome of each export, keeping them sorted by name.
+        let mut cases = self
+            .exports
+            .keys()
+            .filter(|name| other_corpus.exports.contains_key(*name))
+            .map(|name| (name.as_str(), (self, TestOutcome::Passed)))
+            .collect::<Vec<_>>();
+        for &name in removed {
+            let consumers = self.get_consumers(other_corpus, name);
+            let message = format!(
+                "Export '{}' has been removed{}",
+                name,
+                format_consumers(&consumers)
+            );
+            let outcome = TestOutcome::Failed {
+                message,
+                body: String::new(),
+            };
+            cases.push((name, (self, outcome)));
+        }
+        for &name in added {
+            let message = format!("Export '{}' has been added", name);
+            cases.push((name, (other_corpus, TestOutcome::Skipped { message })));
+        }
+        cases.sort_by_key(|(name, _)| *name);
+
+        // Collect the type changes which affect each export.
+        let mut export_changes: HashMap<&str, Vec<usize>> = HashMap::new();
+        for (change_idx, (_, exports)) in changes.iter().enumerate() {
+            for &export in exports {
+                export_changes.entry(export).or_default().push(change_idx);
+            }
+        }
+
+        let diff_options = options.plain_diff();
+        for (name, (_, outcome)) in &mut cases {
+            let change_idxs = match export_changes.get(name) {
+                Some(change_idxs) => change_idxs,
+                None => continue,
+            };
+
+            let mut body = Vec::new();
+            let mut type_names = Vec::new();
+            for &change_idx in change_idxs {
+                let ((type_name, tokens, other_tokens), _) = &changes[change_idx];
+                writeln!(
+                    body,
+                    "because of a changed '{}'{}:",
+                    type_name,
+                    format_locations(&options.type_locations(type_name))
+                )
+                .map_io_err(err_desc)?;
+                write_type_diff_with_options(tokens, other_tokens, &diff_options, &mut body)?;
+                type_names.push(format!("'{}'", type_name));
+            }
+            let consumers = self.get_consumers(other_corpus, name);
+            *outcome = TestOutcome::Failed {
+                message: format!(
+                    "Export '{}' is different because of a changed {}{}",
+                    name,
+                    type_names.join(", "),
+                    format_consumers(&consumers)
+                ),
+                body: String::from_utf8_lossy(&body).into_owned(),
+            };
+        }
+
+        // Write the report.
+        let num_failures = cases
+            .iter()
+            .filter(|(_, (_, outcome))| matches!(outcome, TestOutcome::Failed { .. }))
+            .count();
+        let num_skipped = cases
+            .iter()
+            .filter(|(_, (_, outcome))| matches!(outcome, TestOutcome::Skipped { .. }))
+            .count();
+        let counts = format!(
+            "tests=\"{}\" failures=\"{}\" errors=\"0\" skipped=\"{}\"",
+            cases.len(),
+            num_failures,
+            num_skipped
+        );
+
+        writeln!(writer, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>").map_io_err(err_desc)?;
+        writeln!(writer, "<testsuites name=\"ksymtypes\" {}>", counts).map_io_err(err_desc)?;
+        writeln!(writer, "  <testsuite name=\"kabi\" {}>", counts).map_io_err(err_desc)?;
+        for (name, (corpus, outcome)) in &cases {
+            let class_name = corpus.export_module(name).unwrap_or("kabi");
+            write!(
+                writer,
+                "    <testcase classname=\"{}\" name=\"{}\"",
+                escape_xml(class_name),
+                escape_xml(name)
+            )
+            .map_io_err(err_desc)?;
+            match outcome {
+                TestOutcome::Passed => writeln!(writer, "/>").map_io_err(err_desc)?,
+                TestOutcome::Failed { message, body } => {
+                    writeln!(writer, ">").map_io_err(err_desc)?;
+                    if body.is_empty() {
+                        writeln!(
+                            writer,
+                            "      <failure message=\"{}\"/>",
+                            escape_xml(message)
+                        )
+                        .map_io_err(err_desc)?;
+                    } else {
+                        writeln!(
+                            writer,
+                            "      <failure message=\"{}\">{}</failure>",
+                            escape_xml(message),
+                            escape_xml(body)
+                        )
+                        .map_io_err(err_desc)?;
+                    }
+                    writeln!(writer, "    </testcase>").map_io_err(err_desc)?;
+                }
+                TestOutcome::Skipped { message } => {
+                    writeln!(writer, ">").map_io_err(err_desc)?;
+                    writeln!(
+                        writer,
+                        "      <skipped message=\"{}\"/>",
+                        escape_xml(message)
+                    )
+                    .map_io_err(err_desc)?;
+                    writeln!(writer, "    </testcase>").map_io_err(err_desc)?;
+                }
+            }
+        }
+        writeln!(writer, "  </testsuite>").map_io_err(err_desc)?;
+        writeln!(writer, "</testsuites>").map_io_err(err_desc)
+    }
+}
+
+/// Escapes special characters in a specified text for use in XML content or attribute values.
+pub(super) fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            ch => escaped.push(ch),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,151 @@
+// Copyright (C) 2025 SUSE LLC <petr.pavlu@suse.com>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use super::{
+    format_consumers, format_locations, write_type_diff_with_options, CompareChange,
+    CompareOptions, SymCorpus,
+};
+use crate::MapIOErr;
+use std::io::prelude::*;
+
+// Notes:
+// [1] The report is intended to be posted as a comment on a merge request. It starts with
+//     a summary table, followed by lists of removed and added exports and by a collapsible section
+//     for each changed type. Code hosting sites limit the size of comments, GitHub for instance to
+//     65536 characters. The entries are therefore written only while the report fits in
+//     `MARKDOWN_SIZE_LIMIT` and a note about the omitted ones is added at the end. The summary is
+//     always complete.
+
+/// The maximum size of a Markdown report in bytes, excluding the final truncation note.
+const MARKDOWN_SIZE_LIMIT: usize = 60000;
+
+/// Kinds of changed types counted in the summary, as tuples of (prefix, description). Types
+/// without a prefix are exports.
+const MARKDOWN_TYPE_KINDS: [(&str, &str); 6] = [
+    ("s#", "Changed structures"),
+    ("u#", "Changed unions"),
+    ("e#", "Changed enumerations"),
+    ("t#", "Changed typedefs"),
+    ("E#", "Changed enumerators"),
+    ("", "Changed export declarations"),
+];
+
+impl SymCorpus {
+    /// Writes a Markdown report about removed, added and changed exports to the provided output
+    /// stream [1].
+    pub(super) fn write_markdown_report<W: Write>(
+        &self,
+        other_corpus: &SymCorpus,
+        removed: &[&str],
+        added: &[&str],
+        changes: &[CompareChange],
+        options: &CompareOptions,
+        mut writer: W,
+    ) -> Result<(), crate::Error> {
+        let err_desc = "Failed to write a comparison result";
+
+        let mut changed = changes
+            .iter()
+            .flat_map(|(_, exports)| exports.iter().copied())
+            .collect::<Vec<_>>();
+        changed.sort();
+        changed.dedup();
+
+        // Write the summary.
+        let mut summary = String::new();
+        summary.push_str("## kABI comparison\n\n");
+        summary.push_str("| Change | Count |\n");
+        summary.push_str("| --- | ---: |\n");
+        summary.push_str(&format!("| Removed exports | {} |\n", removed.len()));
+        summary.push_str(&format!("| Added exports | {} |\n", added.len()));
+        summary.push_str(&format!("| Changed exports | {} |\n", changed.len()));
+        for (prefix, desc) in MARKDOWN_TYPE_KINDS {
+            let count = changes
+                .iter()
+                .filter(|((name, _, _), _)| type_kind_prefix(name) == prefix)
+                .count();
+            if count > 0 {
+                summary.push_str(&format!("| {} | {} |\n", desc, count));
+            }
+        }
+        writer.write_all(summary.as_bytes()).map_io_err(err_desc)?;
+
+        // Format all entries and write them until the size limit is reached. The heading of each
+        // list is included in its first entry.
+        let diff_options = options.plain_diff();
+        let mut entries = Vec::new();
+        for (i, &name) in removed.iter().enumerate() {
+            let heading = if i == 0 {
+                "\n### Removed exports\n\n"
+            } else {
+                ""
+            };
+            let consumers = self.get_consumers(other_corpus, name);
+            entries.push(format!(
+                "{}- `{}`{}\n",
+                heading,
+                name,
+                format_consumers(&consumers)
+            ));
+        }
+        for (i, &name) in added.iter().enumerate() {
+            let heading = if i == 0 {
+                "\n### Added exports\n\n"
+            } else {
+                ""
+            };
+            entries.push(format!("{}- `{}`\n", heading, name));
+        }
+        for (i, ((name, tokens, other_tokens), exports)) in changes.iter().enumerate() {
+            let mut section = String::new();
+            if i == 0 {
+                section.push_str("\n### Changed types\n");
+            }
+            section.push_str("\n<details>\n");
+            section.push_str(&format!(
+                "<summary><code>{}</code>{} affecting '{}' exports</summary>\n\n",
+                name,
+                format_locations(&options.type_locations(name)),
+                exports.len()
+            ));
+            section.push_str("Affected exports:\n\n");
+            for export in exports {
+                let consumers = self.get_consumers(other_corpus, export);
+                section.push_str(&format!("- `{}`{}\n", export, format_consumers(&consumers)));
+            }
+
+            let mut diff = Vec::new();
+            write_type_diff_with_options(tokens, other_tokens, &diff_options, &mut diff)?;
+            section.push_str("\n```diff\n");
+            section.push_str(&String::from_utf8_lossy(&diff));
+            section.push_str("```\n\n");
+            section.push_str("</details>\n");
+            entries.push(section);
+        }
+
+        let mut size = summary.len();
+        for (i, entry) in entries.iter().enumerate() {
+            size += entry.len();
+            if size > MARKDOWN_SIZE_LIMIT {
+                writeln!(
+                    writer,
+                    "\n_The report has been truncated, '{}' more entries are not shown._",
+                    entries.len() - i
+                )
+                .map_io_err(err_desc)?;
+                break;
+            }
+            writer.write_all(entry.as_bytes()).map_io_err(err_desc)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the `<x>#` prefix of a type name, or an empty string for an export.
+fn type_kind_prefix(name: &str) -> &str {
+    match name.as_bytes() {
+        [_, b'#', ..] => &name[..2],
+        _ => "",
+    }
+}
@@ -1,20 +1,70 @@
 // Copyright (C) 2024 SUSE LLC <petr.pavlu@suse.com>
 // SPDX-License-Identifier: GPL-2.0-or-later
 
+use crate::diff::{DiffLine, DiffOptions, ANSI_BOLD, ANSI_RESET};
+use crate::elf::ElfObject;
+use crate::maintainers::Maintainers;
+use crate::source::SourceIndex;
 use crate::{debug, MapIOErr, PathFile};
+use std::borrow::Cow;
 use std::collections::hash_map::Entry::{Occupied, Vacant};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::io::{prelude::*, BufReader, BufWriter};
 use std::iter::zip;
+use std::ops::Range;
 use std::path::{Path, PathBuf};
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::{Mutex, RwLock};
 use std::{fs, io, thread};
 
+mod btf;
+mod ctype;
+mod dwarf;
+mod exports;
+mod html;
+mod junit;
+mod markdown;
+mod modules;
+mod sarif;
+mod stats;
+mod tree;
+mod variants;
+mod versions;
+
+use versions::format_consumers;
+
+pub use exports::BinaryExports;
+pub use stats::StatsFormat;
+pub use versions::read_module_imports;
+
 #[cfg(test)]
 mod tests;
 #[cfg(test)]
+mod tests_btf;
+#[cfg(test)]
+mod tests_dwarf;
+#[cfg(test)]
+mod tests_exports;
+#[cfg(test)]
 mod tests_format;
+#[cfg(test)]
+mod tests_html;
+#[cfg(test)]
+mod tests_junit;
+#[cfg(test)]
+mod tests_markdown;
+#[cfg(test)]
+mod tests_modules;
+#[cfg(test)]
+mod tests_sarif;
+#[cfg(test)]
+mod tests_stats;
+#[cfg(test)]
+mod tests_tree;
+#[cfg(test)]
+mod tests_variants;
+#[cfg(test)]
+mod tests_versions;
 
 // Notes:
 // [1] The module uses several HashMaps that are indexed by Strings. Rust allows to do a lookup in
@@ -25,8 +75,30 @@ mod tests_format;
 // [2] HashSet in the stable Rust (1.84) doesn't provide the entry functionality. It is
 //     a nightly-only experimental API and so not used by the module.
 
+/// The maximum number of errors collected when loading symtypes data, after which the loading is
+/// stopped.
+const MAX_LOAD_ERRORS: usize = 100;
+
+/// A dialect of symtypes data, determined by the tool which produced it.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Dialect {
+    /// Data produced by `genksyms` from the preprocessed source code.
+    Genksyms,
+    /// Data produced by `gendwarfksyms` from DWARF debugging information.
+    Gendwarfksyms,
+}
+
+impl std::fmt::Display for Dialect {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Genksyms => write!(f, "genksyms"),
+            Self::Gendwarfksyms => write!(f, "gendwarfksyms"),
+        }
+    }
+}
+
 /// A token used in the description of a type.
-#[derive(Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[derive(Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
 enum Token {
     TypeRef(String),
     Atom(String),
@@ -69,15 +141,27 @@ type Exports = HashMap<String, usize>;
 /// file.
 type FileRecords = HashMap<String, usize>;
 
+/// A mapping from a type name to numbers of its variants shown to the user, indexed by the variant
+/// index in `TypeVariants`.
+type VariantNumbers<'a> = HashMap<&'a str, Vec<usize>>;
+
 /// A representation of a single `.symtypes` file.
+///
+/// The `module` specifies the name of the kernel module which the file is part of, or `vmlinux` for
+/// the kernel itself. It is [`None`] if the attribution is unknown.
 struct SymFile {
     path: PathBuf,
+    module: Option<String>,
     records: FileRecords,
 }
 
 /// A collection of `.symtypes` files.
 type SymFiles = Vec<SymFile>;
 
+/// A mapping from a symbol name to names of all modules which import it, as recorded by their
+/// version tables.
+type Consumers = HashMap<String, Vec<String>>;
+
 /// A representation of a kernel ABI, loaded from `.symtypes` files.
 ///
 /// * The `types` collection stores all types and their variants.
@@ -129,6 +213,7 @@ type SymFiles = Vec<SymFile>;
 ///     files: SymFiles[
 ///         SymFile {
 ///             path: PathBuf("test_a.symtypes"),
+///             module: None,
 ///             records: FileRecords {
 ///                 "s#foo": 0,
 ///                 "bar": 0,
@@ -136,6 +221,7 @@ type SymFiles = Vec<SymFile>;
 ///         },
 ///         SymFile {
 ///             path: PathBuf("test_b.symtypes"),
+///             module: None,
 ///             records: FileRecords {
 ///                 "s#foo": 1,
 ///                 "baz": 0,
@@ -151,11 +237,20 @@ type SymFiles = Vec<SymFile>;
 /// limit memory needed to store the corpus. On the other hand, when comparing two `Tokens` vectors
 /// for ABI equality, the code needs to consider whether all referenced subtypes are actually equal
 /// as well.
+///
+/// All data in the corpus must be in the same [`Dialect`]. It is determined when the first file
+/// which allows to recognize it is loaded.
+///
+/// When the corpus is loaded from a build directory, the `consumers` collection additionally
+/// records which modules import each symbol, as read from the `__versions` tables in their `.mod.c`
+/// files.
 #[derive(Default)]
 pub struct SymCorpus {
     types: Types,
     exports: Exports,
     files: SymFiles,
+    dialect: Option<Dialect>,
+    consumers: Consumers,
 }
 
 /// A helper struct to provide synchronized access to `SymCorpus` data during parallel loading.
@@ -163,6 +258,47 @@ struct LoadContext<'a> {
     types: RwLock<&'a mut Types>,
     exports: Mutex<&'a mut Exports>,
     files: Mutex<&'a mut SymFiles>,
+    dialect: Mutex<&'a mut Option<Dialect>>,
+    consumers: Mutex<&'a mut Consumers>,
+}
+
+impl<'a> LoadContext<'a> {
+    /// Creates a new context to load data into the specified corpus.
+    fn new(corpus: &'a mut SymCorpus) -> Self {
+        Self {
+            types: RwLock::new(&mut corpus.types),
+            exports: Mutex::new(&mut corpus.exports),
+            files: Mutex::new(&mut corpus.files),
+            dialect: Mutex::new(&mut corpus.dialect),
+            consumers: Mutex::new(&mut corpus.consumers),
+        }
+    }
+}
+
+/// A mapping from a type name to an index in `ParsedSymtypes.types`, specifying its definition in
+/// a given file.
+type ParsedRecords = HashMap<String, usize>;
+
+/// A representation of a single file described by symtypes data, before it is added to the corpus.
+struct ParsedFile {
+    path: PathBuf,
+    module: Option<String>,
+    records: ParsedRecords,
+    /// Exports defined by the file, each with a 0-based index of the line which declares it, if
+    /// applicable.
+    exports: Vec<(String, Option<usize>)>,
+}
+
+/// Symtypes data read from a single or consolidated `.symtypes` file, before it is added to the
+/// corpus.
+///
+/// The `types` collection stores all type definitions in the input, as a tuple of the type name and
+/// its tokens. The `files` collection records all files described by the input. The `dialect` is
+/// [`None`] if the input doesn't contain any record which would allow to recognize it.
+struct ParsedSymtypes {
+    types: Vec<(String, Tokens)>,
+    files: Vec<ParsedFile>,
+    dialect: Option<Dialect>,
 }
 
 /// Type names to be present in the consolidated output, along with a mapping from their internal
@@ -177,6 +313,85 @@ type ConsolidateFileTypes<'a> = HashMap<&'a str, usize>;
 /// and its new `tokens`, along with a [`Vec`] of exported symbols affected by the change.
 type CompareChangedTypes<'a> = HashMap<(&'a str, &'a Tokens, &'a Tokens), Vec<&'a str>>;
 
+/// A single change in a comparison report, recording a tuple of the modified type's `name`, its old
+/// `tokens` and its new `tokens`, along with exported symbols affected by the change.
+type CompareChange<'a> = ((&'a str, &'a Tokens, &'a Tokens), Vec<&'a str>);
+
+/// Removed, added and changed exports to be reported together, such as ones in the same module.
+#[derive(Default)]
+struct CompareReport<'a> {
+    removed: Vec<&'a str>,
+    added: Vec<&'a str>,
+    changes: Vec<CompareChange<'a>>,
+}
+
+/// A group of exports in a comparison report, identified by the module which provides them or by
+/// the index of the responsible subsystem. The groups are [`None`] for exports with an unknown
+/// module or subsystem.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+enum CompareGroup<'a> {
+    Module(Option<&'a str>),
+    Subsystem(Option<usize>),
+}
+
+/// An output format of the comparison report.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum CompareFormat {
+    #[default]
+    Text,
+    Sarif,
+    Junit,
+    Markdown,
+    Html,
+}
+
+/// Options to control the report produced by [`SymCorpus::compare_with_options()`].
+///
+/// If `maintainers` is specified, the report is grouped by subsystems and `group_by_module` is
+/// ignored. The grouping applies only to the text format, other formats ignore it.
+#[derive(Clone, Debug, Default)]
+pub struct CompareOptions {
+    /// The output format of the report.
+    pub format: CompareFormat,
+    /// Group the affected exports by the module which provides them.
+    pub group_by_module: bool,
+    /// Group the affected exports by the subsystems which are responsible for their files.
+    pub maintainers: Option<Maintainers>,
+    /// Annotate changed types with the locations of their definitions in the source tree.
+    pub source_index: Option<SourceIndex>,
+    /// Options of the diffs of changed types. Colors are used only in the text format.
+    pub diff: DiffOptions,
+}
+
+impl CompareOptions {
+    /// Returns the options of the diffs of changed types for formats which don't support colors.
+    fn plain_diff(&self) -> DiffOptions {
+        DiffOptions {
+            color: false,
+            ..self.diff.clone()
+        }
+    }
+
+    /// Returns the locations of the definition of a specified type in the source tree, formatted
+    /// as `<path>:<line>`. The result is empty if no source index is available.
+    fn type_locations(&self, name: &str) -> Vec<String> {
+        self.source_index
+            .as_ref()
+            .map_or(&[][..], |source_index| source_index.find(name))
+            .iter()
+            .map(|(path, line)| format!("{}:{}", path.display(), line))
+            .collect()
+    }
+}
+
+/// Formats the locations of the definition of a type, to be appended after its name.
+fn format_locations(locations: &[String]) -> String {
+    if locations.is_empty() {
+        return String::new();
+    }
+    format!(" ({})", locations.join(", "))
+}
+
 /// Type names processed during comparison for a specific file.
 type CompareFileTypes<'a> = HashSet<&'a str>;
 
@@ -187,6 +402,8 @@ impl SymCorpus {
             types: Types::new(),
             exports: Exports::new(),
             files: SymFiles::new(),
+            dialect: None,
+            consumers: Consumers::new(),
         }
     }
 
@@ -194,7 +411,35 @@ impl SymCorpus {
     ///
     /// The `path` can point to a single `.symtypes` file or a directory. In the latter case, the
     /// function recursively collects all `.symtypes` in that directory and loads them.
+    ///
+    /// The function doesn't stop at the first file with an error. It reports errors in all files,
+    /// up to a limit, as [`crate::Error::Multiple`], or as the plain error if only one is found.
     pub fn load<P: AsRef<Path>>(&mut self, path: P, num_workers: i32) -> Result<(), crate::Error> {
+        self.load_with_mode(path, num_workers, false)?;
+        Ok(())
+    }
+
+    /// Loads symtypes data from a given location, skipping any files with errors.
+    ///
+    /// The function works as [`SymCorpus::load()`] but continues past any `.symtypes` file which
+    /// fails to load, without adding any of its data to the corpus. Errors in the skipped files
+    /// are returned as a [`Vec`] of warnings. Only a failure to process the location itself, such
+    /// as an unreadable directory, results in an [`Err`].
+    pub fn load_lenient<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        num_workers: i32,
+    ) -> Result<Vec<crate::Error>, crate::Error> {
+        self.load_with_mode(path, num_workers, true)
+    }
+
+    /// Loads symtypes data from a given location, in the strict or lenient mode.
+    fn load_with_mode<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        num_workers: i32,
+        is_lenient: bool,
+    ) -> Result<Vec<crate::Error>, crate::Error> {
         let path = path.as_ref();
 
         // Determine if the input is a directory tree or a single symtypes file.
@@ -203,23 +448,47 @@ impl SymCorpus {
         })?;
 
         if md.is_dir() {
-            // Recursively collect symtypes files within the directory.
-            let mut symfiles = Vec::new();
-            Self::collect_symfiles(path, "", &mut symfiles)?;
+            // Recursively collect symtypes files within the directory, together with version
+            // tables of modules and the modules themselves.
+            let mut files = [Vec::new(), Vec::new(), Vec::new()];
+            Self::collect_files(path, "", &[".symtypes", ".mod.c", ".ko"], &mut files)?;
+            let [symfiles, modfiles, modules] = files;
+
+            // Fall back to BTF if the directory contains only binaries.
+            if symfiles.is_empty() && path.join("vmlinux").is_file() {
+                return self.load_btf_dir(path, &modules, num_workers, is_lenient);
+            }
 
-            // Load all found files.
-            self.load_symfiles(path, &symfiles, num_workers)
+            // Load all found files and attribute them to modules.
+            let mut warnings =
+                self.load_symfiles(path, &symfiles, &modfiles, num_workers, is_lenient)?;
+            warnings.extend(self.assign_modules(path, is_lenient)?);
+            Ok(warnings)
+        } else if is_elf_file(path)? {
+            // Load BTF from the single binary.
+            self.load_btf_file(path, is_lenient)
         } else {
             // Load the single file.
-            self.load_symfiles("", &[path], num_workers)
+            self.load_symfiles("", &[path], &[], num_workers, is_lenient)
         }
     }
 
-    /// Collects recursively all `.symtypes` files under the given root path and its subpath.
-    fn collect_symfiles<P: AsRef<Path>, Q: AsRef<Path>>(
+    /// Returns the dialect of the data in the corpus.
+    ///
+    /// An empty corpus, or one in which the dialect cannot be recognized, is reported as
+    /// [`Dialect::Genksyms`].
+    pub fn dialect(&self) -> Dialect {
+        self.dialect.unwrap_or(Dialect::Genksyms)
+    }
+
+    /// Collects recursively all files with the specified name suffixes under the given root path
+    /// and its subpath. Files with each suffix are added to the vector in `files` with the same
+    /// index.
+    fn collect_files<P: AsRef<Path>, Q: AsRef<Path>>(
         root: P,
         sub_path: Q,
-        symfiles: &mut Vec<PathBuf>,
+        suffixes: &[&str],
+        files: &mut [Vec<PathBuf>],
     ) -> Result<(), crate::Error> {
         let root = root.as_ref();
         let sub_path = sub_path.as_ref();
@@ -257,71 +526,123 @@ impl SymCorpus {
             let entry_sub_path = sub_path.join(entry.file_name());
 
             if md.is_dir() {
-                Self::collect_symfiles(root, &entry_sub_path, symfiles)?;
+                Self::collect_files(root, &entry_sub_path, suffixes, files)?;
                 continue;
             }
 
-            let ext = match entry_sub_path.extension() {
-                Some(ext) => ext,
+            let file_name = entry.file_name();
+            let file_name = match file_name.to_str() {
+                Some(file_name) => file_name,
                 None => continue,
             };
-            if ext == "symtypes" {
-                symfiles.push(entry_sub_path);
+            if let Some(i) = suffixes
+                .iter()
+                .position(|suffix| file_name.ends_with(suffix))
+            {
+                files[i].push(entry_sub_path);
             }
         }
         Ok(())
     }
 
-    /// Loads all specified `.symtypes` files.
+    /// Loads all specified `.symtypes` files and `.mod.c` files with version tables of modules.
     fn load_symfiles<P: AsRef<Path>, Q: AsRef<Path> + Sync>(
         &mut self,
         root: P,
         symfiles: &[Q],
+        modfiles: &[PathBuf],
         num_workers: i32,
-    ) -> Result<(), crate::Error> {
+        is_lenient: bool,
+    ) -> Result<Vec<crate::Error>, crate::Error> {
         let root = root.as_ref();
 
+        self.load_files(
+            symfiles.len() + modfiles.len(),
+            num_workers,
+            is_lenient,
+            |work_idx, load_context| {
+                let sub_path = match symfiles.get(work_idx) {
+                    Some(symfile) => symfile.as_ref(),
+                    None => &modfiles[work_idx - symfiles.len()],
+                };
+
+                let path = root.join(sub_path);
+                let file = PathFile::open(&path).map_err(|err| {
+                    crate::Error::new_io(&format!("Failed to open file '{}'", path.display()), err)
+                })?;
+                if work_idx < symfiles.len() {
+                    Self::load_inner(sub_path, file, load_context)
+                } else {
+                    Self::load_versions(sub_path, file, load_context)
+                }
+            },
+        )
+    }
+
+    /// Loads a given number of files in parallel, using the `load_file` function to load a file
+    /// with a specified index.
+    ///
+    /// In the strict mode, the function continues past files with errors to report as many
+    /// problems as possible at once, but stops after [`MAX_LOAD_ERRORS`] errors. All found errors
+    /// are then returned as a single [`crate::Error`].
+    ///
+    /// In the lenient mode, files with errors are skipped without leaving any of their data in the
+    /// corpus and the function returns all the encountered errors as warnings.
+    fn load_files<F>(
+        &mut self,
+        num_files: usize,
+        num_workers: i32,
+        is_lenient: bool,
+        load_file: F,
+    ) -> Result<Vec<crate::Error>, crate::Error>
+    where
+        F: Fn(usize, &LoadContext) -> Result<(), crate::Error> + Sync,
+    {
         // Load data from the files.
         let next_work_idx = AtomicUsize::new(0);
+        let errors = Mutex::new(Vec::new());
+        let is_truncated = AtomicBool::new(false);
 
-        let load_context = LoadContext {
-            types: RwLock::new(&mut self.types),
-            exports: Mutex::new(&mut self.exports),
-            files: Mutex::new(&mut self.files),
-        };
+        let load_context = LoadContext::new(self);
 
         thread::scope(|s| {
-            let mut workers = Vec::new();
             for _ in 0..num_workers {
-                workers.push(s.spawn(|| -> Result<(), crate::Error> {
-                    loop {
-                        let work_idx = next_work_idx.fetch_add(1, Ordering::Relaxed);
-                        if work_idx >= symfiles.len() {
-                            return Ok(());
-                        }
-                        let sub_path = &symfiles[work_idx].as_ref();
-
-                        let path = root.join(sub_path);
-                        let file = PathFile::open(&path).map_err(|err| {
-                            crate::Error::new_io(
-                                &format!("Failed to open file '{}'", path.display()),
-                                err,
-                            )
-                        })?;
+                s.spawn(|| loop {
+                    let work_idx = next_work_idx.fetch_add(1, Ordering::Relaxed);
+                    if work_idx >= num_files {
+                        return;
+                    }
+                    if !is_lenient && errors.lock().unwrap().len() >= MAX_LOAD_ERRORS {
+                        is_truncated.store(true, Ordering::Relaxed);
+                        return;
+                    }
 
-                        Self::load_inner(sub_path, file, &load_context)?;
+                    if let Err(err) = load_file(work_idx, &load_context) {
+                        errors.lock().unwrap().push(err);
                     }
-                }));
+                });
             }
+        });
 
-            // Join all worker threads. Return the first error if any is found, others are silently
-            // swallowed which is ok.
-            for worker in workers {
-                worker.join().unwrap()?
-            }
+        // Order the errors to make the output independent of the scheduling of the workers.
+        let mut errors = errors.into_inner().unwrap();
+        errors.sort_by(|a, b| (a.path(), a.line()).cmp(&(b.path(), b.line())));
 
-            Ok(())
-        })
+        if is_lenient {
+            return Ok(errors);
+        }
+        if errors.len() > MAX_LOAD_ERRORS {
+            errors.truncate(MAX_LOAD_ERRORS);
+            is_truncated.store(true, Ordering::Relaxed);
+        }
+        match errors.len() {
+            0 => Ok(Vec::new()),
+            1 => Err(errors.pop().unwrap()),
+            _ => Err(crate::Error::Multiple {
+                errors,
+                is_truncated: is_truncated.into_inner(),
+            }),
+        }
     }
 
     /// Loads symtypes data from a specified reader.
@@ -332,11 +653,7 @@ impl SymCorpus {
         path: P,
         reader: R,
     ) -> Result<(), crate::Error> {
-        let load_context = LoadContext {
-            types: RwLock::new(&mut self.types),
-            exports: Mutex::new(&mut self.exports),
-            files: Mutex::new(&mut self.files),
-        };
+        let load_context = LoadContext::new(self);
 
         Self::load_inner(path, reader, &load_context)?;
 
@@ -344,6 +661,9 @@ impl SymCorpus {
     }
 
     /// Loads symtypes data from a specified reader.
+    ///
+    /// The data is first fully parsed and validated, and only then added to the corpus. A file with
+    /// an error therefore leaves no partial data behind.
     fn load_inner<P: AsRef<Path>, R: Read>(
         path: P,
         reader: R,
@@ -352,176 +672,349 @@ impl SymCorpus {
         let path = path.as_ref();
         debug!("Loading '{}'", path.display());
 
-        let mut records = FileRecords::new();
-
-        // Map each variant name/index that the type has in this specific .symtypes file to one
-        // which it got assigned in the entire loaded corpus.
-        let mut remap: HashMap<String, HashMap<String, usize>> = HashMap::new();
-
         // Read all content from the file.
         let lines = match read_lines(reader) {
             Ok(lines) => lines,
             Err(err) => return Err(crate::Error::new_io("Failed to read symtypes data", err)),
         };
 
+        let parsed = Self::parse_lines(path, &lines)?;
+        Self::merge_parsed(path, parsed, load_context)
+    }
+
+    /// Parses the lines of a single or consolidated symtypes file.
+    fn parse_lines(path: &Path, lines: &[String]) -> Result<ParsedSymtypes, crate::Error> {
         // Detect whether the input is a single or consolidated symtypes file.
-        let mut is_consolidated = false;
-        for line in &lines {
-            if line.starts_with("F#") {
-                is_consolidated = true;
-                break;
-            }
-        }
+        let is_consolidated = lines.iter().any(|line| line.starts_with("F#"));
 
-        let file_idx = if !is_consolidated {
-            // Record the file early to determine its file_idx.
-            let symfile = SymFile {
-                path: path.to_path_buf(),
-                records: FileRecords::new(),
-            };
+        let mut types = Vec::new();
 
-            let mut files = load_context.files.lock().unwrap();
-            files.push(symfile);
-            files.len() - 1
-        } else {
-            usize::MAX
-        };
+        // Map each type name and its variant name/index in this specific .symtypes file to an index
+        // in `types`.
+        let mut type_indices: HashMap<String, HashMap<String, usize>> = HashMap::new();
 
         // Track names of all entries to detect duplicates.
         let mut all_names = HashSet::new();
 
+        // Track type references which could not be resolved immediately when their record was
+        // read. These are typically references to types which are defined later in the file.
+        let mut pending_refs = Vec::new();
+
         // Parse all declarations.
         let mut file_indices = Vec::new();
+        let mut module_indices = Vec::new();
+        let mut exports = Vec::new();
+        let mut dialect = None;
         for (line_idx, line) in lines.iter().enumerate() {
             // Obtain a name of the record.
-            let mut words = line.split_ascii_whitespace();
-            let name = words.next().ok_or_else(|| {
-                crate::Error::new_parse(&format!(
-                    "{}:{}: Expected a record name",
-                    path.display(),
-                    line_idx + 1
-                ))
-            })?;
+            let words = split_words(path, line_idx, line)?;
+            let mut words = words.into_iter();
+            let name = words
+                .next()
+                .ok_or_else(|| crate::Error::MissingRecordName {
+                    path: path.to_path_buf(),
+                    line: line_idx + 1,
+                })?;
 
             // Check if the record is a duplicate of another one.
             match all_names.get(name) {
                 Some(_) => {
-                    return Err(crate::Error::new_parse(&format!(
-                        "{}:{}: Duplicate record '{}'",
-                        path.display(),
-                        line_idx + 1,
-                        name,
-                    )))
+                    return Err(crate::Error::DuplicateRecord {
+                        name: name.to_string(),
+                        path: path.to_path_buf(),
+                        line: line_idx + 1,
+                    })
                 }
-                None => all_names.insert(name.to_string()),
+                None => all_names.insert(name),
             };
 
             // Check for a file declaration and remember its index. File declarations are processed
-            // later after remapping of all symbol variants is known.
+            // later after all types are known.
             if name.starts_with("F#") {
                 file_indices.push(line_idx);
                 continue;
             }
 
+            // Similarly, remember a module declaration which is valid only in a consolidated file.
+            if name.starts_with("M#") {
+                if !is_consolidated {
+                    return Err(crate::Error::new_parse(
+                        path,
+                        Some(line_idx + 1),
+                        "Module record is allowed only in a consolidated file",
+                    ));
+                }
+                module_indices.push(line_idx);
+                continue;
+            }
+
             // Handle a type/export record.
 
             // Turn the remaining words into tokens.
             let tokens = words_into_tokens(&mut words);
 
+            // Recognize the dialect of the data. A gendwarfksyms record starts with a DWARF tag,
+            // a typedef record can be produced by both tools.
+            let record_dialect = if dwarf::is_gendwarfksyms_type(&tokens) {
+                Some(Dialect::Gendwarfksyms)
+            } else if tokens
+                .first()
+                .is_some_and(|token| token.as_str() != "typedef")
+            {
+                Some(Dialect::Genksyms)
+            } else {
+                None
+            };
+            if let Some(record_dialect) = record_dialect {
+                match dialect {
+                    Some(dialect) if dialect != record_dialect => {
+                        return Err(crate::Error::new_parse(
+                            path,
+                            Some(line_idx + 1),
+                            &format!(
+                                "Record is in the {} dialect but previous records are in the {} dialect",
+                                record_dialect, dialect
+                            ),
+                        ))
+                    }
+                    _ => dialect = Some(record_dialect),
+                }
+            }
+
             // Parse the base name and any variant name/index, which is appended as a suffix after
             // the `@` character.
-            let (base_name, orig_variant_name) = if is_consolidated {
+            let (base_name, variant_name) = if is_consolidated {
                 split_type_name(name)
             } else {
                 (name, &name[name.len()..])
             };
+            let base_name = unquote_name(base_name);
+            let base_name = base_name.as_ref();
 
-            // Insert the type into the corpus.
-            let variant_idx = Self::merge_type(base_name, tokens, load_context);
+            // Check that all type references can be resolved. Fast path: the referenced type has
+            // been already seen, otherwise check the reference after the whole file is read.
+            for token in &tokens {
+                if let Token::TypeRef(ref_name) = token {
+                    if !type_indices.contains_key(ref_name) {
+                        pending_refs.push((line_idx, ref_name.clone()));
+                    }
+                }
+            }
 
-            if is_consolidated {
-                // Record a mapping from the original variant name/index to the new one.
-                remap
-                    .entry(base_name.to_string()) // [1]
-                    .or_default()
-                    .insert(orig_variant_name.to_string(), variant_idx);
-            } else {
-                // Insert the record.
-                records.insert(base_name.to_string(), variant_idx);
-                Self::try_insert_export(base_name, file_idx, line_idx, load_context)?;
+            type_indices
+                .entry(base_name.to_string()) // [1]
+                .or_default()
+                .insert(variant_name.to_string(), types.len());
+            types.push((base_name.to_string(), tokens));
+
+            if !is_consolidated && is_export_name(base_name) {
+                exports.push((base_name.to_string(), Some(line_idx)));
             }
         }
 
-        // TODO Validate all references?
+        // Validate all remaining type references.
+        for (line_idx, ref_name) in pending_refs {
+            if !type_indices.contains_key(&ref_name) {
+                return Err(crate::Error::UnknownType {
+                    name: ref_name,
+                    path: path.to_path_buf(),
+                    line: line_idx + 1,
+                });
+            }
+        }
 
         if !is_consolidated {
-            // Update the file records.
-            let mut files = load_context.files.lock().unwrap();
-            files[file_idx].records = records;
-            return Ok(());
+            // A single file has exactly one variant of each type.
+            let records = type_indices
+                .into_iter()
+                .map(|(name, variants)| (name, variants.into_values().next().unwrap()))
+                .collect();
+            let file = ParsedFile {
+                path: path.to_path_buf(),
+                module: None,
+                records,
+                exports,
+            };
+            return Ok(ParsedSymtypes {
+                types,
+                files: vec![file],
+                dialect,
+            });
         }
 
         // Consolidated file needs more work.
 
         // Handle file declarations.
+        let mut files = Vec::new();
         for line_idx in file_indices {
-            let mut words = lines[line_idx].split_ascii_whitespace();
+            let words = split_words(path, line_idx, &lines[line_idx])?;
+            let mut words = words.into_iter();
 
-            let record_name = words.next().unwrap();
+            let record_name = unquote_name(words.next().unwrap());
             assert!(record_name.starts_with("F#"));
             let file_name = &record_name[2..];
 
-            let file_idx = {
-                let symfile = SymFile {
-                    path: Path::new(file_name).to_path_buf(),
-                    records: FileRecords::new(),
-                };
-                let mut files = load_context.files.lock().unwrap();
-                files.push(symfile);
-                files.len() - 1
-            };
-
-            let mut records = FileRecords::new();
+            let mut records = ParsedRecords::new();
+            let mut exports = Vec::new();
             for type_name in words {
                 // Parse the base name and variant name/index.
-                let (base_name, orig_variant_name) = split_type_name(type_name);
+                let (base_name, variant_name) = split_type_name(type_name);
+                let base_name = unquote_name(base_name);
+                let base_name = base_name.as_ref();
 
-                // Look up how the variant got remapped.
-                let variant_idx = *remap
+                // Look up the type.
+                let type_idx = *type_indices
                     .get(base_name)
-                    .and_then(|hash| hash.get(orig_variant_name))
-                    .ok_or_else(|| {
-                        crate::Error::new_parse(&format!(
-                            "{}:{}: Type '{}' is not known",
-                            path.display(),
-                            line_idx + 1,
-                            type_name
-                        ))
+                    .and_then(|hash| hash.get(variant_name))
+                    .ok_or_else(|| crate::Error::UnknownType {
+                        name: type_name.to_string(),
+                        path: path.to_path_buf(),
+                        line: line_idx + 1,
                     })?;
 
                 // Insert the record.
-                records.insert(base_name.to_string(), variant_idx);
-                Self::try_insert_export(base_name, file_idx, line_idx, load_context)?;
+                records.insert(base_name.to_string(), type_idx);
+                if is_export_name(base_name) {
+                    exports.push((base_name.to_string(), Some(line_idx)));
+                }
             }
 
             // Add implicit references, ones that were omitted by the F# declaration because only
             // one variant exists in the entire consolidated file.
-            let walk_records: Vec<_> = records.iter().map(|(k, v)| (k.clone(), *v)).collect();
-            for (name, variant_idx) in walk_records {
-                let types = load_context.types.read().unwrap();
+            let walk_records: Vec<_> = records.values().copied().collect();
+            for type_idx in walk_records {
                 Self::extrapolate_file_record(
                     path,
                     file_name,
-                    &name,
-                    variant_idx,
-                    true,
-                    *types,
+                    type_idx,
+                    &types,
+                    &type_indices,
                     &mut records,
                 )?;
             }
 
+            files.push(ParsedFile {
+                path: PathBuf::from(file_name),
+                module: None,
+                records,
+                exports,
+            });
+        }
+
+        // Handle module declarations.
+        for line_idx in module_indices {
+            let words = split_words(path, line_idx, &lines[line_idx])?;
+            let mut words = words.into_iter();
+
+            let record_name = unquote_name(words.next().unwrap());
+            assert!(record_name.starts_with("M#"));
+            let module = &record_name[2..];
+
+            for file_name in words {
+                let file_name = unquote_name(file_name);
+                let file = files
+                    .iter_mut()
+                    .find(|file| file.path == Path::new(file_name.as_ref()))
+                    .ok_or_else(|| {
+                        crate::Error::new_parse(
+                            path,
+                            Some(line_idx + 1),
+                            &format!("Module record refers to unknown file '{}'", file_name),
+                        )
+                    })?;
+                file.module = Some(module.to_string());
+            }
+        }
+
+        Ok(ParsedSymtypes {
+            types,
+            files,
+            dialect,
+        })
+    }
+
+    /// Adds parsed symtypes data to the corpus.
+    ///
+    /// All exports are checked for duplicates first and the dialect of the data is checked to
+    /// match the corpus. If any problem is found, the function returns an error without modifying
+    /// the corpus.
+    fn merge_parsed(
+        path: &Path,
+        parsed: ParsedSymtypes,
+        load_context: &LoadContext,
+    ) -> Result<(), crate::Error> {
+        // Register the files and their exports.
+        let first_file_idx = {
+            let mut exports = load_context.exports.lock().unwrap();
             let mut files = load_context.files.lock().unwrap();
-            files[file_idx].records = records;
+            let mut dialect = load_context.dialect.lock().unwrap();
+
+            if let (Some(corpus_dialect), Some(parsed_dialect)) = (**dialect, parsed.dialect) {
+                if corpus_dialect != parsed_dialect {
+                    return Err(crate::Error::new_parse(
+                        path,
+                        None,
+                        &format!(
+                            "Data is in the {} dialect but the corpus is in the {} dialect",
+                            parsed_dialect, corpus_dialect
+                        ),
+                    ));
+                }
+            }
+
+            let mut new_exports = HashMap::new();
+            for (i, parsed_file) in parsed.files.iter().enumerate() {
+                for (name, line_idx) in &parsed_file.exports {
+                    let previous_path = match exports.get(name) {
+                        Some(&other_file_idx) => Some(&files[other_file_idx].path),
+                        None => new_exports
+                            .get(name.as_str())
+                            .map(|&other_idx: &usize| &parsed.files[other_idx].path),
+                    };
+                    if let Some(previous_path) = previous_path {
+                        return Err(crate::Error::DuplicateExport {
+                            name: name.clone(),
+                            path: parsed_file.path.clone(),
+                            line: line_idx.map(|line_idx| line_idx + 1),
+                            previous_path: previous_path.clone(),
+                        });
+                    }
+                    new_exports.insert(name.as_str(), i);
+                }
+            }
+
+            let first_file_idx = files.len();
+            for parsed_file in &parsed.files {
+                files.push(SymFile {
+                    path: parsed_file.path.clone(),
+                    module: parsed_file.module.clone(),
+                    records: FileRecords::new(),
+                });
+            }
+            for (name, i) in new_exports {
+                exports.insert(name.to_string(), first_file_idx + i);
+            }
+            if parsed.dialect.is_some() {
+                **dialect = parsed.dialect;
+            }
+            first_file_idx
+        };
+
+        // Insert the types into the corpus and record how their variants got remapped.
+        let variant_indices = parsed
+            .types
+            .into_iter()
+            .map(|(name, tokens)| Self::merge_type(&name, tokens, load_context))
+            .collect::<Vec<_>>();
+
+        // Update the file records.
+        let mut files = load_context.files.lock().unwrap();
+        for (i, parsed_file) in parsed.files.into_iter().enumerate() {
+            files[first_file_idx + i].records = parsed_file
+                .records
+                .into_iter()
+                .map(|(name, type_idx)| (name, variant_indices[type_idx]))
+                .collect();
         }
 
         Ok(())
@@ -548,44 +1041,7 @@ impl SymCorpus {
         }
     }
 
-    /// Checks if a specified `type_name` is an export and, if so, registers it with its `file_idx`
-    /// in the `load_context.exports`.
-    fn try_insert_export(
-        type_name: &str,
-        file_idx: usize,
-        line_idx: usize,
-        load_context: &LoadContext,
-    ) -> Result<(), crate::Error> {
-        if !is_export_name(type_name) {
-            return Ok(());
-        }
-
-        // Try to add the export, return an error if it is a duplicate.
-        let other_file_idx = {
-            let mut exports = load_context.exports.lock().unwrap();
-            match exports.entry(type_name.to_string()) // [1]
-            {
-                Occupied(export_entry) => *export_entry.get(),
-                Vacant(export_entry) => {
-                    export_entry.insert(file_idx);
-                    return Ok(());
-                }
-            }
-        };
-
-        let files = load_context.files.lock().unwrap();
-        let path = &files[file_idx].path;
-        let other_path = &files[other_file_idx].path;
-        Err(crate::Error::new_parse(&format!(
-            "{}:{}: Export '{}' is duplicate. Previous occurrence found in '{}'.",
-            path.display(),
-            line_idx + 1,
-            type_name,
-            other_path.display()
-        )))
-    }
-
-    /// Processes a single symbol in some file originated from an `F#` record and enhances the
+    /// Processes a single type in some file originated from an `F#` record and enhances the
     /// specified file records with the needed implicit types.
     ///
     /// This function is used when reading a consolidated input file and processing its `F#`
@@ -595,62 +1051,50 @@ impl SymCorpus {
     /// such implicit references and add them to `records`.
     ///
     /// A caller of this function should pre-fill `records` with all explicit references given on
-    /// the processed `F#` record and then call this function on each of the references. These root
-    /// calls should be invoked with `is_explicit` set to `true`. The function then recursively adds
-    /// all needed implicit types which are referenced from these roots.
+    /// the processed `F#` record and then call this function on each of them. The function then
+    /// recursively adds all needed implicit types which are referenced from these roots.
     fn extrapolate_file_record(
         corpus_path: &Path,
         file_name: &str,
-        name: &str,
-        variant_idx: usize,
-        is_explicit: bool,
-        types: &Types,
-        records: &mut FileRecords,
+        type_idx: usize,
+        types: &[(String, Tokens)],
+        type_indices: &HashMap<String, HashMap<String, usize>>,
+        records: &mut ParsedRecords,
     ) -> Result<(), crate::Error> {
-        if is_explicit {
-            // All explicit symbols need to be added by the caller.
-            assert!(records.get(name).is_some());
-        } else {
-            // A symbol can be implicit only if it has one variant.
-            assert!(variant_idx == 0);
-
-            // See if the symbol was already processed.
-            if records.get(name).is_some() {
-                return Ok(());
-            }
-            records.insert(name.to_string(), variant_idx); // [1]
-        }
-
-        // Obtain tokens for the selected variant and check it is correctly specified.
-        let variants = types.get(name).unwrap();
-        assert!(!variants.is_empty());
-        if !is_explicit && variants.len() > 1 {
-            return Err(crate::Error::new_parse(&format!(
-                "{}: Type '{}' is implicitly referenced by file '{}' but has multiple variants in the corpus",
-                corpus_path.display(),
-                name,
-                file_name,
-            )));
-        }
-        let tokens = &variants[variant_idx];
-
-        // Process recursively all types referenced by this symbol.
-        for token in tokens {
+        // Process recursively all types referenced by this type.
+        for token in &types[type_idx].1 {
             match token {
                 Token::TypeRef(ref_name) => {
-                    // Process the type. Note that passing variant_idx=0 is ok here:
-                    // * If the type is explicitly specified in the parent F# record then it must be
-                    //   already added in the records and the called function immediately returns.
-                    // * If the type is implicit then it can have only one variant and so only
-                    //   variant_idx=0 can be correct. The invoked function will check that no more
-                    //   than one variant is actually present.
+                    // Skip types which are explicitly specified in the F# record or which were
+                    // already processed.
+                    if records.contains_key(ref_name) {
+                        continue;
+                    }
+
+                    // A type can be implicit only if it has one variant.
+                    let variants = type_indices.get(ref_name).ok_or_else(|| {
+                        crate::Error::UnknownFileType {
+                            name: ref_name.clone(),
+                            path: corpus_path.to_path_buf(),
+                            file_path: PathBuf::from(file_name),
+                        }
+                    })?;
+                    if variants.len() > 1 {
+                        return Err(crate::Error::AmbiguousFileType {
+                            name: ref_name.clone(),
+                            path: corpus_path.to_path_buf(),
+                            file_path: PathBuf::from(file_name),
+                        });
+                    }
+                    let ref_type_idx = *variants.values().next().unwrap();
+
+                    records.insert(ref_name.clone(), ref_type_idx);
                     Self::extrapolate_file_record(
                         corpus_path,
                         file_name,
-                        ref_name,
-                        0,
-                        false,
+                        ref_type_idx,
                         types,
+                        type_indices,
                         records,
                     )?;
                 }
@@ -664,29 +1108,24 @@ impl SymCorpus {
     /// Processes a single symbol specified in a given file and adds it to the consolidated output.
     ///
     /// The specified symbol is added to `output_types` and `processed_types`, if not already
-    /// present, and all its type references get recursively processed in the same way.
+    /// present, and all its type references get recursively processed in the same way. The
+    /// `export` parameter identifies the top-level exported symbol being processed.
     fn consolidate_type<'a>(
         &'a self,
         symfile: &SymFile,
         name: &'a str,
+        export: &str,
         output_types: &mut ConsolidateOutputTypes<'a>,
         processed_types: &mut ConsolidateFileTypes<'a>,
-    ) {
+    ) -> Result<(), crate::Error> {
         // See if the symbol was already processed.
         let processed_entry = match processed_types.entry(name) {
-            Occupied(_) => return,
+            Occupied(_) => return Ok(()),
             Vacant(processed_entry) => processed_entry,
         };
 
-        // Look up the internal variant index.
-        let variant_idx = match symfile.records.get(name) {
-            Some(&variant_idx) => variant_idx,
-            None => panic!(
-                "Type '{}' is not known in file '{}'",
-                name,
-                symfile.path.display()
-            ),
-        };
+        // Look up the internal variant index and the type tokens.
+        let (variant_idx, tokens) = Self::get_type_variant(self, symfile, name, export)?;
 
         // Determine the output variant index for the symbol.
         let remap_idx;
@@ -712,19 +1151,16 @@ impl SymCorpus {
         processed_entry.insert(remap_idx);
 
         // Process recursively all types that the symbol references.
-        let variants = match self.types.get(name) {
-            Some(variants) => variants,
-            None => panic!("Type '{}' has a missing declaration", name),
-        };
-
-        for token in &variants[variant_idx] {
+        for token in tokens {
             match token {
                 Token::TypeRef(ref_name) => {
-                    self.consolidate_type(symfile, ref_name, output_types, processed_types)
+                    self.consolidate_type(symfile, ref_name, export, output_types, processed_types)?
                 }
                 Token::Atom(_word) => {}
             }
         }
+
+        Ok(())
     }
 
     /// Writes the corpus in the consolidated form into a specified file.
@@ -778,7 +1214,13 @@ impl SymCorpus {
             // Add the exported types and their needed types to the output.
             let mut processed_types = ConsolidateFileTypes::new();
             for name in &exports {
-                self.consolidate_type(symfile, name, &mut output_types, &mut processed_types);
+                self.consolidate_type(
+                    symfile,
+                    name,
+                    name,
+                    &mut output_types,
+                    &mut processed_types,
+                )?;
             }
             file_types[i] = processed_types;
         }
@@ -813,12 +1255,17 @@ impl SymCorpus {
                 let tokens = &variants[variant_idx];
 
                 if needs_suffix {
-                    write!(writer, "{}@{}", name, remap_idx).map_io_err(err_desc)?;
+                    write!(writer, "{}@{}", quote_name(name), remap_idx).map_io_err(err_desc)?;
                 } else {
-                    write!(writer, "{}", name).map_io_err(err_desc)?;
+                    write!(writer, "{}", quote_name(name)).map_io_err(err_desc)?;
                 }
                 for token in tokens {
-                    write!(writer, " {}", token.as_str()).map_io_err(err_desc)?;
+                    match token {
+                        Token::TypeRef(ref_name) => {
+                            write!(writer, " {}", quote_name(ref_name)).map_io_err(err_desc)?
+                        }
+                        Token::Atom(word) => write!(writer, " {}", word).map_io_err(err_desc)?,
+                    }
                 }
                 writeln!(writer).map_io_err(err_desc)?;
             }
@@ -838,36 +1285,121 @@ impl SymCorpus {
             // Output the F# record in form `F#<filename> <type@variant>... <export>...`. Types with
             // only one variant in the entire consolidated file can be skipped because they can be
             // implicitly determined by a reader.
-            write!(writer, "F#{}", symfile.path.display()).map_io_err(err_desc)?;
+            let file_name = format!("F#{}", symfile.path.display());
+            write!(writer, "{}", quote_name(&file_name)).map_io_err(err_desc)?;
             for &(_, name, remap_idx) in &sorted_types {
                 if remap_idx != usize::MAX {
-                    write!(writer, " {}@{}", name, remap_idx).map_io_err(err_desc)?;
+                    write!(writer, " {}@{}", quote_name(name), remap_idx).map_io_err(err_desc)?;
                 } else if is_export_name(name) {
-                    write!(writer, " {}", name).map_io_err(err_desc)?;
+                    write!(writer, " {}", quote_name(name)).map_io_err(err_desc)?;
                 }
             }
             writeln!(writer).map_io_err(err_desc)?;
         }
+
+        // Write module records in form `M#<module> <filename>...`, sorted by the module name.
+        let mut modules: BTreeMap<&str, Vec<&Path>> = BTreeMap::new();
+        for &i in &file_indices {
+            let symfile = &self.files[i];
+            if let Some(module) = &symfile.module {
+                modules.entry(module).or_default().push(&symfile.path);
+            }
+        }
+        for (module, paths) in modules {
+            write!(writer, "{}", quote_name(&format!("M#{}", module))).map_io_err(err_desc)?;
+            for path in paths {
+                let file_name = path.display().to_string();
+                write!(writer, " {}", quote_name(&file_name)).map_io_err(err_desc)?;
+            }
+            writeln!(writer).map_io_err(err_desc)?;
+        }
         Ok(())
     }
 
+    /// Obtains the variant index and tokens which describe a specified type name, in a given corpus
+    /// and file.
+    ///
+    /// The `export` parameter identifies the top-level exported symbol being processed and is used
+    /// to provide context in case of an error.
+    fn get_type_variant<'a>(
+        symtypes: &'a SymCorpus,
+        file: &SymFile,
+        name: &str,
+        export: &str,
+    ) -> Result<(usize, &'a Tokens), crate::Error> {
+        let variant_idx = *file
+            .records
+            .get(name)
+            .ok_or_else(|| crate::Error::MissingFileType {
+                name: name.to_string(),
+                file_path: file.path.clone(),
+                export: export.to_string(),
+            })?;
+        match symtypes
+            .types
+            .get(name)
+            .and_then(|variants| variants.get(variant_idx))
+        {
+            Some(tokens) => Ok((variant_idx, tokens)),
+            None => Err(crate::Error::MissingTypeVariant {
+                name: name.to_string(),
+                variant_idx,
+                file_path: file.path.clone(),
+                export: export.to_string(),
+            }),
+        }
+    }
+
     /// Obtains tokens which describe a specified type name, in a given corpus and file.
-    fn get_type_tokens<'a>(symtypes: &'a SymCorpus, file: &SymFile, name: &str) -> &'a Tokens {
-        match file.records.get(name) {
-            Some(&variant_idx) => match symtypes.types.get(name) {
-                Some(variants) => &variants[variant_idx],
-                None => {
-                    panic!("Type '{}' has a missing declaration", name);
+    fn get_type_tokens<'a>(
+        symtypes: &'a SymCorpus,
+        file: &SymFile,
+        name: &str,
+        export: &str,
+    ) -> Result<&'a Tokens, crate::Error> {
+        Ok(Self::get_type_variant(symtypes, file, name, export)?.1)
+    }
+
+    /// Numbers the variants of all types in the corpus in a deterministic way, to be shown to the
+    /// user.
+    ///
+    /// The variant indices in `TypeVariants` follow the order in which the files were loaded, which
+    /// depends on the order of directory entries and on the scheduling of workers. The variants are
+    /// instead numbered in the order of the first file which uses each of them, with the files
+    /// sorted by their path, the same as in the consolidated output. Variants which are not used by
+    /// any file are numbered last.
+    fn get_variant_numbers(&self) -> VariantNumbers<'_> {
+        // Sort all files in the corpus by their path.
+        let mut file_indices = (0..self.files.len()).collect::<Vec<_>>();
+        file_indices.sort_by_key(|&i| &self.files[i].path);
+
+        // Record the order in which the variants are first used.
+        let mut orders: HashMap<&str, Vec<usize>> = HashMap::new();
+        for &i in &file_indices {
+            for (name, &variant_idx) in &self.files[i].records {
+                let order = orders.entry(name.as_str()).or_default();
+                if !order.contains(&variant_idx) {
+                    order.push(variant_idx);
                 }
-            },
-            None => {
-                panic!(
-                    "Type '{}' is not known in file '{}'",
-                    name,
-                    file.path.display()
-                )
             }
         }
+
+        // Number the variants according to the order.
+        self.types
+            .iter()
+            .map(|(name, variants)| {
+                let mut order = orders.remove(name.as_str()).unwrap_or_default();
+                let unused = (0..variants.len())
+                    .filter(|variant_idx| !order.contains(variant_idx))
+                    .collect::<Vec<_>>();
+                order.extend(unused);
+                let mut numbers = vec![0; variants.len()];
+                for (number, variant_idx) in order.into_iter().enumerate() {
+                    numbers[variant_idx] = number;
+                }
+                (name.as_str(), numbers)
+            })
+            .collect()
     }
 
     /// Compares the definition of the symbol `name` in (`corpus`, `file`) with its definition in
@@ -886,16 +1418,16 @@ impl SymCorpus {
         export: &'a str,
         changes: &Mutex<CompareChangedTypes<'a>>,
         processed: &mut CompareFileTypes<'a>,
-    ) {
+    ) -> Result<(), crate::Error> {
         // See if the symbol was already processed.
         if processed.get(name).is_some() {
-            return;
+            return Ok(());
         }
         processed.insert(name); // [2]
 
         // Look up how the symbol is defined in each corpus.
-        let tokens = Self::get_type_tokens(corpus, file, name);
-        let other_tokens = Self::get_type_tokens(other_corpus, other_file, name);
+        let tokens = Self::get_type_tokens(corpus, file, name, export)?;
+        let other_tokens = Self::get_type_tokens(other_corpus, other_file, name, export)?;
 
         // Compare the immediate tokens.
         let is_equal = tokens.len() == other_tokens.len()
@@ -921,7 +1453,7 @@ impl SymCorpus {
                         export,
                         changes,
                         processed,
-                    );
+                    )?;
                 }
             }
         } else {
@@ -937,7 +1469,7 @@ impl SymCorpus {
                                     export,
                                     changes,
                                     processed,
-                                );
+                                )?;
                                 break;
                             }
                         }
@@ -945,6 +1477,8 @@ impl SymCorpus {
                 }
             }
         }
+
+        Ok(())
     }
 
     /// Compares symbols in the `self` and `other_corpus`.
@@ -956,60 +1490,324 @@ impl SymCorpus {
         writer: W,
         num_workers: i32,
     ) -> Result<(), crate::Error> {
+        self.compare_with_options(
+            other_corpus,
+            &CompareOptions::default(),
+            writer,
+            num_workers,
+        )
+    }
+
+    /// Compares symbols in the `self` and `other_corpus`, with the report controlled by the
+    /// specified `options`.
+    ///
+    /// A report about all found changes is written to the provided output stream, in the format
+    /// selected by `options`.
+    pub fn compare_with_options<W: Write>(
+        &self,
+        other_corpus: &SymCorpus,
+        options: &CompareOptions,
+        writer: W,
+        num_workers: i32,
+    ) -> Result<(), crate::Error> {
+        self.compare_exports_with(other_corpus, None, options, writer, num_workers)?;
+        Ok(())
+    }
+
+    /// Checks symbols imported by a module against the `self` reference and the `other_corpus`.
+    ///
+    /// A human-readable report about all imports which have been removed or changed is written to
+    /// the provided output stream. Imports which are not present in the reference are ignored.
+    ///
+    /// The function returns the number of reported imports.
+    pub fn check_module_with<W: Write>(
+        &self,
+        other_corpus: &SymCorpus,
+        imports: &[String],
+        writer: W,
+        num_workers: i32,
+    ) -> Result<usize, crate::Error> {
+        let imports = imports.iter().map(String::as_str).collect();
+        self.compare_exports_with(
+            other_corpus,
+            Some(&imports),
+            &CompareOptions::default(),
+            writer,
+            num_workers,
+        )
+    }
+
+    /// Compares symbols in the `self` and `other_corpus`, optionally limited only to exports in
+    /// `filter`, and writes a human-readable report to the provided output stream.
+    ///
+    /// The function returns the number of reported exports. Added exports are reported only when no
+    /// filter is specified and are not included in the count.
+    fn compare_exports_with<W: Write>(
+        &self,
+        other_corpus: &SymCorpus,
+        filter: Option<&HashSet<&str>>,
+        options: &CompareOptions,
+        writer: W,
+        num_workers: i32,
+    ) -> Result<usize, crate::Error> {
         let mut writer = BufWriter::new(writer);
-        let err_desc = "Failed to write a comparison result";
+        let is_selected = |name: &str| filter.is_none_or(|filter| filter.contains(name));
 
         // Check for symbols in self but not in other_corpus, and vice versa.
-        for (exports_a, exports_b, change) in [
-            (&self.exports, &other_corpus.exports, "removed"),
-            (&other_corpus.exports, &self.exports, "added"),
-        ] {
-            for name in exports_a.keys() {
-                if !exports_b.contains_key(name) {
-                    writeln!(writer, "Export '{}' has been {}", name, change)
-                        .map_io_err(err_desc)?;
-                }
-            }
-        }
+        let mut removed = self
+            .exports
+            .keys()
+            .filter(|name| !other_corpus.exports.contains_key(*name) && is_selected(name))
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+        removed.sort();
+        let mut added = other_corpus
+            .exports
+            .keys()
+            .filter(|name| !self.exports.contains_key(*name) && filter.is_none())
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+        added.sort();
 
         // Compare symbols that are in both corpuses.
-        let works: Vec<_> = self.exports.iter().collect();
+        let works: Vec<_> = self
+            .exports
+            .iter()
+            .filter(|(name, _)| is_selected(name))
+            .collect();
         let next_work_idx = AtomicUsize::new(0);
 
         let changes = Mutex::new(CompareChangedTypes::new());
 
-        thread::scope(|s| {
+        thread::scope(|s| -> Result<(), crate::Error> {
+            let mut workers = Vec::new();
             for _ in 0..num_workers {
-                s.spawn(|| loop {
-                    let work_idx = next_work_idx.fetch_add(1, Ordering::Relaxed);
-                    if work_idx >= works.len() {
-                        break;
-                    }
-                    let (name, file_idx) = works[work_idx];
-
-                    let file = &self.files[*file_idx];
-                    if let Some(other_file_idx) = other_corpus.exports.get(name) {
-                        let other_file = &other_corpus.files[*other_file_idx];
-                        let mut processed = CompareFileTypes::new();
-                        Self::compare_types(
-                            (self, file),
-                            (other_corpus, other_file),
-                            name,
-                            name,
-                            &changes,
-                            &mut processed,
-                        );
+                workers.push(s.spawn(|| -> Result<(), crate::Error> {
+                    loop {
+                        let work_idx = next_work_idx.fetch_add(1, Ordering::Relaxed);
+                        if work_idx >= works.len() {
+                            return Ok(());
+                        }
+                        let (name, file_idx) = works[work_idx];
+
+                        let file = &self.files[*file_idx];
+                        if let Some(other_file_idx) = other_corpus.exports.get(name) {
+                            let other_file = &other_corpus.files[*other_file_idx];
+                            let mut processed = CompareFileTypes::new();
+                            Self::compare_types(
+                                (self, file),
+                                (other_corpus, other_file),
+                                name,
+                                name,
+                                &changes,
+                                &mut processed,
+                            )?;
+                        }
                     }
-                });
+                }));
             }
-        });
 
-        // Format and output collected changes.
+            // Join all worker threads. Return the first error if any is found, others are silently
+            // swallowed which is ok.
+            for worker in workers {
+                worker.join().unwrap()?
+            }
+
+            Ok(())
+        })?;
+
+        // Sort the collected changes.
         let changes = changes.into_inner().unwrap(); // Get the inner HashMap.
         let mut changes = changes.into_iter().collect::<Vec<_>>();
         changes.iter_mut().for_each(|(_, exports)| exports.sort());
         changes.sort();
 
+        // Count the removed and changed exports. An export is counted only once, even if it is
+        // affected by several changed types or reported in several groups.
+        let num_reported = removed
+            .iter()
+            .chain(changes.iter().flat_map(|(_, exports)| exports))
+            .collect::<HashSet<_>>()
+            .len();
+
+        // Write the report and flush it explicitly, so a failure to write its final part is not
+        // silently lost.
+        let out = writer.by_ref();
+        match options.format {
+            CompareFormat::Text if !options.group_by_module && options.maintainers.is_none() => {
+                self.write_compare_report(other_corpus, &removed, &added, &changes, options, out)?
+            }
+            CompareFormat::Text => {
+                self.write_grouped_report(other_corpus, &removed, &added, &changes, options, out)?
+            }
+            CompareFormat::Sarif => {
+                self.write_sarif_report(other_corpus, &removed, &added, &changes, options, out)?
+            }
+            CompareFormat::Junit => {
+                self.write_junit_report(other_corpus, &removed, &added, &changes, options, out)?
+            }
+            CompareFormat::Markdown => {
+                self.write_markdown_report(other_corpus, &removed, &added, &changes, options, out)?
+            }
+            CompareFormat::Html => {
+                self.write_html_report(other_corpus, &removed, &added, &changes, options, out)?
+            }
+        }
+        writer
+            .flush()
+            .map_io_err("Failed to write a comparison result")?;
+        Ok(num_reported)
+    }
+
+    /// Writes a human-readable report about removed, added and changed exports to the provided
+    /// output stream, grouped as requested by `options`.
+    fn write_grouped_report<'a, W: Write>(
+        &'a self,
+        other_corpus: &'a SymCorpus,
+        removed: &[&'a str],
+        added: &[&'a str],
+        changes: &[CompareChange<'a>],
+        options: &CompareOptions,
+        mut writer: W,
+    ) -> Result<(), crate::Error> {
+        // Split the report by groups of the affected exports. Added exports are attributed
+        // according to the other corpus, all others according to self. An export can belong to
+        // multiple subsystems.
+        let mut groups: BTreeMap<CompareGroup, CompareReport> = BTreeMap::new();
+        for &name in removed {
+            for group in self.export_groups(name, options) {
+                groups.entry(group).or_default().removed.push(name);
+            }
+        }
+        for &name in added {
+            for group in other_corpus.export_groups(name, options) {
+                groups.entry(group).or_default().added.push(name);
+            }
+        }
+        for (change, exports) in changes {
+            for &name in exports {
+                for group in self.export_groups(name, options) {
+                    let group_changes = &mut groups.entry(group).or_default().changes;
+                    match group_changes.last_mut() {
+                        Some((last_change, last_exports)) if last_change == change => {
+                            last_exports.push(name)
+                        }
+                        _ => group_changes.push((*change, vec![name])),
+                    }
+                }
+            }
+        }
+
+        let err_desc = "Failed to write a comparison result";
+        for (i, (key, group)) in groups.into_iter().enumerate() {
+            if i > 0 {
+                writeln!(writer).map_io_err(err_desc)?;
+            }
+            match key {
+                CompareGroup::Module(Some(module)) => {
+                    writeln!(writer, "Changes in module '{}':", module).map_io_err(err_desc)?
+                }
+                CompareGroup::Module(None) => {
+                    writeln!(writer, "Changes in exports of an unknown module:")
+                        .map_io_err(err_desc)?
+                }
+                CompareGroup::Subsystem(Some(subsystem_idx)) => {
+                    let maintainers = options.maintainers.as_ref().unwrap();
+                    let subsystem = &maintainers.subsystems()[subsystem_idx];
+                    writeln!(writer, "Changes in subsystem '{}':", subsystem.name())
+                        .map_io_err(err_desc)?;
+                    for maintainer in subsystem.maintainers() {
+                        writeln!(writer, "Maintainer: {}", maintainer).map_io_err(err_desc)?;
+                    }
+                    for list in subsystem.lists() {
+                        writeln!(writer, "List: {}", list).map_io_err(err_desc)?;
+                    }
+                }
+                CompareGroup::Subsystem(None) => {
+                    writeln!(writer, "Changes in exports of an unknown subsystem:")
+                        .map_io_err(err_desc)?
+                }
+            }
+            self.write_compare_report(
+                other_corpus,
+                &group.removed,
+                &group.added,
+                &group.changes,
+                options,
+                writer.by_ref(),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Returns the groups of a specified export in the corpus, according to the grouping requested
+    /// by `options`.
+    fn export_groups(&self, name: &str, options: &CompareOptions) -> Vec<CompareGroup<'_>> {
+        let maintainers = match &options.maintainers {
+            Some(maintainers) => maintainers,
+            None => return vec![CompareGroup::Module(self.export_module(name))],
+        };
+
+        let subsystems = match self.export_source(name) {
+            Some(source) => maintainers.find_subsystems(&source),
+            None => Vec::new(),
+        };
+        if subsystems.is_empty() {
+            return vec![CompareGroup::Subsystem(None)];
+        }
+        subsystems
+            .into_iter()
+            .map(|subsystem_idx| CompareGroup::Subsystem(Some(subsystem_idx)))
+            .collect()
+    }
+
+    /// Returns the source file of a specified export in the corpus, if known. This is the file that
+    /// the symtypes data of the export was produced from.
+    fn export_source(&self, name: &str) -> Option<String> {
+        let &file_idx = self.exports.get(name)?;
+        let path = self.files[file_idx].path.to_string_lossy();
+        match path.strip_suffix(".symtypes") {
+            Some(base) => Some(format!("{}.c", base)),
+            None => Some(path.into_owned()),
+        }
+    }
+
+    /// Returns the name of the module which provides a specified export in the corpus, if known.
+    fn export_module(&self, name: &str) -> Option<&str> {
+        self.exports
+            .get(name)
+            .and_then(|&file_idx| self.files[file_idx].module.as_deref())
+    }
+
+    /// Writes a human-readable report about removed, added and changed exports to the provided
+    /// output stream.
+    fn write_compare_report<W: Write>(
+        &self,
+        other_corpus: &SymCorpus,
+        removed: &[&str],
+        added: &[&str],
+        changes: &[CompareChange],
+        options: &CompareOptions,
+        mut writer: W,
+    ) -> Result<(), crate::Error> {
+        let err_desc = "Failed to write a comparison result";
+
+        // Report the impact of a removal on consumers of the export.
+        for &name in removed {
+            let consumers = self.get_consumers(other_corpus, name);
+            writeln!(
+                writer,
+                "Export '{}' has been removed{}",
+                name,
+                format_consumers(&consumers)
+            )
+            .map_io_err(err_desc)?;
+        }
+        for &name in added {
+            writeln!(writer, "Export '{}' has been added", name).map_io_err(err_desc)?;
+        }
+
+        // Format and output collected changes.
         let mut add_separator = false;
         for ((name, tokens, other_tokens), exports) in changes {
             // Add an empty line to separate individual changes.
@@ -1026,18 +1824,41 @@ impl SymCorpus {
             )
             .map_io_err(err_desc)?;
             for export in exports {
-                writeln!(writer, " {}", export).map_io_err(err_desc)?;
+                let consumers = self.get_consumers(other_corpus, export);
+                writeln!(writer, " {}{}", export, format_consumers(&consumers))
+                    .map_io_err(err_desc)?;
             }
             writeln!(writer).map_io_err(err_desc)?;
 
-            writeln!(writer, "because of a changed '{}':", name).map_io_err(err_desc)?;
-            write_type_diff(tokens, other_tokens, writer.by_ref())?;
+            // Point to the definition of the type in the source tree, if it is known.
+            let banner = format!(
+                "because of a changed '{}'{}:",
+                name,
+                format_locations(&options.type_locations(name))
+            );
+            if options.diff.color {
+                writeln!(writer, "{}{}{}", ANSI_BOLD, banner, ANSI_RESET).map_io_err(err_desc)?;
+            } else {
+                writeln!(writer, "{}", banner).map_io_err(err_desc)?;
+            }
+            write_type_diff_with_options(tokens, other_tokens, &options.diff, writer.by_ref())?;
         }
 
         Ok(())
     }
 }
 
+/// Returns whether a specified file is an ELF object, rather than symtypes data.
+fn is_elf_file(path: &Path) -> Result<bool, crate::Error> {
+    let mut magic = Vec::new();
+    PathFile::open(path)
+        .and_then(|file| file.take(4).read_to_end(&mut magic))
+        .map_err(|err| {
+            crate::Error::new_io(&format!("Failed to read file '{}'", path.display()), err)
+        })?;
+    Ok(ElfObject::is_elf(&magic))
+}
+
 /// Reads data from a specified reader and returns its content as a [`Vec`] of [`String`] lines.
 fn read_lines<R: Read>(reader: R) -> io::Result<Vec<String>> {
     let reader = BufReader::new(reader);
@@ -1051,6 +1872,49 @@ fn read_lines<R: Read>(reader: R) -> io::Result<Vec<String>> {
     Ok(lines)
 }
 
+/// Splits a line of symtypes data into words separated by whitespace.
+///
+/// A type name can be enclosed in single quotes, either at the start of a word or after its `<x>#`
+/// prefix, in which case it can contain whitespace. Such a quoted part is kept within one word,
+/// including the quotes.
+fn split_words<'a>(
+    path: &Path,
+    line_idx: usize,
+    line: &'a str,
+) -> Result<Vec<&'a str>, crate::Error> {
+    let bytes = line.as_bytes();
+    let mut words = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i].is_ascii_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+        while i < bytes.len() && !bytes[i].is_ascii_whitespace() {
+            let is_name_start = i == start || (i == start + 2 && bytes[start + 1] == b'#');
+            if bytes[i] == b'\'' && is_name_start {
+                // Skip over the quoted part.
+                match line[i + 1..].find('\'') {
+                    Some(len) => i += len + 2,
+                    None => {
+                        return Err(crate::Error::new_parse(
+                            path,
+                            Some(line_idx + 1),
+                            "Unterminated quoted name",
+                        ))
+                    }
+                }
+                continue;
+            }
+            i += 1;
+        }
+        words.push(&line[start..i]);
+    }
+    Ok(words)
+}
+
 /// Reads words from a given iterator and converts them to `Tokens`.
 fn words_into_tokens<'a, I: Iterator<Item = &'a str>>(words: &mut I) -> Tokens {
     let mut tokens = Tokens::new();
@@ -1062,7 +1926,7 @@ fn words_into_tokens<'a, I: Iterator<Item = &'a str>>(words: &mut I) -> Tokens {
             }
         }
         tokens.push(if is_typeref {
-            Token::new_typeref(word)
+            Token::new_typeref(unquote_name(word))
         } else {
             Token::new_atom(word)
         });
@@ -1070,6 +1934,39 @@ fn words_into_tokens<'a, I: Iterator<Item = &'a str>>(words: &mut I) -> Tokens {
     tokens
 }
 
+/// Returns the length of the `<x>#` prefix of the specified type name, or zero for an export name.
+fn type_prefix_len(type_name: &str) -> usize {
+    if is_export_name(type_name) {
+        return 0;
+    }
+    type_name
+        .char_indices()
+        .nth(2)
+        .map_or(type_name.len(), |(i, _)| i)
+}
+
+/// Removes single quotes from the specified type name in form `<x>#'<name>'` or `'<name>'`.
+fn unquote_name(type_name: &str) -> Cow<'_, str> {
+    let (prefix, name) = type_name.split_at(type_prefix_len(type_name));
+    match name
+        .strip_prefix('\'')
+        .and_then(|name| name.strip_suffix('\''))
+    {
+        Some(name) => Cow::Owned(format!("{}{}", prefix, name)),
+        None => Cow::Borrowed(type_name),
+    }
+}
+
+/// Encloses the specified type name in single quotes if it contains whitespace, producing
+/// `<x>#'<name>'` or `'<name>'`.
+fn quote_name(type_name: &str) -> Cow<'_, str> {
+    if !type_name.contains(|ch: char| ch.is_ascii_whitespace()) {
+        return Cow::Borrowed(type_name);
+    }
+    let (prefix, name) = type_name.split_at(type_prefix_len(type_name));
+    Cow::Owned(format!("{}'{}'", prefix, name))
+}
+
 /// Returns whether the specified type name is an export definition, as opposed to a `<X>#<foo>`
 /// type definition.
 fn is_export_name(type_name: &str) -> bool {
@@ -1079,40 +1976,91 @@ fn is_export_name(type_name: &str) -> bool {
     }
 }
 
-/// Splits the specified type name into a tuple of two string slices, with the first one being the
-/// base name and the second one containing the variant name/index (or an empty string if no variant
-/// was present).
+/// Splits the specified type name, possibly quoted, into a tuple of two string slices, with the
+/// first one being the base name and the second one containing the variant name/index (or an empty
+/// string if no variant was present).
 fn split_type_name(type_name: &str) -> (&str, &str) {
     match type_name.rfind('@') {
-        Some(i) => (&type_name[..i], &type_name[i + 1..]),
-        None => (type_name, &type_name[type_name.len()..]),
+        // Ignore any `@` character inside a quoted name.
+        Some(i) if !type_name[i..].contains('\'') => (&type_name[..i], &type_name[i + 1..]),
+        _ => (type_name, &type_name[type_name.len()..]),
+    }
+}
+
+/// A line of a pretty-formatted type, which records the byte ranges of its tokens, so that a word
+/// diff can compare them.
+#[derive(Debug, Default)]
+struct PrettyLine {
+    text: String,
+    token_ranges: Vec<Range<usize>>,
+}
+
+impl PrettyLine {
+    /// Appends a specified token to the line.
+    fn push_token(&mut self, token: &str) {
+        let start = self.text.len();
+        self.text.push_str(token);
+        self.token_ranges.push(start..self.text.len());
+    }
+}
+
+impl PartialEq for PrettyLine {
+    fn eq(&self, other: &Self) -> bool {
+        self.text == other.text
+    }
+}
+
+impl AsRef<str> for PrettyLine {
+    fn as_ref(&self) -> &str {
+        &self.text
+    }
+}
+
+impl std::fmt::Display for PrettyLine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+impl DiffLine for PrettyLine {
+    fn word_ranges(&self) -> Vec<Range<usize>> {
+        self.token_ranges.clone()
     }
 }
 
 /// Processes tokens describing a type and produces its pretty-formatted version as a [`Vec`] of
 /// [`String`] lines.
 fn pretty_format_type(tokens: &Tokens) -> Vec<String> {
+    pretty_format_type_lines(tokens)
+        .into_iter()
+        .map(|line| line.text)
+        .collect()
+}
+
+/// Processes tokens describing a type and produces its pretty-formatted version as a [`Vec`] of
+/// [`PrettyLine`] lines.
+fn pretty_format_type_lines(tokens: &Tokens) -> Vec<PrettyLine> {
     // Iterate over all tokens and produce the formatted output.
     let mut res = Vec::new();
     let mut indent: usize = 0;
 
-    let mut line = String::new();
+    let mut line = PrettyLine::default();
     for token in tokens {
         // Handle the closing bracket and parenthesis early, they end any prior line and reduce
         // indentation.
         if token.as_str() == "}" || token.as_str() == ")" {
-            if !line.is_empty() {
+            if !line.text.is_empty() {
                 res.push(line);
             }
             indent = indent.saturating_sub(1);
-            line = String::new();
+            line = PrettyLine::default();
         }
 
         // Insert any newline indentation.
-        let is_first = line.is_empty();
+        let is_first = line.text.is_empty();
         if is_first {
             for _ in 0..indent {
-                line.push('\t');
+                line.text.push('\t');
             }
         }
 
@@ -1120,39 +2068,33 @@ fn pretty_format_type(tokens: &Tokens) -> Vec<String> {
         match token.as_str() {
             "{" | "(" => {
                 if !is_first {
-                    line.push(' ');
+                    line.text.push(' ');
                 }
-                line.push_str(token.as_str());
+                line.push_token(token.as_str());
                 res.push(line);
                 indent = indent.saturating_add(1);
 
-                line = String::new();
+                line = PrettyLine::default();
             }
             "}" | ")" => {
-                line.push_str(token.as_str());
+                line.push_token(token.as_str());
             }
-            ";" => {
-                line.push(';');
+            ";" | "," => {
+                line.push_token(token.as_str());
                 res.push(line);
 
-                line = String::new();
-            }
-            "," => {
-                line.push(',');
-                res.push(line);
-
-                line = String::new();
+                line = PrettyLine::default();
             }
             _ => {
                 if !is_first {
-                    line.push(' ');
+                    line.text.push(' ');
                 }
-                line.push_str(token.as_str());
+                line.push_token(token.as_str());
             }
         };
     }
 
-    if !line.is_empty() {
+    if !line.text.is_empty() {
         res.push(line);
     }
 
@@ -1161,12 +2103,24 @@ fn pretty_format_type(tokens: &Tokens) -> Vec<String> {
 
 /// Formats a unified diff between two supposedly different types and writes it to the provided
 /// output stream.
+#[cfg(test)]
 fn write_type_diff<W: Write>(
     tokens: &Tokens,
     other_tokens: &Tokens,
     writer: W,
 ) -> Result<(), crate::Error> {
-    let pretty = pretty_format_type(tokens);
-    let other_pretty = pretty_format_type(other_tokens);
-    crate::diff::unified(&pretty, &other_pretty, writer)
+    write_type_diff_with_options(tokens, other_tokens, &DiffOptions::default(), writer)
+}
+
+/// Formats a unified diff between two supposedly different types and writes it to the provided
+/// output stream, with the diff controlled by the specified `options`.
+fn write_type_diff_with_options<W: Write>(
+    tokens: &Tokens,
+    other_tokens: &Tokens,
+    options: &DiffOptions,
+    writer: W,
+) -> Result<(), crate::Error> {
+    let pretty = pretty_format_type_lines(tokens);
+    let other_pretty = pretty_format_type_lines(other_tokens);
+    crate::diff::unified_with_options(&pretty, &other_pretty, options, writer)
 }
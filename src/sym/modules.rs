@@ -0,0 +1,112 @@
+// Copyright (C) 2025 SUSE LLC <petr.pavlu@suse.com>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use super::{read_lines, SymCorpus};
+use crate::{debug, PathFile};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+// Notes:
+// [1] Kbuild records all modules of a build in `modules.order`, one per line. Recent kernels list
+//     each module by its object file, such as `drivers/net/foo/bar.o`, older ones by its installed
+//     path, such as `kernel/drivers/net/foo/bar.ko`.
+// [2] For each module, Kbuild creates a `.mod` file next to its object file which lists all object
+//     files linked into the module. Older kernels add a second line with undefined symbols of the
+//     module, which is skipped by considering only words that end with `.o`.
+
+/// A mapping from a path of a `.symtypes` file to the name of the module which it is part of.
+type FileModules = HashMap<PathBuf, String>;
+
+impl SymCorpus {
+    /// Attributes all files in the corpus which have no module assigned yet to kernel modules,
+    /// according to `modules.order` and `.mod` files in a specified build directory.
+    ///
+    /// Files which are not part of any module are attributed to `vmlinux`. If the directory
+    /// contains no `modules.order` file, the function leaves the files unchanged.
+    ///
+    /// In the lenient mode, a module whose `.mod` file cannot be read is skipped and the error is
+    /// returned as a warning.
+    pub(super) fn assign_modules(
+        &mut self,
+        root: &Path,
+        is_lenient: bool,
+    ) -> Result<Vec<crate::Error>, crate::Error> {
+        let order_path = root.join("modules.order");
+        if !order_path.is_file() {
+            return Ok(Vec::new());
+        }
+
+        let (file_modules, warnings) = read_file_modules(root, &order_path, is_lenient)?;
+        for symfile in &mut self.files {
+            if symfile.module.is_none() {
+                let module = match file_modules.get(&symfile.path) {
+                    Some(module) => module.as_str(),
+                    None => "vmlinux",
+                };
+                symfile.module = Some(module.to_string());
+            }
+        }
+        Ok(warnings)
+    }
+}
+
+/// Reads a `modules.order` file and the `.mod` files of all listed modules, and returns the
+/// mapping of `.symtypes` files to the modules, together with any warnings in the lenient mode.
+fn read_file_modules(
+    root: &Path,
+    order_path: &Path,
+    is_lenient: bool,
+) -> Result<(FileModules, Vec<crate::Error>), crate::Error> {
+    debug!("Loading modules from '{}'", order_path.display());
+
+    let lines = read_path_lines(order_path)?;
+
+    let mut file_modules = FileModules::new();
+    let mut warnings = Vec::new();
+    for line in &lines {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        // Determine the base path of the module, without any suffix [1].
+        let base_path = match line.strip_suffix(".ko") {
+            Some(base_path) => base_path.strip_prefix("kernel/").unwrap_or(base_path),
+            None => line.strip_suffix(".o").unwrap_or(line),
+        };
+        let module = match Path::new(base_path).file_name() {
+            Some(module) => module.to_string_lossy().into_owned(),
+            None => continue,
+        };
+
+        // Read the object files of the module [2].
+        let mod_path = root.join(format!("{}.mod", base_path));
+        let mod_lines = match read_path_lines(&mod_path) {
+            Ok(mod_lines) => mod_lines,
+            Err(err) if is_lenient => {
+                warnings.push(err);
+                continue;
+            }
+            Err(err) => return Err(err),
+        };
+        for object in mod_lines.iter().flat_map(|line| line.split_whitespace()) {
+            if let Some(object) = object.strip_suffix(".o") {
+                file_modules.insert(
+                    PathBuf::from(format!("{}.symtypes", object)),
+                    module.clone(),
+                );
+            }
+        }
+    }
+    Ok((file_modules, warnings))
+}
+
+/// Reads all lines of a file at a specified path.
+fn read_path_lines(path: &Path) -> Result<Vec<String>, crate::Error> {
+    let file = PathFile::open(path).map_err(|err| {
+        crate::Error::new_io(&format!("Failed to open file '{}'", path.display()), err)
+    })?;
+    read_lines(file).map_err(|err| {
+        crate::Error::new_io(&format!("Failed to read file '{}'", path.display()), err)
+    })
+}
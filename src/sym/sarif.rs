@@ -0,0 +1,211 @@
+// Copyright (C) 2025 SUSE LLC <petr.pavlu@suse.com>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use super::{
+    format_consumers, write_type_diff_with_options, CompareChange, CompareOptions, SymCorpus,
+};
+use crate::json::Value;
+use crate::MapIOErr;
+use std::io::prelude::*;
+
+// Notes:
+// [1] The report follows the Static Analysis Results Interchange Format (SARIF) 2.1.0. Each
+//     removed or added export and each changed type produces one result. The rule of a result
+//     identifies the class of the change, its level tells whether the change breaks the kABI.
+// [2] A result points to the source file of the symtypes record of the affected export, which is
+//     derived from the path of its symtypes file. For a changed type, the locations of its
+//     definition are used instead if a source index is available.
+
+/// The SARIF version produced by the report.
+const SARIF_VERSION: &str = "2.1.0";
+
+/// The schema of the SARIF version produced by the report.
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// Classes of changes reported as SARIF rules, as tuples of (rule ID, level, description).
+const SARIF_RULES: [(&str, &str, &str); 3] = [
+    ("export-removed", "error", "An export has been removed"),
+    ("export-added", "note", "An export has been added"),
+    (
+        "type-changed",
+        "error",
+        "A type used by exports has changed",
+    ),
+];
+
+impl SymCorpus {
+    /// Writes a SARIF report about removed, added and changed exports to the provided output
+    /// stream [1].
+    pub(super) fn write_sarif_report<W: Write>(
+        &self,
+        other_corpus: &SymCorpus,
+        removed: &[&str],
+        added: &[&str],
+        changes: &[CompareChange],
+        options: &CompareOptions,
+        mut writer: W,
+    ) -> Result<(), crate::Error> {
+        let mut results = Vec::new();
+
+        for &name in removed {
+            let consumers = self.get_consumers(other_corpus, name);
+            let message = format!(
+                "Export '{}' has been removed{}",
+                name,
+                format_consumers(&consumers)
+            );
+            let locations = self.export_sources(&[name]);
+            results.push(sarif_result(
+                "export-removed",
+                &message,
+                &[name],
+                locations,
+                None,
+            ));
+        }
+        for &name in added {
+            let message = format!("Export '{}' has been added", name);
+            let locations = other_corpus.export_sources(&[name]);
+            results.push(sarif_result(
+                "export-added",
+                &message,
+                &[name],
+                locations,
+                None,
+            ));
+        }
+
+        for ((name, tokens, other_tokens), exports) in changes {
+            let message = format!(
+                "Type '{}' has changed, affecting '{}' exports: {}",
+                name,
+                exports.len(),
+                exports.join(", ")
+            );
+
+            let locations = match &options.source_index {
+                Some(source_index) if !source_index.find(name).is_empty() => source_index
+                    .find(name)
+                    .iter()
+                    .map(|(path, line)| (path.to_string_lossy().into_owned(), Some(*line)))
+                    .collect(),
+                _ => self.export_sources(exports),
+            };
+
+            let mut diff = Vec::new();
+            write_type_diff_with_options(tokens, other_tokens, &options.plain_diff(), &mut diff)?;
+            let diff = String::from_utf8_lossy(&diff);
+
+            results.push(sarif_result(
+                "type-changed",
+                &message,
+                exports,
+                locations,
+                Some(&diff),
+            ));
+        }
+
+        let rules = SARIF_RULES
+            .iter()
+            .map(|(id, level, description)| {
+                Value::new_object([
+                    ("id", Value::new_string(*id)),
+                    (
+                        "shortDescription",
+                        Value::new_object([("text", Value::new_string(*description))]),
+                    ),
+                    (
+                        "defaultConfiguration",
+                        Value::new_object([("level", Value::new_string(*level))]),
+                    ),
+                ])
+            })
+            .collect();
+        let driver = Value::new_object([
+            ("name", Value::new_string("ksymtypes")),
+            ("version", Value::new_string(env!("CARGO_PKG_VERSION"))),
+            ("rules", Value::Array(rules)),
+        ]);
+        let run = Value::new_object([
+            ("tool", Value::new_object([("driver", driver)])),
+            ("results", Value::Array(results)),
+        ]);
+        let log = Value::new_object([
+            ("$schema", Value::new_string(SARIF_SCHEMA)),
+            ("version", Value::new_string(SARIF_VERSION)),
+            ("runs", Value::Array(vec![run])),
+        ]);
+
+        log.write_pretty(writer.by_ref())
+            .map_io_err("Failed to write a comparison result")
+    }
+
+    /// Returns the source files of specified exports in the corpus, without duplicates [2].
+    fn export_sources(&self, names: &[&str]) -> Vec<(String, Option<usize>)> {
+        let mut sources = Vec::new();
+        for name in names {
+            if let Some(source) = self.export_source(name) {
+                if !sources.iter().any(|(other, _)| *other == source) {
+                    sources.push((source, None));
+                }
+            }
+        }
+        sources
+    }
+}
+
+/// Creates a single SARIF result for a specified rule.
+fn sarif_result(
+    rule_id: &str,
+    message: &str,
+    exports: &[&str],
+    locations: Vec<(String, Option<usize>)>,
+    diff: Option<&str>,
+) -> Value {
+    let level = SARIF_RULES
+        .iter()
+        .find(|(id, _, _)| *id == rule_id)
+        .map(|(_, level, _)| *level)
+        .unwrap();
+
+    let locations = locations
+        .into_iter()
+        .map(|(uri, maybe_line)| {
+            let mut physical_location = vec![(
+                "artifactLocation",
+                Value::new_object([("uri", Value::new_string(uri))]),
+            )];
+            if let Some(line) = maybe_line {
+                physical_location.push((
+                    "region",
+                    Value::new_object([("startLine", Value::Int(line as i64))]),
+                ));
+            }
+            Value::new_object([("physicalLocation", Value::new_object(physical_location))])
+        })
+        .collect();
+
+    let mut properties = vec![(
+        "exports",
+        Value::Array(
+            exports
+                .iter()
+                .map(|name| Value::new_string(*name))
+                .collect(),
+        ),
+    )];
+    if let Some(diff) = diff {
+        properties.push(("diff", Value::new_string(diff)));
+    }
+
+    Value::new_object([
+        ("ruleId", Value::new_string(rule_id)),
+        ("level", Value::new_string(level)),
+        (
+            "message",
+            Value::new_object([("text", Value::new_string(message))]),
+        ),
+        ("locations", Value::Array(locations)),
+        ("properties", Value::new_object(properties)),
+    ])
+}
@@ -0,0 +1,288 @@
+// Copyright (C) 2025 SUSE LLC <petr.pavlu@suse.com>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use super::{is_export_name, SymCorpus, SymFile, Token};
+use crate::json::Value;
+use crate::MapIOErr;
+use std::collections::HashSet;
+use std::io::{prelude::*, BufWriter};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+/// The number of entries shown in each top list of the statistics.
+const STATS_TOP_SIZE: usize = 10;
+
+/// Type kinds recognized in the statistics, identified by their `<x>#` prefix.
+const STATS_TYPE_KINDS: [char; 5] = ['s', 'u', 'e', 't', 'E'];
+
+/// An output format of the corpus statistics.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum StatsFormat {
+    Text,
+    Json,
+}
+
+/// Statistics about a corpus.
+struct Stats<'a> {
+    num_files: usize,
+    num_exports: usize,
+    /// The number of distinct types for each kind in `STATS_TYPE_KINDS`.
+    num_types: Vec<(char, usize)>,
+    num_variants: usize,
+    /// Types with the most variants, as tuples of (name, number of variants).
+    most_variants: Vec<(&'a str, usize)>,
+    /// Largest type definitions, as tuples of (name, variant number, number of tokens).
+    largest_types: Vec<(&'a str, usize, usize)>,
+    /// Exports with the biggest type closures, as tuples of (name, number of types).
+    largest_closures: Vec<(&'a str, usize)>,
+    /// The total number of type records in all files.
+    num_file_records: usize,
+    /// The number of type records in the consolidated output.
+    num_consolidated_records: usize,
+}
+
+impl Stats<'_> {
+    /// Returns the percentage of type records which are saved by the consolidation.
+    fn consolidation_savings(&self) -> f64 {
+        if self.num_file_records == 0 {
+            return 0.0;
+        }
+        100.0 * (1.0 - self.num_consolidated_records as f64 / self.num_file_records as f64)
+    }
+}
+
+impl SymCorpus {
+    /// Calculates the number of distinct types which a specified export references, directly or
+    /// indirectly, in its file.
+    fn get_closure_size(&self, file: &SymFile, export: &str) -> Result<usize, crate::Error> {
+        let mut processed = HashSet::new();
+        let mut pending = vec![export];
+        while let Some(name) = pending.pop() {
+            for token in Self::get_type_tokens(self, file, name, export)? {
+                if let Token::TypeRef(ref_name) = token {
+                    if processed.insert(ref_name.as_str()) {
+                        pending.push(ref_name.as_str());
+                    }
+                }
+            }
+        }
+        Ok(processed.len())
+    }
+
+    /// Collects statistics about the corpus.
+    fn get_stats(&self, num_workers: i32) -> Result<Stats<'_>, crate::Error> {
+        // Count types and their variants.
+        let mut num_types = STATS_TYPE_KINDS.map(|kind| (kind, 0)).to_vec();
+        let mut num_variants = 0;
+        let mut most_variants = Vec::new();
+        let mut largest_types = Vec::new();
+        let variant_numbers = self.get_variant_numbers();
+        for (name, variants) in &self.types {
+            if is_export_name(name) {
+                continue;
+            }
+            for (variant_idx, tokens) in variants.iter().enumerate() {
+                let variant_number = variant_numbers[name.as_str()][variant_idx];
+                largest_types.push((name.as_str(), variant_number, tokens.len()));
+            }
+            let kind = name.chars().next().unwrap();
+            if let Some(entry) = num_types.iter_mut().find(|(k, _)| *k == kind) {
+                entry.1 += 1;
+            }
+            num_variants += variants.len();
+            most_variants.push((name.as_str(), variants.len()));
+        }
+
+        most_variants.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        most_variants.truncate(STATS_TOP_SIZE);
+        largest_types.sort_by(|a, b| b.2.cmp(&a.2).then((a.0, a.1).cmp(&(b.0, b.1))));
+        largest_types.truncate(STATS_TOP_SIZE);
+
+        // Calculate the type closure of each export.
+        let works: Vec<_> = self.exports.iter().collect();
+        let next_work_idx = AtomicUsize::new(0);
+        let largest_closures = Mutex::new(Vec::new());
+
+        thread::scope(|s| -> Result<(), crate::Error> {
+            let mut workers = Vec::new();
+            for _ in 0..num_workers {
+                workers.push(s.spawn(|| -> Result<(), crate::Error> {
+                    loop {
+                        let work_idx = next_work_idx.fetch_add(1, Ordering::Relaxed);
+                        if work_idx >= works.len() {
+                            return Ok(());
+                        }
+                        let (name, file_idx) = works[work_idx];
+
+                        let size = self.get_closure_size(&self.files[*file_idx], name)?;
+                        largest_closures.lock().unwrap().push((name.as_str(), size));
+                    }
+                }));
+            }
+
+            // Join all worker threads. Return the first error if any is found, others are silently
+            // swallowed which is ok.
+            for worker in workers {
+                worker.join().unwrap()?
+            }
+
+            Ok(())
+        })?;
+
+        let mut largest_closures = largest_closures.into_inner().unwrap();
+        largest_closures.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        largest_closures.truncate(STATS_TOP_SIZE);
+
+        // Determine the consolidation savings. Each used type variant gets written exactly once in
+        // the consolidated output.
+        let num_file_records = self.files.iter().map(|file| file.records.len()).sum();
+        let num_consolidated_records = self.types.values().map(|variants| variants.len()).sum();
+
+        Ok(Stats {
+            num_files: self.files.len(),
+            num_exports: self.exports.len(),
+            num_types,
+            num_variants,
+            most_variants,
+            largest_types,
+            largest_closures,
+            num_file_records,
+            num_consolidated_records,
+        })
+    }
+
+    /// Writes statistics about the corpus in a specified format to the provided output stream.
+    pub fn write_stats_buffer<W: Write>(
+        &self,
+        format: StatsFormat,
+        writer: W,
+        num_workers: i32,
+    ) -> Result<(), crate::Error> {
+        let mut writer = BufWriter::new(writer);
+        let stats = self.get_stats(num_workers)?;
+
+        match format {
+            StatsFormat::Text => write_stats_text(&stats, &mut writer),
+            StatsFormat::Json => stats_to_json(&stats)
+                .write_pretty(&mut writer)
+                .map_io_err("Failed to write statistics"),
+        }
+    }
+}
+
+/// Writes the statistics in a human-readable form.
+fn write_stats_text<W: Write>(stats: &Stats, writer: &mut W) -> Result<(), crate::Error> {
+    let err_desc = "Failed to write statistics";
+
+    writeln!(writer, "Files: {}", stats.num_files).map_io_err(err_desc)?;
+    writeln!(writer, "Exports: {}", stats.num_exports).map_io_err(err_desc)?;
+    writeln!(writer, "Types:").map_io_err(err_desc)?;
+    for (kind, count) in &stats.num_types {
+        writeln!(writer, " {}#: {}", kind, count).map_io_err(err_desc)?;
+    }
+    writeln!(writer, "Type variants: {}", stats.num_variants).map_io_err(err_desc)?;
+
+    writeln!(writer, "Types with the most variants:").map_io_err(err_desc)?;
+    for (name, count) in &stats.most_variants {
+        writeln!(writer, " {}: {}", name, count).map_io_err(err_desc)?;
+    }
+
+    writeln!(writer, "Largest type definitions:").map_io_err(err_desc)?;
+    for (name, variant_idx, count) in &stats.largest_types {
+        writeln!(writer, " {}@{}: {} tokens", name, variant_idx, count).map_io_err(err_desc)?;
+    }
+
+    writeln!(writer, "Exports with the biggest type closures:").map_io_err(err_desc)?;
+    for (name, count) in &stats.largest_closures {
+        writeln!(writer, " {}: {} types", name, count).map_io_err(err_desc)?;
+    }
+
+    writeln!(
+        writer,
+        "Consolidation: {} records in files, {} consolidated records, {:.1}% saved",
+        stats.num_file_records,
+        stats.num_consolidated_records,
+        stats.consolidation_savings()
+    )
+    .map_io_err(err_desc)?;
+
+    Ok(())
+}
+
+/// Converts the statistics to a JSON value.
+fn stats_to_json(stats: &Stats) -> Value {
+    let to_int = |value: usize| Value::Int(value as i64);
+
+    Value::new_object([
+        ("files", to_int(stats.num_files)),
+        ("exports", to_int(stats.num_exports)),
+        (
+            "types",
+            Value::new_object(
+                stats
+                    .num_types
+                    .iter()
+                    .map(|(kind, count)| (kind.to_string(), to_int(*count))),
+            ),
+        ),
+        ("variants", to_int(stats.num_variants)),
+        (
+            "most_variants",
+            Value::Array(
+                stats
+                    .most_variants
+                    .iter()
+                    .map(|(name, count)| {
+                        Value::new_object([
+                            ("name", Value::new_string(*name)),
+                            ("variants", to_int(*count)),
+                        ])
+                    })
+                    .collect(),
+            ),
+        ),
+        (
+            "largest_types",
+            Value::Array(
+                stats
+                    .largest_types
+                    .iter()
+                    .map(|(name, variant_idx, count)| {
+                        Value::new_object([
+                            ("name", Value::new_string(*name)),
+                            ("variant", to_int(*variant_idx)),
+                            ("tokens", to_int(*count)),
+                        ])
+                    })
+                    .collect(),
+            ),
+        ),
+        (
+            "largest_closures",
+            Value::Array(
+                stats
+                    .largest_closures
+                    .iter()
+                    .map(|(name, count)| {
+                        Value::new_object([
+                            ("export", Value::new_string(*name)),
+                            ("types", to_int(*count)),
+                        ])
+                    })
+                    .collect(),
+            ),
+        ),
+        (
+            "consolidation",
+            Value::new_object([
+                ("file_records", to_int(stats.num_file_records)),
+                (
+                    "consolidated_records",
+                    to_int(stats.num_consolidated_records),
+                ),
+                ("saved_percent", Value::Float(stats.consolidation_savings())),
+            ]),
+        ),
+    ])
+}
@@ -7,11 +7,10 @@ use crate::assert_ok;
 macro_rules! assert_parse_err {
     ($result:expr, $exp_desc:expr) => {
         match $result {
-            Err(crate::Error::Parse(actual_desc)) => assert_eq!(actual_desc, $exp_desc),
-            result => panic!(
-                "assertion failed: {:?} is not of type Err(crate::Error::Parse())",
-                result
-            ),
+            result @ (Err(crate::Error::IO { .. }) | Ok(_)) => {
+                panic!("assertion failed: {:?} is not a parse error", result)
+            }
+            Err(err) => assert_eq!(err.to_string(), $exp_desc),
         }
     };
 }
@@ -114,6 +113,54 @@ fn read_invalid_file_record_ref3() {
     assert_parse_err!(result, "test.symtypes:3: Type 'bar@1' is not known");
 }
 
+#[test]
+fn read_forward_type_ref() {
+    // Check that a type reference to a type defined later in the file is accepted.
+    let mut syms = SymCorpus::new();
+    let result = syms.load_buffer(
+        "test.symtypes",
+        concat!(
+            "bar int bar ( s#foo )\n",
+            "s#foo struct foo { s#foo * next ; }\n", //
+        )
+        .as_bytes(),
+    );
+    assert_ok!(result);
+}
+
+#[test]
+fn read_invalid_type_ref() {
+    // Check that a type reference to a type which is not defined in the file is rejected.
+    let mut syms = SymCorpus::new();
+    let result = syms.load_buffer(
+        "test.symtypes",
+        concat!(
+            "s#foo struct foo { int a ; }\n",
+            "bar int bar ( s#foo , s#baz )\n", //
+        )
+        .as_bytes(),
+    );
+    assert_parse_err!(result, "test.symtypes:2: Type 's#baz' is not known");
+}
+
+#[test]
+fn read_invalid_consolidated_type_ref() {
+    // Check that a type reference to a type which is not defined in a consolidated file is
+    // rejected.
+    let mut syms = SymCorpus::new();
+    let result = syms.load_buffer(
+        "test.symtypes",
+        concat!(
+            "s#foo@0 struct foo { int a ; }\n",
+            "s#foo@1 struct foo { s#baz b ; }\n",
+            "bar int bar ( s#foo )\n",
+            "F#test.symtypes s#foo@0 bar\n", //
+        )
+        .as_bytes(),
+    );
+    assert_parse_err!(result, "test.symtypes:2: Type 's#baz' is not known");
+}
+
 #[test]
 fn read_duplicate_type_export() {
     // Check that two exports with the same name in different files get rejected.
@@ -136,6 +183,100 @@ fn read_duplicate_type_export() {
     assert_parse_err!(result, "test2.symtypes:1: Export 'foo' is duplicate. Previous occurrence found in 'test.symtypes'.");
 }
 
+#[test]
+fn read_duplicate_type_export_details() {
+    // Check that the error about a duplicate export provides its details.
+    let mut syms = SymCorpus::new();
+    let result = syms.load_buffer(
+        "test.symtypes",
+        concat!(
+            "foo int foo ( )\n", //
+        )
+        .as_bytes(),
+    );
+    assert_ok!(result);
+    let result = syms.load_buffer(
+        "test2.symtypes",
+        concat!(
+            "bar int bar ( )\n",
+            "foo int foo ( )\n", //
+        )
+        .as_bytes(),
+    );
+    let err = result.unwrap_err();
+    assert_eq!(err.path(), Some(Path::new("test2.symtypes")));
+    assert_eq!(err.line(), Some(2));
+    match err {
+        crate::Error::DuplicateExport {
+            name,
+            previous_path,
+            ..
+        } => {
+            assert_eq!(name, "foo");
+            assert_eq!(previous_path, Path::new("test.symtypes"));
+        }
+        err => panic!("unexpected error {:?}", err),
+    }
+}
+
+#[test]
+fn read_duplicate_type_export_no_partial_data() {
+    // Check that a file rejected because of a duplicate export doesn't leave any of its data in the
+    // corpus.
+    let mut syms = SymCorpus::new();
+    let result = syms.load_buffer(
+        "test.symtypes",
+        concat!(
+            "s#foo struct foo { int a ; }\n",
+            "bar int bar ( s#foo )\n", //
+        )
+        .as_bytes(),
+    );
+    assert_ok!(result);
+    let result = syms.load_buffer(
+        "test2.symtypes",
+        concat!(
+            "s#foo struct foo { long a ; }\n",
+            "baz int baz ( s#foo )\n",
+            "bar int bar ( s#foo )\n", //
+        )
+        .as_bytes(),
+    );
+    assert_parse_err!(result, "test2.symtypes:3: Export 'bar' is duplicate. Previous occurrence found in 'test.symtypes'.");
+    let mut out = Vec::new();
+    let result = syms.write_consolidated_buffer(&mut out);
+    assert_ok!(result);
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        concat!(
+            "s#foo struct foo { int a ; }\n",
+            "bar int bar ( s#foo )\n",
+            "F#test.symtypes bar\n", //
+        )
+    );
+}
+
+#[test]
+fn read_invalid_type_ref_details() {
+    // Check that the error about an unknown type provides its details.
+    let mut syms = SymCorpus::new();
+    let result = syms.load_buffer(
+        "test.symtypes",
+        concat!(
+            "bar int bar ( s#foo )\n", //
+        )
+        .as_bytes(),
+    );
+    match result {
+        Err(crate::Error::UnknownType { name, path, line }) => {
+            assert_eq!(name, "s#foo");
+            assert_eq!(path, Path::new("test.symtypes"));
+            assert_eq!(line, 1);
+        }
+        result => panic!("unexpected result {:?}", result),
+    }
+}
+
 #[test]
 fn read_write_basic() {
     // Check reading of a single file and writing the consolidated output.
@@ -379,6 +520,105 @@ fn compare_changed_type() {
     );
 }
 
+#[test]
+fn compare_changed_type_color() {
+    // Check that the comparison can highlight the differences with colors.
+    let mut syms = SymCorpus::new();
+    let result = syms.load_buffer(
+        "a/test.symtypes",
+        concat!(
+            "s#foo struct foo { int a ; }\n",
+            "bar int bar ( s#foo )\n", //
+        )
+        .as_bytes(),
+    );
+    assert_ok!(result);
+    let mut syms2 = SymCorpus::new();
+    let result = syms2.load_buffer(
+        "b/test.symtypes",
+        concat!(
+            "s#foo struct foo { long a ; }\n",
+            "bar int bar ( s#foo )\n", //
+        )
+        .as_bytes(),
+    );
+    assert_ok!(result);
+    let options = CompareOptions {
+        diff: DiffOptions {
+            color: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mut out = Vec::new();
+    let result = syms.compare_with_options(&syms2, &options, &mut out, 1);
+    assert_ok!(result);
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        concat!(
+            "The following '1' exports are different:\n",
+            " bar\n",
+            "\n",
+            "\x1b[1mbecause of a changed 's#foo':\x1b[m\n",
+            "\x1b[36m@@ -1,3 +1,3 @@\x1b[m\n",
+            " struct foo {\n",
+            "\x1b[31m-\tint a;\x1b[m\n",
+            "\x1b[32m+\tlong a;\x1b[m\n",
+            " }\n", //
+        )
+    );
+}
+
+#[test]
+fn compare_changed_type_word_diff() {
+    // Check that the word diff of a changed type marks exactly the changed tokens.
+    let mut syms = SymCorpus::new();
+    let result = syms.load_buffer(
+        "a/test.symtypes",
+        concat!(
+            "s#foo struct foo { int * p ; int a , b ; }\n",
+            "bar int bar ( s#foo )\n", //
+        )
+        .as_bytes(),
+    );
+    assert_ok!(result);
+    let mut syms2 = SymCorpus::new();
+    let result = syms2.load_buffer(
+        "b/test.symtypes",
+        concat!(
+            "s#foo struct foo { int * q ; int a , c ; }\n",
+            "bar int bar ( s#foo )\n", //
+        )
+        .as_bytes(),
+    );
+    assert_ok!(result);
+    let options = CompareOptions {
+        diff: DiffOptions {
+            word_diff: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mut out = Vec::new();
+    let result = syms.compare_with_options(&syms2, &options, &mut out, 1);
+    assert_ok!(result);
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        concat!(
+            "The following '1' exports are different:\n",
+            " bar\n",
+            "\n",
+            "because of a changed 's#foo':\n",
+            "@@ -1,5 +1,5 @@\n",
+            "struct foo {\n",
+            "\tint * [-p-]{+q+};\n",
+            "\tint a,\n",
+            "\t[-b-]{+c+};\n",
+            "}\n", //
+        )
+    );
+}
+
 #[test]
 fn compare_changed_nested_type() {
     // Check that the comparison of two corpuses reports also changes in subtypes even if the parent
@@ -434,3 +674,145 @@ fn compare_changed_nested_type() {
         )
     );
 }
+
+#[test]
+fn compare_group_by_subsystem() {
+    // Check that the comparison can group affected exports by subsystems in MAINTAINERS.
+    let mut syms = SymCorpus::new();
+    let result = syms.load_buffer(
+        "test.symtypes",
+        concat!(
+            "s#foo struct foo { int a ; }\n",
+            "bar int bar ( s#foo * p )\n",
+            "baz int baz ( s#foo * p )\n",
+            "qux int qux ( )\n",
+            "F#drivers/foo/bar.symtypes bar\n",
+            "F#kernel/baz.symtypes baz qux\n", //
+        )
+        .as_bytes(),
+    );
+    assert_ok!(result);
+    let mut syms2 = SymCorpus::new();
+    let result = syms2.load_buffer(
+        "test.symtypes",
+        concat!(
+            "s#foo struct foo { long a ; }\n",
+            "bar int bar ( s#foo * p )\n",
+            "baz int baz ( s#foo * p )\n",
+            "F#drivers/foo/bar.symtypes bar\n",
+            "F#kernel/baz.symtypes baz\n", //
+        )
+        .as_bytes(),
+    );
+    assert_ok!(result);
+    let mut maintainers = Maintainers::new();
+    let result = maintainers.load_buffer(
+        "MAINTAINERS",
+        concat!(
+            "FOO DRIVER\n",
+            "M:\tJane Doe <jane@example.com>\n",
+            "L:\tfoo@vger.kernel.org\n",
+            "F:\tdrivers/foo/\n",
+            "\n",
+            "BAZ CORE\n",
+            "M:\tJohn Doe <john@example.com>\n",
+            "F:\tkernel/baz.c\n",
+            "N:\tqux\n", //
+        )
+        .as_bytes(),
+    );
+    assert!(result.is_ok_and(|warnings| warnings.is_empty()));
+    let options = CompareOptions {
+        maintainers: Some(maintainers),
+        ..Default::default()
+    };
+    let mut out = Vec::new();
+    let result = syms.compare_with_options(&syms2, &options, &mut out, 1);
+    assert_ok!(result);
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        concat!(
+            "Changes in subsystem 'FOO DRIVER':\n",
+            "Maintainer: Jane Doe <jane@example.com>\n",
+            "List: foo@vger.kernel.org\n",
+            "The following '1' exports are different:\n",
+            " bar\n",
+            "\n",
+            "because of a changed 's#foo':\n",
+            "@@ -1,3 +1,3 @@\n",
+            " struct foo {\n",
+            "-\tint a;\n",
+            "+\tlong a;\n",
+            " }\n",
+            "\n",
+            "Changes in subsystem 'BAZ CORE':\n",
+            "Maintainer: John Doe <john@example.com>\n",
+            "Export 'qux' has been removed\n",
+            "The following '1' exports are different:\n",
+            " baz\n",
+            "\n",
+            "because of a changed 's#foo':\n",
+            "@@ -1,3 +1,3 @@\n",
+            " struct foo {\n",
+            "-\tint a;\n",
+            "+\tlong a;\n",
+            " }\n", //
+        )
+    );
+}
+
+#[test]
+fn write_missing_file_record() {
+    // Check that writing the consolidated output of a corpus with a file missing a type record
+    // reports an error instead of crashing.
+    let mut syms = SymCorpus::new();
+    let result = syms.load_buffer(
+        "test.symtypes",
+        concat!(
+            "s#foo struct foo { int a ; }\n",
+            "bar int bar ( s#foo )\n", //
+        )
+        .as_bytes(),
+    );
+    assert_ok!(result);
+    syms.files[0].records.remove("s#foo");
+    let mut out = Vec::new();
+    let result = syms.write_consolidated_buffer(&mut out);
+    assert_parse_err!(
+        result,
+        "Type 's#foo' is not known in file 'test.symtypes', needed by export 'bar'"
+    );
+}
+
+#[test]
+fn compare_missing_type_variant() {
+    // Check that the comparison of a corpus with a file referencing a non-existent type variant
+    // reports an error instead of crashing.
+    let mut syms = SymCorpus::new();
+    let result = syms.load_buffer(
+        "a/test.symtypes",
+        concat!(
+            "s#foo struct foo { int a ; }\n",
+            "bar int bar ( s#foo )\n", //
+        )
+        .as_bytes(),
+    );
+    assert_ok!(result);
+    let mut syms2 = SymCorpus::new();
+    let result = syms2.load_buffer(
+        "b/test.symtypes",
+        concat!(
+            "s#foo struct foo { int a ; }\n",
+            "bar int bar ( s#foo )\n", //
+        )
+        .as_bytes(),
+    );
+    assert_ok!(result);
+    syms2.files[0].records.insert("s#foo".to_string(), 1);
+    let mut out = Vec::new();
+    let result = syms.compare_with(&syms2, &mut out, 1);
+    assert_parse_err!(
+        result,
+        "Type 's#foo@1' has a missing declaration in file 'b/test.symtypes', needed by export 'bar'"
+    );
+}
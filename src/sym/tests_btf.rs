@@ -0,0 +1,239 @@
+// Copyright (C) 2025 SUSE LLC <petr.pavlu@suse.com>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use super::*;
+use crate::assert_ok;
+use crate::btf::builder::BtfBuilder;
+use crate::btf::Btf;
+use crate::elf::builder::ElfBuilder;
+
+/// Loads a test ELF object with the specified BTF data and exports into a corpus.
+fn load_elf(
+    syms: &mut SymCorpus,
+    path: &str,
+    btf_data: &[u8],
+    base: Option<&Btf>,
+    exports: &[u8],
+) -> Result<(), crate::Error> {
+    let data = ElfBuilder::new()
+        .section(".BTF", btf_data)
+        .section("__ksymtab_strings", exports)
+        .build();
+    let elf = ElfObject::parse(path, data)?;
+    let btf = Btf::parse(path, btf_data, base)?;
+    SymCorpus::load_btf(&elf, &btf, &LoadContext::new(syms))
+}
+
+/// Returns the corpus in the consolidated form.
+fn consolidated(syms: &SymCorpus) -> String {
+    let mut out = Vec::new();
+    assert_ok!(syms.write_consolidated_buffer(&mut out));
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn read_btf() {
+    // Check that types of exported functions and variables are converted from BTF.
+    let mut builder = BtfBuilder::new();
+    let int_id = builder.int("int", 4);
+    let uint_id = builder.int("unsigned int", 4);
+    let fwd_id = builder.fwd("foo", false);
+    let ptr_id = builder.pointer(fwd_id);
+    let color_id = builder.enumeration("color", &[("RED", 0), ("GREEN", 1), ("BLUE", 4)]);
+    let struct_id = builder.structure(
+        "foo",
+        &[
+            ("a", int_id, 0),
+            ("next", ptr_id, 0),
+            ("flags", uint_id, 3),
+            ("color", color_id, 0),
+        ],
+    );
+    let typedef_id = builder.typedef("foo_t", struct_id);
+    let typedef_ptr_id = builder.pointer(typedef_id);
+    let proto_id = builder.func_proto(int_id, &[("p", typedef_ptr_id), ("", 0)]);
+    builder.func("bar", proto_id, true);
+    let const_id = builder.constant(int_id);
+    let array_id = builder.array(const_id, 4);
+    builder.var("baz", array_id, true);
+    builder.func("qux", proto_id, true);
+    let btf_data = builder.build();
+
+    let mut syms = SymCorpus::new();
+    let result = load_elf(
+        &mut syms,
+        "vmlinux",
+        &btf_data,
+        None,
+        b"bar\0baz\0missing\0",
+    );
+    assert_ok!(result);
+    assert_eq!(
+        consolidated(&syms),
+        concat!(
+            "e#color enum color { RED , GREEN , BLUE = 4 }\n",
+            "s#foo struct foo { int a ; s#foo * next ; unsigned int flags : 3 ; e#color color ; }\n",
+            "t#foo_t typedef s#foo foo_t\n",
+            "bar int bar ( t#foo_t * p , ... )\n",
+            "baz const int baz [ 4 ]\n",
+            "F#vmlinux bar baz\n",
+            "M#vmlinux vmlinux\n", //
+        )
+    );
+}
+
+#[test]
+fn read_btf_anonymous() {
+    // Check that anonymous types are expanded in place and that forward declarations without
+    // a complete type are opaque.
+    let mut builder = BtfBuilder::new();
+    let int_id = builder.int("int", 4);
+    let char_id = builder.int("char", 1);
+    let union_id = builder.union("", &[("i", int_id, 0), ("c", char_id, 0)]);
+    let enum_id = builder.enumeration("", &[("A", -1), ("B", 0)]);
+    let fwd_id = builder.fwd("bar", true);
+    let fwd_ptr_id = builder.pointer(fwd_id);
+    let user_id = builder.type_tag("user", char_id);
+    let user_ptr_id = builder.pointer(user_id);
+    let struct_id = builder.structure(
+        "foo",
+        &[
+            ("", union_id, 0),
+            ("e", enum_id, 0),
+            ("b", fwd_ptr_id, 0),
+            ("buf", user_ptr_id, 0),
+        ],
+    );
+    let proto_id = builder.func_proto(0, &[("f", struct_id)]);
+    let func_ptr_id = builder.pointer(proto_id);
+    builder.var("callback", func_ptr_id, true);
+    let btf_data = builder.build();
+
+    let mut syms = SymCorpus::new();
+    let result = load_elf(&mut syms, "vmlinux", &btf_data, None, b"callback\0");
+    assert_ok!(result);
+    assert_eq!(
+        consolidated(&syms),
+        concat!(
+            "s#foo struct foo { union { int i ; char c ; } ; enum { A = -1 , B } e ; u#bar * b ; char * buf ; }\n",
+            "u#bar union bar { UNKNOWN }\n",
+            "callback void ( * callback ) ( s#foo f )\n",
+            "F#vmlinux callback\n",
+            "M#vmlinux vmlinux\n", //
+        )
+    );
+}
+
+#[test]
+fn read_btf_modules() {
+    // Check that BTF of a module is read together with the base BTF of vmlinux.
+    let mut builder = BtfBuilder::new();
+    let int_id = builder.int("int", 4);
+    let struct_id = builder.structure("foo", &[("a", int_id, 0)]);
+    let ptr_id = builder.pointer(struct_id);
+    let proto_id = builder.func_proto(int_id, &[("p", ptr_id)]);
+    builder.func("bar", proto_id, true);
+    let btf_data = builder.build();
+    let base = Btf::parse("vmlinux", &btf_data, None).unwrap();
+
+    let mut builder = BtfBuilder::new_split(&base);
+    let fwd_id = builder.fwd("foo", false);
+    let fwd_ptr_id = builder.pointer(fwd_id);
+    let proto_id = builder.func_proto(0, &[("p", fwd_ptr_id)]);
+    builder.func("baz", proto_id, true);
+    let module_btf_data = builder.build();
+
+    let mut syms = SymCorpus::new();
+    let result = load_elf(&mut syms, "vmlinux", &btf_data, None, b"bar\0");
+    assert_ok!(result);
+    let result = load_elf(&mut syms, "foo.ko", &module_btf_data, Some(&base), b"baz\0");
+    assert_ok!(result);
+    assert_eq!(
+        consolidated(&syms),
+        concat!(
+            "s#foo struct foo { int a ; }\n",
+            "bar int bar ( s#foo * p )\n",
+            "baz void baz ( s#foo * p )\n",
+            "F#foo.ko baz\n",
+            "F#vmlinux bar\n",
+            "M#foo foo.ko\n",
+            "M#vmlinux vmlinux\n", //
+        )
+    );
+}
+
+#[test]
+fn read_btf_duplicate_export() {
+    // Check that an export defined by vmlinux and a module is reported as a duplicate.
+    let mut builder = BtfBuilder::new();
+    let int_id = builder.int("int", 4);
+    builder.var("foo", int_id, true);
+    let btf_data = builder.build();
+    let base = Btf::parse("vmlinux", &btf_data, None).unwrap();
+
+    let mut builder = BtfBuilder::new_split(&base);
+    builder.var("foo", int_id, true);
+    let module_btf_data = builder.build();
+
+    let mut syms = SymCorpus::new();
+    let result = load_elf(&mut syms, "vmlinux", &btf_data, None, b"foo\0");
+    assert_ok!(result);
+    let result = load_elf(&mut syms, "foo.ko", &module_btf_data, Some(&base), b"foo\0");
+    match result {
+        Err(err @ crate::Error::DuplicateExport { .. }) => assert_eq!(
+            err.to_string(),
+            "foo.ko: Export 'foo' is duplicate. Previous occurrence found in 'vmlinux'."
+        ),
+        result => panic!("unexpected result {:?}", result),
+    }
+}
+
+#[test]
+fn compare_btf_base_types() {
+    // Check that names of base types are converted to their canonical spelling, so comparing with
+    // symtypes produced by genksyms doesn't report them as changed.
+    let mut builder = BtfBuilder::new();
+    let long_id = builder.int("long int", 8);
+    let ulong_id = builder.int("long unsigned int", 8);
+    let ushort_id = builder.int("short unsigned int", 2);
+    let proto_id = builder.func_proto(long_id, &[("a", ulong_id), ("b", ushort_id)]);
+    builder.func("foo", proto_id, true);
+    let btf_data = builder.build();
+
+    let mut syms = SymCorpus::new();
+    let result = syms.load_buffer(
+        "test.symtypes",
+        concat!(
+            "foo long foo ( unsigned long a , unsigned short b )\n", //
+        )
+        .as_bytes(),
+    );
+    assert_ok!(result);
+    let mut syms2 = SymCorpus::new();
+    let result = load_elf(&mut syms2, "vmlinux", &btf_data, None, b"foo\0");
+    assert_ok!(result);
+    let mut out = Vec::new();
+    let result = syms.compare_with(&syms2, &mut out, 1);
+    assert_ok!(result);
+    assert_eq!(String::from_utf8(out).unwrap(), "");
+}
+
+#[test]
+fn read_btf_cycle() {
+    // Check that an anonymous type which references itself is rejected.
+    let mut builder = BtfBuilder::new();
+    let ptr_id = builder.pointer(1);
+    let proto_id = builder.func_proto(0, &[("p", ptr_id)]);
+    builder.func("foo", proto_id, true);
+    let btf_data = builder.build();
+
+    let mut syms = SymCorpus::new();
+    let result = load_elf(&mut syms, "vmlinux", &btf_data, None, b"foo\0");
+    match result {
+        Err(err @ crate::Error::Parse { .. }) => assert_eq!(
+            err.to_string(),
+            "vmlinux: Failed to convert the type of export 'foo': Type 1 references itself"
+        ),
+        result => panic!("unexpected result {:?}", result),
+    }
+}
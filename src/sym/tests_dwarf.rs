@@ -0,0 +1,207 @@
+// Copyright (C) 2025 SUSE LLC <petr.pavlu@suse.com>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use super::*;
+use crate::assert_ok;
+
+#[test]
+fn read_gendwarfksyms() {
+    // Check that symtypes data produced by gendwarfksyms is recognized.
+    let mut syms = SymCorpus::new();
+    let result = syms.load_buffer(
+        "test.symtypes",
+        concat!(
+            "s#foo structure_type foo { member base_type int byte_size(4) encoding(5) a data_member_location(0) } byte_size(4)\n",
+            "bar subprogram ( formal_parameter pointer_type { s#foo } byte_size(8) p ) -> base_type void\n", //
+        )
+        .as_bytes(),
+    );
+    assert_ok!(result);
+    assert_eq!(syms.dialect(), Dialect::Gendwarfksyms);
+}
+
+#[test]
+fn read_mixed_dialects() {
+    // Check that data in the genksyms and gendwarfksyms dialects cannot be mixed.
+    let mut syms = SymCorpus::new();
+    let result = syms.load_buffer(
+        "test.symtypes",
+        concat!(
+            "s#foo struct foo { int a ; }\n",
+            "bar int bar ( s#foo * p )\n", //
+        )
+        .as_bytes(),
+    );
+    assert_ok!(result);
+    assert_eq!(syms.dialect(), Dialect::Genksyms);
+    let result = syms.load_buffer(
+        "test2.symtypes",
+        concat!(
+            "baz subprogram ( ) -> base_type void\n", //
+        )
+        .as_bytes(),
+    );
+    match result {
+        Err(err @ crate::Error::Parse { .. }) => assert_eq!(
+            err.to_string(),
+            "test2.symtypes: Data is in the gendwarfksyms dialect but the corpus is in the genksyms dialect"
+        ),
+        result => panic!("unexpected result {:?}", result),
+    }
+}
+
+#[test]
+fn convert_gendwarfksyms() {
+    // Check that types described by gendwarfksyms are converted to C declarations as produced by
+    // genksyms.
+    let mut syms = SymCorpus::new();
+    let result = syms.load_buffer(
+        "test.symtypes",
+        concat!(
+            "t#u32 typedef u32 { base_type unsigned int byte_size(4) encoding(7) }\n",
+            "e#mode enumeration_type mode { enumerator MODE_A = 0 , enumerator MODE_B = 1 , enumerator MODE_C = 4 } byte_size(4)\n",
+            "s#ops structure_type ops { member pointer_type { subroutine_type ( formal_parameter pointer_type { const_type { base_type char byte_size(1) encoding(6) } } byte_size(8) , unspecified_parameters ) -> base_type int byte_size(4) encoding(5) } byte_size(8) log data_member_location(0) } byte_size(8)\n",
+            "s#bar structure_type bar { }\n",
+            "s#foo structure_type foo { member t#u32 flags bit_size(3) data_bit_offset(0) , member array_type [16] { base_type char byte_size(1) encoding(6) } name data_member_location(1) , member pointer_type { s#bar } byte_size(8) next data_member_location(24) , member const_type { pointer_type { s#ops } byte_size(8) } ops data_member_location(32) , member e#mode mode data_member_location(40) } byte_size(48)\n",
+            "baz subprogram ( formal_parameter pointer_type { s#foo } byte_size(8) p , formal_parameter t#u32 len ) -> base_type void\n",
+            "qux subprogram ( ) -> pointer_type { base_type void } byte_size(8)\n",
+            "quux variable array_type [4] { s#foo }\n", //
+        )
+        .as_bytes(),
+    );
+    assert_ok!(result);
+    let syms = syms.into_genksyms();
+    assert_eq!(syms.dialect(), Dialect::Genksyms);
+    let mut out = Vec::new();
+    let result = syms.write_consolidated_buffer(&mut out);
+    assert_ok!(result);
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        concat!(
+            "e#mode enum mode { MODE_A , MODE_B , MODE_C = 4 }\n",
+            "s#bar struct bar { UNKNOWN }\n",
+            "s#foo struct foo { t#u32 flags : 3 ; char name [ 16 ] ; s#bar * next ; s#ops * const ops ; e#mode mode ; }\n",
+            "s#ops struct ops { int ( * log ) ( const char * , ... ) ; }\n",
+            "t#u32 typedef unsigned int u32\n",
+            "baz void baz ( s#foo * p , t#u32 len )\n",
+            "quux s#foo quux [ 4 ]\n",
+            "qux void * qux ( void )\n",
+            "F#test.symtypes baz quux qux\n", //
+        )
+    );
+}
+
+#[test]
+fn compare_with_gendwarfksyms() {
+    // Check that a corpus produced by genksyms can be compared with one produced by gendwarfksyms
+    // after converting the latter.
+    let mut syms = SymCorpus::new();
+    let result = syms.load_buffer(
+        "test.symtypes",
+        concat!(
+            "s#foo struct foo { int a ; }\n",
+            "bar int bar ( s#foo * p )\n", //
+        )
+        .as_bytes(),
+    );
+    assert_ok!(result);
+    let mut syms2 = SymCorpus::new();
+    let result = syms2.load_buffer(
+        "test.symtypes",
+        concat!(
+            "s#foo structure_type foo { member base_type int byte_size(4) encoding(5) a data_member_location(0) , member base_type int byte_size(4) encoding(5) b data_member_location(4) } byte_size(8)\n",
+            "bar subprogram ( formal_parameter pointer_type { s#foo } byte_size(8) p ) -> base_type int byte_size(4) encoding(5)\n", //
+        )
+        .as_bytes(),
+    );
+    assert_ok!(result);
+    let syms2 = syms2.into_genksyms();
+    let mut out = Vec::new();
+    let result = syms.compare_with(&syms2, &mut out, 1);
+    assert_ok!(result);
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        concat!(
+            "The following '1' exports are different:\n",
+            " bar\n",
+            "\n",
+            "because of a changed 's#foo':\n",
+            "@@ -1,3 +1,4 @@\n",
+            " struct foo {\n",
+            " \tint a;\n",
+            "+\tint b;\n",
+            " }\n", //
+        )
+    );
+}
+
+#[test]
+fn read_gendwarfksyms_layout() {
+    // Check that records produced by gendwarfksyms are accepted in any order, with an export
+    // preceding the types which it references, and that a kABI rule result is converted as is.
+    let mut syms = SymCorpus::new();
+    let result = syms.load_buffer(
+        "test.symtypes",
+        concat!(
+            "bar subprogram ( formal_parameter pointer_type { s#foo } byte_size(8) p ) -> base_type void\n",
+            "s#foo structure_type foo { member pointer_type { s#ops } byte_size(8) ops data_member_location(0) , member union_type { member base_type long int byte_size(8) encoding(5) __kabi_reserved0 data_member_location(0) } byte_size(8) data_member_location(8) } byte_size(16)\n",
+            "baz subprogram ( formal_parameter pointer_type { s#ops } byte_size(8) p ) -> base_type void\n",
+            "s#ops structure_type ops { }\n", //
+        )
+        .as_bytes(),
+    );
+    assert_ok!(result);
+    let syms = syms.into_genksyms();
+    let mut out = Vec::new();
+    let result = syms.write_consolidated_buffer(&mut out);
+    assert_ok!(result);
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        concat!(
+            "s#foo struct foo { s#ops * ops ; union { long __kabi_reserved0 ; } ; }\n",
+            "s#ops struct ops { UNKNOWN }\n",
+            "bar void bar ( s#foo * p )\n",
+            "baz void baz ( s#ops * p )\n",
+            "F#test.symtypes bar baz\n", //
+        )
+    );
+}
+
+#[test]
+fn convert_gendwarfksyms_base_types() {
+    // Check that names of base types are converted to their canonical spelling.
+    let mut syms = SymCorpus::new();
+    let result = syms.load_buffer(
+        "test.symtypes",
+        concat!(
+            "s#foo structure_type foo { ",
+            "member base_type long int byte_size(8) encoding(5) a data_member_location(0) , ",
+            "member base_type long unsigned int byte_size(8) encoding(7) b data_member_location(8) , ",
+            "member base_type long long int byte_size(8) encoding(5) c data_member_location(16) , ",
+            "member base_type long long unsigned int byte_size(8) encoding(7) d data_member_location(24) , ",
+            "member base_type short int byte_size(2) encoding(5) e data_member_location(32) , ",
+            "member base_type short unsigned int byte_size(2) encoding(7) f data_member_location(34) , ",
+            "member base_type unsigned int byte_size(4) encoding(7) g data_member_location(36) , ",
+            "member base_type signed char byte_size(1) encoding(6) h data_member_location(40) , ",
+            "member base_type unsigned char byte_size(1) encoding(8) i data_member_location(41) , ",
+            "member base_type _Bool byte_size(1) encoding(2) j data_member_location(42) , ",
+            "member base_type long double byte_size(16) encoding(4) k data_member_location(48) ",
+            "} byte_size(64)\n",
+            "bar subprogram ( formal_parameter pointer_type { s#foo } byte_size(8) p ) -> base_type void\n", //
+        )
+        .as_bytes(),
+    );
+    assert_ok!(result);
+    let syms = syms.into_genksyms();
+    let mut out = Vec::new();
+    let result = syms.write_consolidated_buffer(&mut out);
+    assert_ok!(result);
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        concat!(
+            "s#foo struct foo { long a ; unsigned long b ; long long c ; unsigned long long d ; short e ; unsigned short f ; unsigned int g ; signed char h ; unsigned char i ; _Bool j ; long double k ; }\n",
+            "bar void bar ( s#foo * p )\n",
+            "F#test.symtypes bar\n", //
+        )
+    );
+}
@@ -0,0 +1,85 @@
+// Copyright (C) 2025 SUSE LLC <petr.pavlu@suse.com>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use super::*;
+use crate::{assert_ok, string_vec};
+
+#[test]
+fn check_exports() {
+    // Check that exports present only in the corpus or only in binaries are reported.
+    let mut syms = SymCorpus::new();
+    let result = syms.load_buffer(
+        "test.symtypes",
+        concat!(
+            "foo int foo ( )\n",
+            "bar int bar ( )\n", //
+        )
+        .as_bytes(),
+    );
+    assert_ok!(result);
+    let result = syms.load_buffer(
+        "test2.symtypes",
+        concat!(
+            "baz int baz ( )\n", //
+        )
+        .as_bytes(),
+    );
+    assert_ok!(result);
+    let mut binary_exports = BinaryExports::new();
+    let result = binary_exports.add(Path::new("vmlinux"), string_vec!["foo", "qux"]);
+    assert_ok!(result);
+    let result = binary_exports.add(Path::new("drivers/quux.ko"), string_vec!["quux"]);
+    assert_ok!(result);
+    let mut out = Vec::new();
+    let result = syms.write_export_mismatches_buffer(&binary_exports, &mut out);
+    assert_eq!(result.ok(), Some(4));
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        concat!(
+            "The following '2' exports are present in binaries but missing in the symtypes corpus:\n",
+            " quux (drivers/quux.ko)\n",
+            " qux (vmlinux)\n",
+            "\n",
+            "The following '2' exports are present in the symtypes corpus but missing in binaries:\n",
+            " bar (test.symtypes)\n",
+            " baz (test2.symtypes)\n", //
+        )
+    );
+}
+
+#[test]
+fn check_exports_match() {
+    // Check that nothing is reported if the exports match.
+    let mut syms = SymCorpus::new();
+    let result = syms.load_buffer(
+        "test.symtypes",
+        concat!(
+            "foo int foo ( )\n", //
+        )
+        .as_bytes(),
+    );
+    assert_ok!(result);
+    let mut binary_exports = BinaryExports::new();
+    let result = binary_exports.add(Path::new("vmlinux"), string_vec!["foo"]);
+    assert_ok!(result);
+    let mut out = Vec::new();
+    let result = syms.write_export_mismatches_buffer(&binary_exports, &mut out);
+    assert_eq!(result.ok(), Some(0));
+    assert_eq!(String::from_utf8(out).unwrap(), "");
+}
+
+#[test]
+fn check_exports_duplicate() {
+    // Check that an export provided by multiple binaries is rejected.
+    let mut binary_exports = BinaryExports::new();
+    let result = binary_exports.add(Path::new("vmlinux"), string_vec!["foo"]);
+    assert_ok!(result);
+    let result = binary_exports.add(Path::new("drivers/foo.ko"), string_vec!["foo"]);
+    match result {
+        Err(err @ crate::Error::DuplicateExport { .. }) => assert_eq!(
+            err.to_string(),
+            "drivers/foo.ko: Export 'foo' is duplicate. Previous occurrence found in 'vmlinux'."
+        ),
+        result => panic!("unexpected result {:?}", result),
+    }
+}
@@ -592,3 +592,86 @@ fn format_modification() {
         )
     );
 }
+
+#[test]
+fn read_quoted_names() {
+    // Check that type names enclosed in single quotes can contain spaces, both when defining and
+    // referencing a type, and that the quotes are kept when writing consolidated output.
+    let mut syms = SymCorpus::new();
+    let result = syms.load_buffer(
+        "test.symtypes",
+        concat!(
+            "s#'foo bar' struct 'foo bar' { int a ; }\n",
+            "E#BAZ ( 'a' )\n",
+            "qux int qux ( s#'foo bar' * p , int c [ E#BAZ ] )\n", //
+        )
+        .as_bytes(),
+    );
+    assert_ok!(result);
+    let mut out = Vec::new();
+    let result = syms.write_consolidated_buffer(&mut out);
+    assert_ok!(result);
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        concat!(
+            "E#BAZ ( 'a' )\n",
+            "s#'foo bar' struct 'foo bar' { int a ; }\n",
+            "qux int qux ( s#'foo bar' * p , int c [ E#BAZ ] )\n",
+            "F#test.symtypes qux\n", //
+        )
+    );
+}
+
+#[test]
+fn read_quoted_names_consolidated() {
+    // Check that quoted type names with variant suffixes and quoted file names are recognized when
+    // reading a consolidated file.
+    let mut syms = SymCorpus::new();
+    let result = syms.load_buffer(
+        "test.kabi",
+        concat!(
+            "s#'foo bar'@0 struct 'foo bar' { int a ; }\n",
+            "s#'foo bar'@1 struct 'foo bar' { UNKNOWN }\n",
+            "quux int quux ( s#'foo bar' * p )\n",
+            "'qux 1' int qux ( s#'foo bar' * p )\n",
+            "F#'a dir/test.symtypes' s#'foo bar'@0 'qux 1'\n",
+            "F#test2.symtypes s#'foo bar'@1 quux\n", //
+        )
+        .as_bytes(),
+    );
+    assert_ok!(result);
+    let mut out = Vec::new();
+    let result = syms.write_consolidated_buffer(&mut out);
+    assert_ok!(result);
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        concat!(
+            "s#'foo bar'@0 struct 'foo bar' { int a ; }\n",
+            "s#'foo bar'@1 struct 'foo bar' { UNKNOWN }\n",
+            "quux int quux ( s#'foo bar' * p )\n",
+            "'qux 1' int qux ( s#'foo bar' * p )\n",
+            "F#'a dir/test.symtypes' s#'foo bar'@0 'qux 1'\n",
+            "F#test2.symtypes s#'foo bar'@1 quux\n", //
+        )
+    );
+}
+
+#[test]
+fn read_unterminated_quoted_name() {
+    // Check that a quoted type name without the closing quote is rejected.
+    let mut syms = SymCorpus::new();
+    let result = syms.load_buffer(
+        "test.symtypes",
+        concat!(
+            "s#foo struct foo { int a ; }\n",
+            "bar int bar ( s#'foo )\n", //
+        )
+        .as_bytes(),
+    );
+    match result {
+        Err(err @ crate::Error::Parse { .. }) => {
+            assert_eq!(err.to_string(), "test.symtypes:2: Unterminated quoted name")
+        }
+        result => panic!("unexpected result {:?}", result),
+    }
+}
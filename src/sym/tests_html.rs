@@ -0,0 +1,71 @@
+// Copyright (C) 2025 SUSE LLC <petr.pavlu@suse.com>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use super::*;
+use crate::assert_ok;
+
+#[test]
+fn compare_html() {
+    // Check that the comparison can produce an HTML report.
+    let mut syms = SymCorpus::new();
+    let result = syms.load_buffer(
+        "test.symtypes",
+        concat!(
+            "s#foo struct foo { int a ; }\n",
+            "bar int bar ( s#foo * p )\n",
+            "quux int quux ( )\n", //
+        )
+        .as_bytes(),
+    );
+    assert_ok!(result);
+    let mut syms2 = SymCorpus::new();
+    let result = syms2.load_buffer(
+        "test.symtypes",
+        concat!(
+            "s#foo struct foo { long a ; int b ; }\n",
+            "bar int bar ( s#foo * p )\n",
+            "qux int qux ( )\n", //
+        )
+        .as_bytes(),
+    );
+    assert_ok!(result);
+    let options = CompareOptions {
+        format: CompareFormat::Html,
+        ..Default::default()
+    };
+    let mut out = Vec::new();
+    let result = syms.compare_with_options(&syms2, &options, &mut out, 1);
+    assert_ok!(result);
+    let out = String::from_utf8(out).unwrap();
+    assert!(out.starts_with("<!DOCTYPE html>\n<html lang=\"en\">\n"));
+    assert!(out.ends_with("</body>\n</html>\n"));
+    assert!(!out.contains("http"));
+    assert!(out.contains(concat!(
+        "<h2>Summary</h2>\n",
+        "<table class=\"summary\">\n",
+        "<tr><th>Removed exports</th><td>1</td></tr>\n",
+        "<tr><th>Added exports</th><td>1</td></tr>\n",
+        "<tr><th>Changed exports</th><td>1</td></tr>\n",
+        "<tr><th>Changed types</th><td>1</td></tr>\n",
+        "</table>\n",
+        "<h2>Affected exports</h2>\n",
+        "<input id=\"search\" type=\"search\" placeholder=\"Filter exports\">\n",
+        "<ul class=\"exports\">\n",
+        "<li id=\"export-bar\" class=\"changed\" data-name=\"bar\"><code>bar</code> is different because of a changed <a href=\"#type-s-foo\"><code>s#foo</code></a></li>\n",
+        "<li id=\"export-quux\" class=\"removed\" data-name=\"quux\"><code>quux</code> has been removed</li>\n",
+        "<li id=\"export-qux\" class=\"added\" data-name=\"qux\"><code>qux</code> has been added</li>\n",
+        "</ul>\n",
+        "<h2>Changed types</h2>\n",
+        "<section id=\"type-s-foo\">\n",
+        "<h3><code>s#foo</code></h3>\n",
+        "<p>Affected exports: <a href=\"#export-bar\"><code>bar</code></a></p>\n",
+        "<table class=\"diff\">\n",
+        "<tr><th>Old</th><th>New</th></tr>\n",
+        "<tr><td>struct foo {</td><td>struct foo {</td></tr>\n",
+        "<tr><td class=\"removed\">\tint a;</td><td class=\"added\">\tlong a;</td></tr>\n",
+        "<tr><td class=\"empty\"></td><td class=\"added\">\tint b;</td></tr>\n",
+        "<tr><td>}</td><td>}</td></tr>\n",
+        "</table>\n",
+        "</section>\n", //
+    )));
+}
@@ -0,0 +1,72 @@
+// Copyright (C) 2025 SUSE LLC <petr.pavlu@suse.com>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use super::*;
+use crate::assert_ok;
+
+#[test]
+fn compare_junit() {
+    // Check that the comparison can produce a JUnit XML report.
+    let mut syms = SymCorpus::new();
+    let result = syms.load_buffer(
+        "test.symtypes",
+        concat!(
+            "s#foo struct foo { int a ; }\n",
+            "bar int bar ( s#foo * p )\n",
+            "baz int baz ( )\n",
+            "quux int quux ( )\n",
+            "F#a.symtypes bar quux\n",
+            "F#b.symtypes baz\n",
+            "M#vmlinux a.symtypes\n",
+            "M#xyz b.symtypes\n", //
+        )
+        .as_bytes(),
+    );
+    assert_ok!(result);
+    let mut syms2 = SymCorpus::new();
+    let result = syms2.load_buffer(
+        "test.symtypes",
+        concat!(
+            "s#foo struct foo { long a ; }\n",
+            "bar int bar ( s#foo * p )\n",
+            "qux int qux ( )\n",
+            "quux int quux ( )\n",
+            "F#a.symtypes bar qux quux\n", //
+        )
+        .as_bytes(),
+    );
+    assert_ok!(result);
+    let options = CompareOptions {
+        format: CompareFormat::Junit,
+        ..Default::default()
+    };
+    let mut out = Vec::new();
+    let result = syms.compare_with_options(&syms2, &options, &mut out, 1);
+    assert_ok!(result);
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<testsuites name=\"ksymtypes\" tests=\"4\" failures=\"2\" errors=\"0\" skipped=\"1\">\n",
+            "  <testsuite name=\"kabi\" tests=\"4\" failures=\"2\" errors=\"0\" skipped=\"1\">\n",
+            "    <testcase classname=\"vmlinux\" name=\"bar\">\n",
+            "      <failure message=\"Export &apos;bar&apos; is different because of a changed &apos;s#foo&apos;\">because of a changed &apos;s#foo&apos;:\n",
+            "@@ -1,3 +1,3 @@\n",
+            " struct foo {\n",
+            "-\tint a;\n",
+            "+\tlong a;\n",
+            " }\n",
+            "</failure>\n",
+            "    </testcase>\n",
+            "    <testcase classname=\"xyz\" name=\"baz\">\n",
+            "      <failure message=\"Export &apos;baz&apos; has been removed\"/>\n",
+            "    </testcase>\n",
+            "    <testcase classname=\"vmlinux\" name=\"quux\"/>\n",
+            "    <testcase classname=\"kabi\" name=\"qux\">\n",
+            "      <skipped message=\"Export &apos;qux&apos; has been added\"/>\n",
+            "    </testcase>\n",
+            "  </testsuite>\n",
+            "</testsuites>\n", //
+        )
+    );
+}
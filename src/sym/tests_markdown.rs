@@ -0,0 +1,135 @@
+// Copyright (C) 2025 SUSE LLC <petr.pavlu@suse.com>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use super::*;
+use crate::assert_ok;
+
+#[test]
+fn compare_markdown() {
+    // Check that the comparison can produce a Markdown report.
+    let mut syms = SymCorpus::new();
+    let result = syms.load_buffer(
+        "test.symtypes",
+        concat!(
+            "s#foo struct foo { int a ; }\n",
+            "bar int bar ( s#foo * p )\n",
+            "baz int baz ( s#foo * p )\n",
+            "quux int quux ( )\n", //
+        )
+        .as_bytes(),
+    );
+    assert_ok!(result);
+    let mut syms2 = SymCorpus::new();
+    let result = syms2.load_buffer(
+        "test.symtypes",
+        concat!(
+            "s#foo struct foo { long a ; }\n",
+            "bar int bar ( s#foo * p )\n",
+            "baz int baz ( s#foo * p , int b )\n",
+            "qux int qux ( )\n", //
+        )
+        .as_bytes(),
+    );
+    assert_ok!(result);
+    let options = CompareOptions {
+        format: CompareFormat::Markdown,
+        ..Default::default()
+    };
+    let mut out = Vec::new();
+    let result = syms.compare_with_options(&syms2, &options, &mut out, 1);
+    assert_ok!(result);
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        concat!(
+            "## kABI comparison\n",
+            "\n",
+            "| Change | Count |\n",
+            "| --- | ---: |\n",
+            "| Removed exports | 1 |\n",
+            "| Added exports | 1 |\n",
+            "| Changed exports | 2 |\n",
+            "| Changed structures | 1 |\n",
+            "| Changed export declarations | 1 |\n",
+            "\n",
+            "### Removed exports\n",
+            "\n",
+            "- `quux`\n",
+            "\n",
+            "### Added exports\n",
+            "\n",
+            "- `qux`\n",
+            "\n",
+            "### Changed types\n",
+            "\n",
+            "<details>\n",
+            "<summary><code>baz</code> affecting '1' exports</summary>\n",
+            "\n",
+            "Affected exports:\n",
+            "\n",
+            "- `baz`\n",
+            "\n",
+            "```diff\n",
+            "@@ -1,3 +1,4 @@\n",
+            " int baz (\n",
+            "-\ts#foo * p\n",
+            "+\ts#foo * p,\n",
+            "+\tint b\n",
+            " )\n",
+            "```\n",
+            "\n",
+            "</details>\n",
+            "\n",
+            "<details>\n",
+            "<summary><code>s#foo</code> affecting '2' exports</summary>\n",
+            "\n",
+            "Affected exports:\n",
+            "\n",
+            "- `bar`\n",
+            "- `baz`\n",
+            "\n",
+            "```diff\n",
+            "@@ -1,3 +1,3 @@\n",
+            " struct foo {\n",
+            "-\tint a;\n",
+            "+\tlong a;\n",
+            " }\n",
+            "```\n",
+            "\n",
+            "</details>\n", //
+        )
+    );
+}
+
+#[test]
+fn compare_markdown_truncated() {
+    // Check that a large Markdown report is truncated.
+    let mut data = String::new();
+    for i in 0..10000 {
+        data.push_str(&format!("foo{} int foo{} ( )\n", i, i));
+    }
+    let mut syms = SymCorpus::new();
+    let result = syms.load_buffer("test.symtypes", data.as_bytes());
+    assert_ok!(result);
+    let mut syms2 = SymCorpus::new();
+    let result = syms2.load_buffer("test.symtypes", "bar int bar ( )\n".as_bytes());
+    assert_ok!(result);
+    let options = CompareOptions {
+        format: CompareFormat::Markdown,
+        ..Default::default()
+    };
+    let mut out = Vec::new();
+    let result = syms.compare_with_options(&syms2, &options, &mut out, 1);
+    assert_ok!(result);
+    let out = String::from_utf8(out).unwrap();
+    assert!(out.starts_with(concat!(
+        "## kABI comparison\n",
+        "\n",
+        "| Change | Count |\n",
+        "| --- | ---: |\n",
+        "| Removed exports | 10000 |\n",
+        "| Added exports | 1 |\n",
+        "| Changed exports | 0 |\n", //
+    )));
+    assert!(out.len() < 61000);
+    assert!(out.ends_with(" more entries are not shown._\n"));
+}
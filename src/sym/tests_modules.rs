@@ -0,0 +1,158 @@
+// Copyright (C) 2025 SUSE LLC <petr.pavlu@suse.com>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use super::*;
+use crate::assert_ok;
+
+#[test]
+fn read_write_modules() {
+    // Check that M# records are read from a consolidated file and preserved in the output.
+    let mut syms = SymCorpus::new();
+    let result = syms.load_buffer(
+        "test.symtypes",
+        concat!(
+            "bar int bar ( )\n",
+            "baz int baz ( )\n",
+            "qux int qux ( )\n",
+            "F#drivers/foo/a.symtypes bar\n",
+            "F#drivers/foo/b.symtypes baz\n",
+            "F#kernel/c.symtypes qux\n",
+            "M#foo drivers/foo/a.symtypes drivers/foo/b.symtypes\n",
+            "M#vmlinux kernel/c.symtypes\n", //
+        )
+        .as_bytes(),
+    );
+    assert_ok!(result);
+    assert_eq!(syms.export_module("bar"), Some("foo"));
+    assert_eq!(syms.export_module("baz"), Some("foo"));
+    assert_eq!(syms.export_module("qux"), Some("vmlinux"));
+    let mut out = Vec::new();
+    let result = syms.write_consolidated_buffer(&mut out);
+    assert_ok!(result);
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        concat!(
+            "bar int bar ( )\n",
+            "baz int baz ( )\n",
+            "qux int qux ( )\n",
+            "F#drivers/foo/a.symtypes bar\n",
+            "F#drivers/foo/b.symtypes baz\n",
+            "F#kernel/c.symtypes qux\n",
+            "M#foo drivers/foo/a.symtypes drivers/foo/b.symtypes\n",
+            "M#vmlinux kernel/c.symtypes\n", //
+        )
+    );
+}
+
+#[test]
+fn read_module_record_not_consolidated() {
+    // Check that M# records are rejected in a single symtypes file.
+    let mut syms = SymCorpus::new();
+    let result = syms.load_buffer(
+        "test.symtypes",
+        concat!(
+            "bar int bar ( )\n",
+            "M#foo test.symtypes\n", //
+        )
+        .as_bytes(),
+    );
+    match result {
+        Err(err @ crate::Error::Parse { .. }) => assert_eq!(
+            err.to_string(),
+            "test.symtypes:2: Module record is allowed only in a consolidated file"
+        ),
+        result => panic!("unexpected result {:?}", result),
+    }
+}
+
+#[test]
+fn read_module_record_unknown_file() {
+    // Check that M# records which refer to an unknown file are rejected.
+    let mut syms = SymCorpus::new();
+    let result = syms.load_buffer(
+        "test.symtypes",
+        concat!(
+            "bar int bar ( )\n",
+            "F#a.symtypes bar\n",
+            "M#foo b.symtypes\n", //
+        )
+        .as_bytes(),
+    );
+    match result {
+        Err(err @ crate::Error::Parse { .. }) => assert_eq!(
+            err.to_string(),
+            "test.symtypes:3: Module record refers to unknown file 'b.symtypes'"
+        ),
+        result => panic!("unexpected result {:?}", result),
+    }
+}
+
+#[test]
+fn compare_group_by_module() {
+    // Check that the comparison can group affected exports by their modules.
+    let mut syms = SymCorpus::new();
+    let result = syms.load_buffer(
+        "test.symtypes",
+        concat!(
+            "s#foo struct foo { int a ; }\n",
+            "bar int bar ( s#foo * p )\n",
+            "baz int baz ( s#foo * p )\n",
+            "qux int qux ( )\n",
+            "F#a.symtypes bar\n",
+            "F#b.symtypes baz qux\n",
+            "M#vmlinux a.symtypes\n",
+            "M#xyz b.symtypes\n", //
+        )
+        .as_bytes(),
+    );
+    assert_ok!(result);
+    let mut syms2 = SymCorpus::new();
+    let result = syms2.load_buffer(
+        "test.symtypes",
+        concat!(
+            "s#foo struct foo { long a ; }\n",
+            "bar int bar ( s#foo * p )\n",
+            "baz int baz ( s#foo * p )\n",
+            "F#a.symtypes bar\n",
+            "F#b.symtypes baz\n",
+            "M#vmlinux a.symtypes\n",
+            "M#xyz b.symtypes\n", //
+        )
+        .as_bytes(),
+    );
+    assert_ok!(result);
+    let options = CompareOptions {
+        group_by_module: true,
+        ..Default::default()
+    };
+    let mut out = Vec::new();
+    let result = syms.compare_with_options(&syms2, &options, &mut out, 1);
+    assert_ok!(result);
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        concat!(
+            "Changes in module 'vmlinux':\n",
+            "The following '1' exports are different:\n",
+            " bar\n",
+            "\n",
+            "because of a changed 's#foo':\n",
+            "@@ -1,3 +1,3 @@\n",
+            " struct foo {\n",
+            "-\tint a;\n",
+            "+\tlong a;\n",
+            " }\n",
+            "\n",
+            "Changes in module 'xyz':\n",
+            "Export 'qux' has been removed\n",
+            "The following '1' exports are different:\n",
+            " baz\n",
+            "\n",
+            "because of a changed 's#foo':\n",
+            "@@ -1,3 +1,3 @@\n",
+            " struct foo {\n",
+            "-\tint a;\n",
+            "+\tlong a;\n",
+            " }\n", //
+        )
+    );
+}
@@ -0,0 +1,159 @@
+// Copyright (C) 2025 SUSE LLC <petr.pavlu@suse.com>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use super::*;
+use crate::assert_ok;
+
+#[test]
+fn compare_sarif() {
+    // Check that the comparison can produce a SARIF report.
+    let mut syms = SymCorpus::new();
+    let result = syms.load_buffer(
+        "test.symtypes",
+        concat!(
+            "s#foo struct foo { int a ; }\n",
+            "bar int bar ( s#foo * p )\n",
+            "baz int baz ( )\n",
+            "F#drivers/a.symtypes bar\n",
+            "F#drivers/b.symtypes baz\n", //
+        )
+        .as_bytes(),
+    );
+    assert_ok!(result);
+    let mut syms2 = SymCorpus::new();
+    let result = syms2.load_buffer(
+        "test.symtypes",
+        concat!(
+            "s#foo struct foo { long a ; }\n",
+            "bar int bar ( s#foo * p )\n",
+            "qux int qux ( )\n",
+            "F#drivers/a.symtypes bar\n",
+            "F#drivers/c.symtypes qux\n", //
+        )
+        .as_bytes(),
+    );
+    assert_ok!(result);
+    let options = CompareOptions {
+        format: CompareFormat::Sarif,
+        ..Default::default()
+    };
+    let mut out = Vec::new();
+    let result = syms.compare_with_options(&syms2, &options, &mut out, 1);
+    assert_ok!(result);
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        concat!(
+            "{\n",
+            "  \"$schema\": \"https://json.schemastore.org/sarif-2.1.0.json\",\n",
+            "  \"version\": \"2.1.0\",\n",
+            "  \"runs\": [\n",
+            "    {\n",
+            "      \"tool\": {\n",
+            "        \"driver\": {\n",
+            "          \"name\": \"ksymtypes\",\n",
+            "          \"version\": \"",
+            env!("CARGO_PKG_VERSION"),
+            "\",\n",
+            "          \"rules\": [\n",
+            "            {\n",
+            "              \"id\": \"export-removed\",\n",
+            "              \"shortDescription\": {\n",
+            "                \"text\": \"An export has been removed\"\n",
+            "              },\n",
+            "              \"defaultConfiguration\": {\n",
+            "                \"level\": \"error\"\n",
+            "              }\n",
+            "            },\n",
+            "            {\n",
+            "              \"id\": \"export-added\",\n",
+            "              \"shortDescription\": {\n",
+            "                \"text\": \"An export has been added\"\n",
+            "              },\n",
+            "              \"defaultConfiguration\": {\n",
+            "                \"level\": \"note\"\n",
+            "              }\n",
+            "            },\n",
+            "            {\n",
+            "              \"id\": \"type-changed\",\n",
+            "              \"shortDescription\": {\n",
+            "                \"text\": \"A type used by exports has changed\"\n",
+            "              },\n",
+            "              \"defaultConfiguration\": {\n",
+            "                \"level\": \"error\"\n",
+            "              }\n",
+            "            }\n",
+            "          ]\n",
+            "        }\n",
+            "      },\n",
+            "      \"results\": [\n",
+            "        {\n",
+            "          \"ruleId\": \"export-removed\",\n",
+            "          \"level\": \"error\",\n",
+            "          \"message\": {\n",
+            "            \"text\": \"Export 'baz' has been removed\"\n",
+            "          },\n",
+            "          \"locations\": [\n",
+            "            {\n",
+            "              \"physicalLocation\": {\n",
+            "                \"artifactLocation\": {\n",
+            "                  \"uri\": \"drivers/b.c\"\n",
+            "                }\n",
+            "              }\n",
+            "            }\n",
+            "          ],\n",
+            "          \"properties\": {\n",
+            "            \"exports\": [\n",
+            "              \"baz\"\n",
+            "            ]\n",
+            "          }\n",
+            "        },\n",
+            "        {\n",
+            "          \"ruleId\": \"export-added\",\n",
+            "          \"level\": \"note\",\n",
+            "          \"message\": {\n",
+            "            \"text\": \"Export 'qux' has been added\"\n",
+            "          },\n",
+            "          \"locations\": [\n",
+            "            {\n",
+            "              \"physicalLocation\": {\n",
+            "                \"artifactLocation\": {\n",
+            "                  \"uri\": \"drivers/c.c\"\n",
+            "                }\n",
+            "              }\n",
+            "            }\n",
+            "          ],\n",
+            "          \"properties\": {\n",
+            "            \"exports\": [\n",
+            "              \"qux\"\n",
+            "            ]\n",
+            "          }\n",
+            "        },\n",
+            "        {\n",
+            "          \"ruleId\": \"type-changed\",\n",
+            "          \"level\": \"error\",\n",
+            "          \"message\": {\n",
+            "            \"text\": \"Type 's#foo' has changed, affecting '1' exports: bar\"\n",
+            "          },\n",
+            "          \"locations\": [\n",
+            "            {\n",
+            "              \"physicalLocation\": {\n",
+            "                \"artifactLocation\": {\n",
+            "                  \"uri\": \"drivers/a.c\"\n",
+            "                }\n",
+            "              }\n",
+            "            }\n",
+            "          ],\n",
+            "          \"properties\": {\n",
+            "            \"exports\": [\n",
+            "              \"bar\"\n",
+            "            ],\n",
+            "            \"diff\": \"@@ -1,3 +1,3 @@\\n struct foo {\\n-\\tint a;\\n+\\tlong a;\\n }\\n\"\n",
+            "          }\n",
+            "        }\n",
+            "      ]\n",
+            "    }\n",
+            "  ]\n",
+            "}\n", //
+        )
+    );
+}
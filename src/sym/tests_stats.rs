@@ -0,0 +1,139 @@
+// Copyright (C) 2025 SUSE LLC <petr.pavlu@suse.com>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use super::*;
+use crate::assert_ok;
+
+/// Loads a small corpus with two files sharing a structure in two variants.
+fn load_stats_corpus() -> SymCorpus {
+    let mut syms = SymCorpus::new();
+    let result = syms.load_buffer(
+        "test.symtypes",
+        concat!(
+            "t#u32 typedef unsigned int u32\n",
+            "s#foo struct foo { t#u32 a ; }\n",
+            "bar int bar ( s#foo )\n", //
+        )
+        .as_bytes(),
+    );
+    assert_ok!(result);
+    let result = syms.load_buffer(
+        "test2.symtypes",
+        concat!(
+            "t#u32 typedef unsigned int u32\n",
+            "s#foo struct foo { UNKNOWN }\n",
+            "baz int baz ( s#foo )\n", //
+        )
+        .as_bytes(),
+    );
+    assert_ok!(result);
+    syms
+}
+
+#[test]
+fn stats_text() {
+    // Check the human-readable statistics of a corpus.
+    let syms = load_stats_corpus();
+    let mut out = Vec::new();
+    let result = syms.write_stats_buffer(StatsFormat::Text, &mut out, 1);
+    assert_ok!(result);
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        concat!(
+            "Files: 2\n",
+            "Exports: 2\n",
+            "Types:\n",
+            " s#: 1\n",
+            " u#: 0\n",
+            " e#: 0\n",
+            " t#: 1\n",
+            " E#: 0\n",
+            "Type variants: 3\n",
+            "Types with the most variants:\n",
+            " s#foo: 2\n",
+            " t#u32: 1\n",
+            "Largest type definitions:\n",
+            " s#foo@0: 7 tokens\n",
+            " s#foo@1: 5 tokens\n",
+            " t#u32@0: 4 tokens\n",
+            "Exports with the biggest type closures:\n",
+            " bar: 2 types\n",
+            " baz: 1 types\n",
+            "Consolidation: 6 records in files, 5 consolidated records, 16.7% saved\n", //
+        )
+    );
+}
+
+#[test]
+fn stats_largest_types() {
+    // Check that exports are not included in the largest type definitions, even if their
+    // declarations are the largest records.
+    let mut syms = SymCorpus::new();
+    let result = syms.load_buffer(
+        "test.symtypes",
+        concat!(
+            "s#foo struct foo { int a ; }\n",
+            "bar int bar ( s#foo * a , s#foo * b , s#foo * c , unsigned long d )\n", //
+        )
+        .as_bytes(),
+    );
+    assert_ok!(result);
+    let mut out = Vec::new();
+    let result = syms.write_stats_buffer(StatsFormat::Text, &mut out, 1);
+    assert_ok!(result);
+    let out = String::from_utf8(out).unwrap();
+    assert!(out.contains(concat!(
+        "Largest type definitions:\n",
+        " s#foo@0: 7 tokens\n",
+        "Exports with the biggest type closures:\n", //
+    )));
+}
+
+#[test]
+fn stats_largest_types_numbering() {
+    // Check that variants of the largest type definitions are numbered by the sorted path of the
+    // first file which uses each of them, independently of the order in which the files were
+    // loaded.
+    let mut syms = SymCorpus::new();
+    for (path, data) in [
+        (
+            "b.symtypes",
+            "s#foo struct foo { int a ; }\nbaz int baz ( s#foo )\n",
+        ),
+        (
+            "a.symtypes",
+            "s#foo struct foo { int a ; int b ; }\nbar int bar ( s#foo )\n",
+        ),
+    ] {
+        let result = syms.load_buffer(path, data.as_bytes());
+        assert_ok!(result);
+    }
+    let mut out = Vec::new();
+    let result = syms.write_stats_buffer(StatsFormat::Text, &mut out, 1);
+    assert_ok!(result);
+    let out = String::from_utf8(out).unwrap();
+    assert!(out.contains(concat!(
+        "Largest type definitions:\n",
+        " s#foo@0: 10 tokens\n",
+        " s#foo@1: 7 tokens\n",
+        "Exports with the biggest type closures:\n", //
+    )));
+}
+
+#[test]
+fn stats_json() {
+    // Check the JSON statistics of a corpus.
+    let syms = load_stats_corpus();
+    let mut out = Vec::new();
+    let result = syms.write_stats_buffer(StatsFormat::Json, &mut out, 1);
+    assert_ok!(result);
+    let out = String::from_utf8(out).unwrap();
+    assert!(out.starts_with("{\n  \"files\": 2,\n  \"exports\": 2,\n"));
+    assert!(out.contains(concat!(
+        "  \"consolidation\": {\n",
+        "    \"file_records\": 6,\n",
+        "    \"consolidated_records\": 5,\n",
+        "    \"saved_percent\": 16.666666666666664\n",
+        "  }\n", //
+    )));
+}
@@ -0,0 +1,91 @@
+// Copyright (C) 2025 SUSE LLC <petr.pavlu@suse.com>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use super::*;
+use crate::assert_ok;
+
+#[test]
+fn tree_basic() {
+    // Check that the tree of an export shows all referenced types with their variants and the
+    // members through which they are referenced.
+    let mut syms = SymCorpus::new();
+    let result = syms.load_buffer(
+        "test.symtypes",
+        concat!(
+            "t#u32 typedef unsigned int u32\n",
+            "s#bar struct bar { t#u32 x ; }\n",
+            "s#foo struct foo { s#bar * b ; int c ; }\n",
+            "baz int baz ( s#foo * a1 , t#u32 )\n", //
+        )
+        .as_bytes(),
+    );
+    assert_ok!(result);
+    let mut out = Vec::new();
+    let result = syms.write_tree_buffer("baz", &mut out);
+    assert_ok!(result);
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        concat!(
+            "baz@0\n",
+            "|-s#foo@0 (a1)\n",
+            "| `-s#bar@0 (b)\n",
+            "|   `-t#u32@0 (x)\n",
+            "`-t#u32@0 [see above]\n", //
+        )
+    );
+}
+
+#[test]
+fn tree_variant() {
+    // Check that the tree of an export shows the type variants used by the file of the export,
+    // numbered by the sorted path of the first file which uses each of them.
+    let mut syms = SymCorpus::new();
+    let result = syms.load_buffer(
+        "test2.symtypes",
+        concat!(
+            "s#foo struct foo { UNKNOWN }\n",
+            "baz int baz ( s#foo * p )\n", //
+        )
+        .as_bytes(),
+    );
+    assert_ok!(result);
+    let result = syms.load_buffer(
+        "test.symtypes",
+        concat!(
+            "s#foo struct foo { int a ; }\n",
+            "bar int bar ( s#foo )\n", //
+        )
+        .as_bytes(),
+    );
+    assert_ok!(result);
+    let mut out = Vec::new();
+    let result = syms.write_tree_buffer("baz", &mut out);
+    assert_ok!(result);
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        concat!(
+            "baz@0\n",
+            "`-s#foo@1 (p)\n", //
+        )
+    );
+}
+
+#[test]
+fn tree_unknown_export() {
+    // Check that requesting the tree of a missing export is rejected.
+    let mut syms = SymCorpus::new();
+    let result = syms.load_buffer(
+        "test.symtypes",
+        concat!(
+            "bar int bar ( )\n", //
+        )
+        .as_bytes(),
+    );
+    assert_ok!(result);
+    let mut out = Vec::new();
+    let result = syms.write_tree_buffer("baz", &mut out);
+    match result {
+        Err(crate::Error::NoSuchExport { name }) => assert_eq!(name, "baz"),
+        result => panic!("unexpected result {:?}", result),
+    }
+}
@@ -0,0 +1,358 @@
+// Copyright (C) 2025 SUSE LLC <petr.pavlu@suse.com>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use super::*;
+use crate::assert_ok;
+
+#[test]
+fn variants_basic() {
+    // Check that all variants of a type are shown with their files, grouped by directory, and with
+    // a difference against the most common variant.
+    let mut syms = SymCorpus::new();
+    for (path, data) in [
+        (
+            "a/test.symtypes",
+            "s#foo struct foo { int a ; }\nbar int bar ( s#foo )\n",
+        ),
+        (
+            "a/test2.symtypes",
+            "s#foo struct foo { long a ; }\nbaz int baz ( s#foo )\n",
+        ),
+        (
+            "b/test3.symtypes",
+            "s#foo struct foo { long a ; }\nqux int qux ( s#foo )\n",
+        ),
+    ] {
+        let result = syms.load_buffer(path, data.as_bytes());
+        assert_ok!(result);
+    }
+    let mut out = Vec::new();
+    let result = syms.write_variants_buffer("s#foo", &mut out);
+    assert_ok!(result);
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        concat!(
+            "Type 's#foo' has '2' variants\n",
+            "\n",
+            "Variant '1' is used by the following '2' files:\n",
+            " a/\n",
+            "  test2.symtypes\n",
+            " b/\n",
+            "  test3.symtypes\n",
+            "\n",
+            "with the definition:\n",
+            "struct foo {\n",
+            "\tlong a;\n",
+            "}\n",
+            "\n",
+            "Variant '0' is used by the following '1' files:\n",
+            " a/\n",
+            "  test.symtypes\n",
+            "\n",
+            "with the definition:\n",
+            "struct foo {\n",
+            "\tint a;\n",
+            "}\n",
+            "\n",
+            "and the difference against the most common variant '1':\n",
+            "@@ -1,3 +1,3 @@\n",
+            " struct foo {\n",
+            "-\tlong a;\n",
+            "+\tint a;\n",
+            " }\n", //
+        )
+    );
+}
+
+#[test]
+fn variants_unknown_type() {
+    // Check that requesting variants of a missing type is rejected.
+    let mut syms = SymCorpus::new();
+    let result = syms.load_buffer(
+        "test.symtypes",
+        concat!(
+            "bar int bar ( )\n", //
+        )
+        .as_bytes(),
+    );
+    assert_ok!(result);
+    let mut out = Vec::new();
+    let result = syms.write_variants_buffer("s#foo", &mut out);
+    match result {
+        Err(crate::Error::NoSuchType { name }) => assert_eq!(name, "s#foo"),
+        result => panic!("unexpected result {:?}", result),
+    }
+}
+
+#[test]
+fn odr_violations() {
+    // Check that types with multiple complete definitions are reported, while types which differ
+    // only by being opaque are not.
+    let mut syms = SymCorpus::new();
+    for (path, data) in [
+        (
+            "a/test.symtypes",
+            "s#foo struct foo { int a ; }\ns#bar struct bar { int b ; }\nbar int bar ( s#foo , s#bar )\n",
+        ),
+        (
+            "a/test2.symtypes",
+            "s#foo struct foo { long a ; }\ns#bar struct bar { UNKNOWN }\nbaz int baz ( s#foo , s#bar )\n",
+        ),
+    ] {
+        let result = syms.load_buffer(path, data.as_bytes());
+        assert_ok!(result);
+    }
+    let mut out = Vec::new();
+    let result = syms.write_odr_violations_buffer(&mut out);
+    assert!(matches!(result, Ok(1)));
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        concat!(
+            "Type 's#foo' has '2' conflicting complete definitions\n",
+            "\n",
+            "Variant '0' is used by the following '1' files:\n",
+            " a/\n",
+            "  test.symtypes\n",
+            "\n",
+            "Variant '1' is used by the following '1' files:\n",
+            " a/\n",
+            "  test2.symtypes\n",
+            "\n",
+            "and the difference against the most common variant '0':\n",
+            "@@ -1,3 +1,3 @@\n",
+            " struct foo {\n",
+            "-\tint a;\n",
+            "+\tlong a;\n",
+            " }\n", //
+        )
+    );
+}
+
+#[test]
+fn odr_no_violations() {
+    // Check that a corpus without conflicting definitions produces no report.
+    let mut syms = SymCorpus::new();
+    let result = syms.load_buffer(
+        "test.symtypes",
+        concat!(
+            "s#foo struct foo { int a ; }\n",
+            "bar int bar ( s#foo )\n", //
+        )
+        .as_bytes(),
+    );
+    assert_ok!(result);
+    let mut out = Vec::new();
+    let result = syms.write_odr_violations_buffer(&mut out);
+    assert!(matches!(result, Ok(0)));
+    assert_eq!(String::from_utf8(out).unwrap(), "");
+}
+
+#[test]
+fn odr_violations_gendwarfksyms() {
+    // Check that conflicting complete definitions are reported in gendwarfksyms data, while
+    // declarations without any members and size are recognized as opaque.
+    let mut syms = SymCorpus::new();
+    for (path, data) in [
+        (
+            "a/test.symtypes",
+            concat!(
+                "s#foo structure_type foo { member base_type int byte_size(4) encoding(5) a data_member_location(0) } byte_size(4)\n",
+                "s#bar structure_type bar { member base_type int byte_size(4) encoding(5) b data_member_location(0) } byte_size(4)\n",
+                "bar subprogram ( formal_parameter pointer_type { s#foo } byte_size(8) p , formal_parameter pointer_type { s#bar } byte_size(8) q ) -> base_type void\n", //
+            ),
+        ),
+        (
+            "a/test2.symtypes",
+            concat!(
+                "s#foo structure_type foo { member base_type long int byte_size(8) encoding(5) a data_member_location(0) } byte_size(8)\n",
+                "s#bar structure_type bar { }\n",
+                "baz subprogram ( formal_parameter pointer_type { s#foo } byte_size(8) p , formal_parameter pointer_type { s#bar } byte_size(8) q ) -> base_type void\n", //
+            ),
+        ),
+    ] {
+        let result = syms.load_buffer(path, data.as_bytes());
+        assert_ok!(result);
+    }
+    let mut out = Vec::new();
+    let result = syms.write_odr_violations_buffer(&mut out);
+    assert!(matches!(result, Ok(1)));
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        concat!(
+            "Type 's#foo' has '2' conflicting complete definitions\n",
+            "\n",
+            "Variant '0' is used by the following '1' files:\n",
+            " a/\n",
+            "  test.symtypes\n",
+            "\n",
+            "Variant '1' is used by the following '1' files:\n",
+            " a/\n",
+            "  test2.symtypes\n",
+            "\n",
+            "and the difference against the most common variant '0':\n",
+            "@@ -1,3 +1,3 @@\n",
+            " structure_type foo {\n",
+            "-\tmember base_type int byte_size(4) encoding(5) a data_member_location(0)\n",
+            "-} byte_size(4)\n",
+            "+\tmember base_type long int byte_size(8) encoding(5) a data_member_location(0)\n",
+            "+} byte_size(8)\n", //
+        )
+    );
+}
+
+#[test]
+fn variants_prefer_complete() {
+    // Check that a complete definition is preferred over an opaque one as the most common variant
+    // if both are used by the same number of files.
+    let mut syms = SymCorpus::new();
+    for (path, data) in [
+        (
+            "test.symtypes",
+            concat!(
+                "s#foo structure_type foo { }\n",
+                "bar subprogram ( formal_parameter pointer_type { s#foo } byte_size(8) p ) -> base_type void\n", //
+            ),
+        ),
+        (
+            "test2.symtypes",
+            concat!(
+                "s#foo structure_type foo { member base_type int byte_size(4) encoding(5) a data_member_location(0) } byte_size(4)\n",
+                "baz subprogram ( formal_parameter pointer_type { s#foo } byte_size(8) p ) -> base_type void\n", //
+            ),
+        ),
+    ] {
+        let result = syms.load_buffer(path, data.as_bytes());
+        assert_ok!(result);
+    }
+    let mut out = Vec::new();
+    let result = syms.write_variants_buffer("s#foo", &mut out);
+    assert_ok!(result);
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        concat!(
+            "Type 's#foo' has '2' variants\n",
+            "\n",
+            "Variant '1' is used by the following '1' files:\n",
+            " ./\n",
+            "  test2.symtypes\n",
+            "\n",
+            "with the definition:\n",
+            "structure_type foo {\n",
+            "\tmember base_type int byte_size(4) encoding(5) a data_member_location(0)\n",
+            "} byte_size(4)\n",
+            "\n",
+            "Variant '0' is used by the following '1' files:\n",
+            " ./\n",
+            "  test.symtypes\n",
+            "\n",
+            "with the definition:\n",
+            "structure_type foo {\n",
+            "}\n",
+            "\n",
+            "and the difference against the most common variant '1':\n",
+            "@@ -1,3 +1,2 @@\n",
+            " structure_type foo {\n",
+            "-\tmember base_type int byte_size(4) encoding(5) a data_member_location(0)\n",
+            "-} byte_size(4)\n",
+            "+}\n", //
+        )
+    );
+}
+
+#[test]
+fn variants_numbering() {
+    // Check that variants are numbered by the sorted path of the first file which uses each of
+    // them, independently of the order in which the files were loaded.
+    let mut syms = SymCorpus::new();
+    for (path, data) in [
+        (
+            "b.symtypes",
+            "s#foo struct foo { long a ; }\nbaz int baz ( s#foo )\n",
+        ),
+        (
+            "a.symtypes",
+            "s#foo struct foo { int a ; }\nbar int bar ( s#foo )\n",
+        ),
+    ] {
+        let result = syms.load_buffer(path, data.as_bytes());
+        assert_ok!(result);
+    }
+    let mut out = Vec::new();
+    let result = syms.write_variants_buffer("s#foo", &mut out);
+    assert_ok!(result);
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        concat!(
+            "Type 's#foo' has '2' variants\n",
+            "\n",
+            "Variant '0' is used by the following '1' files:\n",
+            " ./\n",
+            "  a.symtypes\n",
+            "\n",
+            "with the definition:\n",
+            "struct foo {\n",
+            "\tint a;\n",
+            "}\n",
+            "\n",
+            "Variant '1' is used by the following '1' files:\n",
+            " ./\n",
+            "  b.symtypes\n",
+            "\n",
+            "with the definition:\n",
+            "struct foo {\n",
+            "\tlong a;\n",
+            "}\n",
+            "\n",
+            "and the difference against the most common variant '0':\n",
+            "@@ -1,3 +1,3 @@\n",
+            " struct foo {\n",
+            "-\tint a;\n",
+            "+\tlong a;\n",
+            " }\n", //
+        )
+    );
+}
+
+#[test]
+fn odr_violations_numbering() {
+    // Check that conflicting variants are numbered by the sorted path of the first file which uses
+    // each of them, independently of the order in which the files were loaded.
+    let mut syms = SymCorpus::new();
+    for (path, data) in [
+        (
+            "b.symtypes",
+            "s#foo struct foo { long a ; }\nbaz int baz ( s#foo )\n",
+        ),
+        (
+            "a.symtypes",
+            "s#foo struct foo { int a ; }\nbar int bar ( s#foo )\n",
+        ),
+    ] {
+        let result = syms.load_buffer(path, data.as_bytes());
+        assert_ok!(result);
+    }
+    let mut out = Vec::new();
+    let result = syms.write_odr_violations_buffer(&mut out);
+    assert!(matches!(result, Ok(1)));
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        concat!(
+            "Type 's#foo' has '2' conflicting complete definitions\n",
+            "\n",
+            "Variant '0' is used by the following '1' files:\n",
+            " ./\n",
+            "  a.symtypes\n",
+            "\n",
+            "Variant '1' is used by the following '1' files:\n",
+            " ./\n",
+            "  b.symtypes\n",
+            "\n",
+            "and the difference against the most common variant '0':\n",
+            "@@ -1,3 +1,3 @@\n",
+            " struct foo {\n",
+            "-\tint a;\n",
+            "+\tlong a;\n",
+            " }\n", //
+        )
+    );
+}
@@ -0,0 +1,297 @@
+// Copyright (C) 2025 SUSE LLC <petr.pavlu@suse.com>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use super::*;
+use crate::{assert_ok, string_vec};
+
+/// Loads a `.mod.c` file from a specified buffer into a corpus.
+fn load_versions_buffer(syms: &mut SymCorpus, path: &str, data: &str) -> Result<(), crate::Error> {
+    SymCorpus::load_versions(path, data.as_bytes(), &LoadContext::new(syms))
+}
+
+#[test]
+fn read_versions() {
+    // Check that imported symbols are read from the __versions table of a module.
+    let mut syms = SymCorpus::new();
+    let result = load_versions_buffer(
+        &mut syms,
+        "drivers/foo.mod.c",
+        concat!(
+            "#include <linux/module.h>\n",
+            "\n",
+            "static const struct modversion_info ____versions[]\n",
+            "__used __section(\"__versions\") = {\n",
+            "\t{ 0x12345678, \"bar\" },\n",
+            "\t{ 0x9abcdef0, \"baz\" },\n",
+            "};\n",
+            "\n",
+            "MODULE_INFO(srcversion, \"0123456789ABCDEF\");\n", //
+        ),
+    );
+    assert_ok!(result);
+    let result = load_versions_buffer(
+        &mut syms,
+        "drivers/qux.mod.c",
+        concat!(
+            "static const struct modversion_info ____versions[]\n",
+            "__used __attribute__((section(\"__versions\"))) = {\n",
+            "\t{ 0x12345678, \"bar\" },\n",
+            "};\n", //
+        ),
+    );
+    assert_ok!(result);
+    assert_eq!(
+        syms.consumers,
+        HashMap::from([
+            ("bar".to_string(), string_vec!["foo", "qux"]),
+            ("baz".to_string(), string_vec!["foo"]),
+        ])
+    );
+}
+
+#[test]
+fn read_ext_versions() {
+    // Check that imported symbols are read from the __version_ext_names table of a module, which
+    // is preferred over the __versions table.
+    let mut syms = SymCorpus::new();
+    let result = load_versions_buffer(
+        &mut syms,
+        "drivers/foo.mod.c",
+        concat!(
+            "static const struct modversion_info ____versions[]\n",
+            "__used __section(\"__versions\") = {\n",
+            "\t{ 0x12345678, \"bar\" },\n",
+            "};\n",
+            "\n",
+            "static const u32 ____version_ext_crcs[]\n",
+            "__used __section(\"__version_ext_crcs\") = {\n",
+            "\t0x12345678,\n",
+            "\t0x9abcdef0,\n",
+            "};\n",
+            "static const char ____version_ext_names[]\n",
+            "__used __section(\"__version_ext_names\") =\n",
+            "\t\"bar\\0\"\n",
+            "\t\"a_symbol_with_a_name_too_long_for_the_versions_table\\0\"\n",
+            ";\n", //
+        ),
+    );
+    assert_ok!(result);
+    let result = load_versions_buffer(
+        &mut syms,
+        "drivers/qux.mod.c",
+        concat!(
+            "static const u32 ____version_ext_crcs[]\n",
+            "__used __section(\"__version_ext_crcs\") = {\n",
+            "\t0x12345678,\n",
+            "};\n",
+            "static const char ____version_ext_names[]\n",
+            "__used __section(\"__version_ext_names\") =\n",
+            "\t\"bar\\0\"\n",
+            ";\n", //
+        ),
+    );
+    assert_ok!(result);
+    assert_eq!(
+        syms.consumers,
+        HashMap::from([
+            ("bar".to_string(), string_vec!["foo", "qux"]),
+            (
+                "a_symbol_with_a_name_too_long_for_the_versions_table".to_string(),
+                string_vec!["foo"]
+            ),
+        ])
+    );
+}
+
+#[test]
+fn read_invalid_version() {
+    // Check that a malformed entry in the __versions table is rejected.
+    let mut syms = SymCorpus::new();
+    let result = load_versions_buffer(
+        &mut syms,
+        "foo.mod.c",
+        concat!(
+            "static const struct modversion_info ____versions[]\n",
+            "__used __section(\"__versions\") = {\n",
+            "\t0x12345678, bar\n",
+            "};\n", //
+        ),
+    );
+    match result {
+        Err(err @ crate::Error::Parse { .. }) => {
+            assert_eq!(err.to_string(), "foo.mod.c:3: Invalid version entry")
+        }
+        result => panic!("unexpected result {:?}", result),
+    }
+}
+
+#[test]
+fn compare_consumers() {
+    // Check that the comparison reports modules which consume changed and removed exports.
+    let mut syms = SymCorpus::new();
+    let result = syms.load_buffer(
+        "test.symtypes",
+        concat!(
+            "bar int bar ( int a )\n",
+            "baz int baz ( )\n",
+            "qux int qux ( )\n", //
+        )
+        .as_bytes(),
+    );
+    assert_ok!(result);
+    let mut syms2 = SymCorpus::new();
+    let result = syms2.load_buffer(
+        "test.symtypes",
+        concat!(
+            "bar int bar ( long a )\n",
+            "qux int qux ( )\n", //
+        )
+        .as_bytes(),
+    );
+    assert_ok!(result);
+    let result = load_versions_buffer(
+        &mut syms2,
+        "a.mod.c",
+        concat!(
+            "static const struct modversion_info ____versions[]\n",
+            "__used __section(\"__versions\") = {\n",
+            "\t{ 0x12345678, \"bar\" },\n",
+            "\t{ 0x12345678, \"baz\" },\n",
+            "\t{ 0x12345678, \"qux\" },\n",
+            "};\n", //
+        ),
+    );
+    assert_ok!(result);
+    let result = load_versions_buffer(
+        &mut syms2,
+        "b.mod.c",
+        concat!(
+            "static const struct modversion_info ____versions[]\n",
+            "__used __section(\"__versions\") = {\n",
+            "\t{ 0x12345678, \"bar\" },\n",
+            "};\n", //
+        ),
+    );
+    assert_ok!(result);
+    let mut out = Vec::new();
+    let result = syms.compare_with(&syms2, &mut out, 1);
+    assert_ok!(result);
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        concat!(
+            "Export 'baz' has been removed (consumed by '1' modules: a)\n",
+            "The following '1' exports are different:\n",
+            " bar (consumed by '2' modules: a, b)\n",
+            "\n",
+            "because of a changed 'bar':\n",
+            "@@ -1,3 +1,3 @@\n",
+            " int bar (\n",
+            "-\tint a\n",
+            "+\tlong a\n",
+            " )\n", //
+        )
+    );
+}
+
+#[test]
+fn check_module() {
+    // Check that only removed and changed imports of a module are reported.
+    let mut syms = SymCorpus::new();
+    let result = syms.load_buffer(
+        "test.symtypes",
+        concat!(
+            "bar int bar ( int a )\n",
+            "baz int baz ( )\n",
+            "qux int qux ( )\n",
+            "zed int zed ( int a )\n", //
+        )
+        .as_bytes(),
+    );
+    assert_ok!(result);
+    let mut syms2 = SymCorpus::new();
+    let result = syms2.load_buffer(
+        "test.symtypes",
+        concat!(
+            "bar int bar ( long a )\n",
+            "qux int qux ( )\n",
+            "zed int zed ( long a )\n",
+            "quux int quux ( )\n", //
+        )
+        .as_bytes(),
+    );
+    assert_ok!(result);
+    let imports = string_vec!["bar", "baz", "qux", "quux", "module_layout"];
+    let mut out = Vec::new();
+    let result = syms.check_module_with(&syms2, &imports, &mut out, 1);
+    assert_eq!(result.ok(), Some(2));
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        concat!(
+            "Export 'baz' has been removed\n",
+            "The following '1' exports are different:\n",
+            " bar\n",
+            "\n",
+            "because of a changed 'bar':\n",
+            "@@ -1,3 +1,3 @@\n",
+            " int bar (\n",
+            "-\tint a\n",
+            "+\tlong a\n",
+            " )\n", //
+        )
+    );
+}
+
+#[test]
+fn check_module_multiple_changes() {
+    // Check that an import affected by several changed types is counted only once.
+    let mut syms = SymCorpus::new();
+    let result = syms.load_buffer(
+        "test.symtypes",
+        concat!(
+            "s#a struct a { int m ; }\n",
+            "s#b struct b { int m ; }\n",
+            "foo int foo ( s#a * , s#b * )\n", //
+        )
+        .as_bytes(),
+    );
+    assert_ok!(result);
+    let mut syms2 = SymCorpus::new();
+    let result = syms2.load_buffer(
+        "test.symtypes",
+        concat!(
+            "s#a struct a { long m ; }\n",
+            "s#b struct b { long m ; }\n",
+            "foo int foo ( s#a * , s#b * )\n", //
+        )
+        .as_bytes(),
+    );
+    assert_ok!(result);
+    let imports = string_vec!["foo"];
+    let mut out = Vec::new();
+    let result = syms.check_module_with(&syms2, &imports, &mut out, 1);
+    assert_eq!(result.ok(), Some(1));
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        concat!(
+            "The following '1' exports are different:\n",
+            " foo\n",
+            "\n",
+            "because of a changed 's#a':\n",
+            "@@ -1,3 +1,3 @@\n",
+            " struct a {\n",
+            "-\tint m;\n",
+            "+\tlong m;\n",
+            " }\n",
+            "\n",
+            "The following '1' exports are different:\n",
+            " foo\n",
+            "\n",
+            "because of a changed 's#b':\n",
+            "@@ -1,3 +1,3 @@\n",
+            " struct b {\n",
+            "-\tint m;\n",
+            "+\tlong m;\n",
+            " }\n", //
+        )
+    );
+}
@@ -0,0 +1,143 @@
+// Copyright (C) 2025 SUSE LLC <petr.pavlu@suse.com>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use super::{SymCorpus, SymFile, Token, Tokens, VariantNumbers};
+use crate::MapIOErr;
+use std::collections::HashSet;
+use std::io::{prelude::*, BufWriter};
+
+/// Type names already printed in the tree of a specific export.
+type TreeVisitedTypes<'a> = HashSet<&'a str>;
+
+impl SymCorpus {
+    /// Writes the reference tree of a specified export to the provided output stream.
+    ///
+    /// Each node of the tree shows the type name, its variant number and, if it can be determined,
+    /// the member or parameter through which the type is referenced by its parent. The variants are
+    /// numbered in the same way as by [`SymCorpus::write_variants_buffer()`].
+    /// A type that was already expanded earlier in the tree is marked as such and not expanded
+    /// again.
+    pub fn write_tree_buffer<W: Write>(&self, export: &str, writer: W) -> Result<(), crate::Error> {
+        let mut writer = BufWriter::new(writer);
+
+        let file_idx = match self.exports.get(export) {
+            Some(&file_idx) => file_idx,
+            None => {
+                return Err(crate::Error::NoSuchExport {
+                    name: export.to_string(),
+                })
+            }
+        };
+        let file = &self.files[file_idx];
+
+        let variant_numbers = self.get_variant_numbers();
+        let mut visited = TreeVisitedTypes::new();
+        self.write_tree_node(
+            file,
+            export,
+            export,
+            None,
+            "",
+            "",
+            &variant_numbers,
+            &mut visited,
+            &mut writer,
+        )
+    }
+
+    /// Writes a single node of the reference tree and recursively all its children.
+    ///
+    /// The `prefix` is written before the node itself, the `child_prefix` is used as the base
+    /// indentation of all its children.
+    #[allow(clippy::too_many_arguments)]
+    fn write_tree_node<'a, W: Write>(
+        &'a self,
+        file: &SymFile,
+        name: &'a str,
+        export: &str,
+        member: Option<&str>,
+        prefix: &str,
+        child_prefix: &str,
+        variant_numbers: &VariantNumbers,
+        visited: &mut TreeVisitedTypes<'a>,
+        writer: &mut BufWriter<W>,
+    ) -> Result<(), crate::Error> {
+        let err_desc = "Failed to write a tree node";
+
+        let (variant_idx, tokens) = Self::get_type_variant(self, file, name, export)?;
+
+        let variant_number = variant_numbers[name][variant_idx];
+        write!(writer, "{}{}@{}", prefix, name, variant_number).map_io_err(err_desc)?;
+        if let Some(member) = member {
+            write!(writer, " ({})", member).map_io_err(err_desc)?;
+        }
+
+        // Check if the type was already expanded.
+        if visited.get(name).is_some() {
+            writeln!(writer, " [see above]").map_io_err(err_desc)?;
+            return Ok(());
+        }
+        visited.insert(name);
+        writeln!(writer).map_io_err(err_desc)?;
+
+        // Collect all type references and process them recursively.
+        let children = tokens
+            .iter()
+            .enumerate()
+            .filter_map(|(i, token)| match token {
+                Token::TypeRef(ref_name) => Some((ref_name.as_str(), find_member_name(tokens, i))),
+                Token::Atom(_word) => None,
+            })
+            .collect::<Vec<_>>();
+
+        for (i, (ref_name, ref_member)) in children.iter().enumerate() {
+            let is_last = i == children.len() - 1;
+            let (node_prefix, node_child_prefix) = if is_last {
+                (format!("{}`-", child_prefix), format!("{}  ", child_prefix))
+            } else {
+                (format!("{}|-", child_prefix), format!("{}| ", child_prefix))
+            };
+            self.write_tree_node(
+                file,
+                ref_name,
+                export,
+                *ref_member,
+                &node_prefix,
+                &node_child_prefix,
+                variant_numbers,
+                visited,
+                writer,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Determines the name of a member or parameter declared with the type referenced by the token at
+/// `ref_idx`.
+///
+/// The function skips over any pointer and qualifier tokens that follow the type reference and
+/// returns the first identifier, if present. For instance, the name `a` is found for
+/// `s#foo * const a ;`, and no name for `int bar ( s#foo )`.
+fn find_member_name(tokens: &Tokens, ref_idx: usize) -> Option<&str> {
+    for token in &tokens[ref_idx + 1..] {
+        let word = match token {
+            Token::TypeRef(_) => return None,
+            Token::Atom(word) => word.as_str(),
+        };
+        match word {
+            "*" | "(" | "const" | "volatile" | "restrict" | "__restrict" => continue,
+            _ => {}
+        }
+        let is_ident = word
+            .chars()
+            .next()
+            .is_some_and(|ch| ch.is_ascii_alphabetic() || ch == '_')
+            && word
+                .chars()
+                .all(|ch| ch.is_ascii_alphanumeric() || ch == '_');
+        return if is_ident { Some(word) } else { None };
+    }
+    None
+}
@@ -0,0 +1,285 @@
+// Copyright (C) 2025 SUSE LLC <petr.pavlu@suse.com>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use super::dwarf::is_gendwarfksyms_opaque_type;
+use super::{
+    is_export_name, pretty_format_type, write_type_diff_with_options, Dialect, SymCorpus, SymFile,
+    Token, Tokens,
+};
+use crate::diff::{DiffOptions, ANSI_BOLD, ANSI_RESET};
+use crate::MapIOErr;
+use std::collections::BTreeMap;
+use std::io::{prelude::*, BufWriter};
+use std::path::Path;
+
+impl SymCorpus {
+    /// Returns for each variant of a specified type the files in the corpus which use it.
+    ///
+    /// The returned [`Vec`] is indexed by the variant index and each item is sorted by the file
+    /// path.
+    fn get_variant_files(&self, name: &str, num_variants: usize) -> Vec<Vec<&SymFile>> {
+        let mut variant_files = vec![Vec::new(); num_variants];
+        for file in &self.files {
+            if let Some(&variant_idx) = file.records.get(name) {
+                variant_files[variant_idx].push(file);
+            }
+        }
+        for files in &mut variant_files {
+            files.sort_by_key(|file| &file.path);
+        }
+        variant_files
+    }
+
+    /// Writes all variants of a specified type to the provided output stream.
+    ///
+    /// Each variant is shown together with the files that use it, grouped by their directory. All
+    /// variants except the most common one, used by the most files, additionally show their
+    /// difference against the most common variant. If several variants are used by the same number
+    /// of files, a complete definition is preferred over an opaque one as the most common variant,
+    /// and then the variant with the lower number.
+    ///
+    /// The variants are numbered in the order of the first file which uses each of them, with the
+    /// files sorted by their path.
+    pub fn write_variants_buffer<W: Write>(
+        &self,
+        name: &str,
+        writer: W,
+    ) -> Result<(), crate::Error> {
+        self.write_variants_buffer_with_options(name, &DiffOptions::default(), writer)
+    }
+
+    /// Writes all variants of a specified type to the provided output stream, with the differences
+    /// controlled by the specified `options`.
+    ///
+    /// The function works as [`SymCorpus::write_variants_buffer()`].
+    pub fn write_variants_buffer_with_options<W: Write>(
+        &self,
+        name: &str,
+        options: &DiffOptions,
+        writer: W,
+    ) -> Result<(), crate::Error> {
+        let mut writer = BufWriter::new(writer);
+        let err_desc = "Failed to write a type variant";
+
+        let variants = match self.types.get(name) {
+            Some(variants) => variants,
+            None => {
+                return Err(crate::Error::NoSuchType {
+                    name: name.to_string(),
+                })
+            }
+        };
+        let variant_files = self.get_variant_files(name, variants.len());
+        let variant_numbers = &self.get_variant_numbers()[name];
+
+        // Order the variants from the most commonly used one.
+        let mut order = (0..variants.len()).collect::<Vec<_>>();
+        order.sort_by_key(|&i| {
+            (
+                std::cmp::Reverse(variant_files[i].len()),
+                is_opaque_type(&variants[i], self.dialect()),
+                variant_numbers[i],
+            )
+        });
+        let common_idx = order[0];
+
+        writeln!(writer, "Type '{}' has '{}' variants", name, variants.len())
+            .map_io_err(err_desc)?;
+
+        for variant_idx in order {
+            writeln!(writer).map_io_err(err_desc)?;
+
+            let files = &variant_files[variant_idx];
+            writeln!(
+                writer,
+                "Variant '{}' is used by the following '{}' files:",
+                variant_numbers[variant_idx],
+                files.len()
+            )
+            .map_io_err(err_desc)?;
+            write_grouped_files(files, writer.by_ref())?;
+            writeln!(writer).map_io_err(err_desc)?;
+
+            writeln!(writer, "with the definition:").map_io_err(err_desc)?;
+            for line in pretty_format_type(&variants[variant_idx]) {
+                writeln!(writer, "{}", line).map_io_err(err_desc)?;
+            }
+
+            if variant_idx != common_idx {
+                write_variant_diff(
+                    &variants[common_idx],
+                    &variants[variant_idx],
+                    variant_numbers[common_idx],
+                    options,
+                    writer.by_ref(),
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks the corpus for types which have more than one complete definition and writes
+    /// a report about them to the provided output stream.
+    ///
+    /// Different compilation units can see different complete definitions of the same type, for
+    /// instance, because of a header that depends on the kernel configuration. This is a violation
+    /// of the One Definition Rule (ODR). Unlike the difference between an opaque and a full
+    /// definition, it is not expected and typically indicates a bug.
+    ///
+    /// Each found type is reported with all its complete variants and their files. The variants
+    /// which are not the most common one additionally show their difference against it. The
+    /// variants are numbered in the same way as by [`SymCorpus::write_variants_buffer()`]. The
+    /// function returns the number of found types.
+    pub fn write_odr_violations_buffer<W: Write>(&self, writer: W) -> Result<usize, crate::Error> {
+        self.write_odr_violations_buffer_with_options(&DiffOptions::default(), writer)
+    }
+
+    /// Checks the corpus for types which have more than one complete definition and writes
+    /// a report about them to the provided output stream, with the differences controlled by the
+    /// specified `options`.
+    ///
+    /// The function works as [`SymCorpus::write_odr_violations_buffer()`].
+    pub fn write_odr_violations_buffer_with_options<W: Write>(
+        &self,
+        options: &DiffOptions,
+        writer: W,
+    ) -> Result<usize, crate::Error> {
+        let mut writer = BufWriter::new(writer);
+        let err_desc = "Failed to write an ODR violation";
+
+        // Find all types with multiple complete definitions.
+        let mut names = self
+            .types
+            .iter()
+            .filter(|(name, variants)| {
+                !is_export_name(name)
+                    && variants
+                        .iter()
+                        .filter(|tokens| !is_opaque_type(tokens, self.dialect()))
+                        .count()
+                        > 1
+            })
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>();
+        names.sort();
+
+        let all_variant_numbers = self.get_variant_numbers();
+
+        for (i, name) in names.iter().enumerate() {
+            if i != 0 {
+                writeln!(writer).map_io_err(err_desc)?;
+            }
+
+            let variants = self.types.get(*name).unwrap();
+            let variant_files = self.get_variant_files(name, variants.len());
+            let variant_numbers = &all_variant_numbers[name];
+
+            // Order the complete variants from the most commonly used one.
+            let mut order = (0..variants.len())
+                .filter(|&i| !is_opaque_type(&variants[i], self.dialect()))
+                .collect::<Vec<_>>();
+            order.sort_by_key(|&i| {
+                (
+                    std::cmp::Reverse(variant_files[i].len()),
+                    variant_numbers[i],
+                )
+            });
+            let common_idx = order[0];
+
+            writeln!(
+                writer,
+                "Type '{}' has '{}' conflicting complete definitions",
+                name,
+                order.len()
+            )
+            .map_io_err(err_desc)?;
+
+            for variant_idx in order {
+                writeln!(writer).map_io_err(err_desc)?;
+                writeln!(
+                    writer,
+                    "Variant '{}' is used by the following '{}' files:",
+                    variant_numbers[variant_idx],
+                    variant_files[variant_idx].len()
+                )
+                .map_io_err(err_desc)?;
+                write_grouped_files(&variant_files[variant_idx], writer.by_ref())?;
+
+                if variant_idx != common_idx {
+                    write_variant_diff(
+                        &variants[common_idx],
+                        &variants[variant_idx],
+                        variant_numbers[common_idx],
+                        options,
+                        writer.by_ref(),
+                    )?;
+                }
+            }
+        }
+
+        Ok(names.len())
+    }
+}
+
+/// Writes the difference of a type variant against the most common variant, given by its number,
+/// to the provided output stream.
+fn write_variant_diff<W: Write>(
+    common_tokens: &Tokens,
+    tokens: &Tokens,
+    common_number: usize,
+    options: &DiffOptions,
+    mut writer: W,
+) -> Result<(), crate::Error> {
+    let err_desc = "Failed to write a type variant difference";
+
+    writeln!(writer).map_io_err(err_desc)?;
+    let banner = format!(
+        "and the difference against the most common variant '{}':",
+        common_number
+    );
+    if options.color {
+        writeln!(writer, "{}{}{}", ANSI_BOLD, banner, ANSI_RESET).map_io_err(err_desc)?;
+    } else {
+        writeln!(writer, "{}", banner).map_io_err(err_desc)?;
+    }
+    write_type_diff_with_options(common_tokens, tokens, options, writer)
+}
+
+/// Writes a list of files, grouped by their directory, to the provided output stream.
+fn write_grouped_files<W: Write>(files: &[&SymFile], mut writer: W) -> Result<(), crate::Error> {
+    let err_desc = "Failed to write a list of files";
+
+    let mut dirs: BTreeMap<&Path, Vec<&SymFile>> = BTreeMap::new();
+    for file in files {
+        let dir = file.path.parent().unwrap_or(Path::new(""));
+        dirs.entry(dir).or_default().push(file);
+    }
+    for (dir, dir_files) in dirs {
+        if dir.as_os_str().is_empty() {
+            writeln!(writer, " ./").map_io_err(err_desc)?;
+        } else {
+            writeln!(writer, " {}/", dir.display()).map_io_err(err_desc)?;
+        }
+        for file in dir_files {
+            let file_name = file.path.file_name().unwrap_or(file.path.as_os_str());
+            writeln!(writer, "  {}", Path::new(file_name).display()).map_io_err(err_desc)?;
+        }
+    }
+    Ok(())
+}
+
+/// Returns whether the specified type definition in a given dialect is opaque. It is represented in
+/// genksyms as `struct foo { UNKNOWN }` and in gendwarfksyms as a declaration without any members
+/// and size, such as `structure_type foo { }`.
+fn is_opaque_type(tokens: &Tokens, dialect: Dialect) -> bool {
+    match dialect {
+        Dialect::Genksyms => match tokens.as_slice() {
+            [.., Token::Atom(open), Token::Atom(unknown), Token::Atom(close)] => {
+                open == "{" && unknown == "UNKNOWN" && close == "}"
+            }
+            _ => false,
+        },
+        Dialect::Gendwarfksyms => is_gendwarfksyms_opaque_type(tokens),
+    }
+}
@@ -0,0 +1,187 @@
+// Copyright (C) 2025 SUSE LLC <petr.pavlu@suse.com>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use super::{read_lines, LoadContext, SymCorpus};
+use crate::elf::ElfObject;
+use crate::{debug, PathFile};
+use std::io::prelude::*;
+use std::path::Path;
+
+// Notes:
+// [1] A `.mod.c` file is generated by modpost for each module. When the kernel is built with
+//     CONFIG_MODVERSIONS, the file contains a table of all symbols that the module imports, along
+//     with their CRCs:
+//
+//     static const struct modversion_info ____versions[]
+//     __used __section("__versions") = {
+//             { 0x12345678, "module_layout" },
+//             { 0x9abcdef0, "printk" },
+//     };
+//
+//     With CONFIG_EXTENDED_MODVERSIONS, available since Linux 6.14, the names are stored also, or
+//     exclusively if CONFIG_BASIC_MODVERSIONS is disabled, in a string table. Symbols with names
+//     too long for `struct modversion_info` appear only in this table, which is therefore preferred
+//     if present, in the same way as in `ElfObject::module_imports()`:
+//
+//     static const char ____version_ext_names[]
+//     __used __section("__version_ext_names") =
+//             "module_layout\0"
+//             "printk\0"
+//     ;
+
+impl SymCorpus {
+    /// Loads the version table of a module from a specified reader [1].
+    ///
+    /// The `path` should point to a `.mod.c` file, indicating the origin of the data. The name of
+    /// the module is derived from it.
+    pub(super) fn load_versions<P: AsRef<Path>, R: Read>(
+        path: P,
+        reader: R,
+        load_context: &LoadContext,
+    ) -> Result<(), crate::Error> {
+        let path = path.as_ref();
+        debug!("Loading versions from '{}'", path.display());
+
+        let module = path
+            .file_name()
+            .and_then(|file_name| file_name.to_str())
+            .and_then(|file_name| file_name.strip_suffix(".mod.c"))
+            .ok_or_else(|| crate::Error::new_parse(path, None, "Invalid name of a .mod.c file"))?;
+
+        let lines = match read_lines(reader) {
+            Ok(lines) => lines,
+            Err(err) => return Err(crate::Error::new_io("Failed to read .mod.c data", err)),
+        };
+
+        let imports = parse_versions(path, &lines)?;
+
+        let mut consumers = load_context.consumers.lock().unwrap();
+        for name in imports {
+            consumers.entry(name).or_default().push(module.to_string());
+        }
+
+        Ok(())
+    }
+
+    /// Returns sorted names of all modules which consume a specified export, according to the
+    /// version tables recorded in this corpus and the `other_corpus`.
+    pub(super) fn get_consumers<'a>(
+        &'a self,
+        other_corpus: &'a SymCorpus,
+        name: &str,
+    ) -> Vec<&'a str> {
+        let mut modules = [self, other_corpus]
+            .iter()
+            .filter_map(|corpus| corpus.consumers.get(name))
+            .flatten()
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+        modules.sort();
+        modules.dedup();
+        modules
+    }
+}
+
+/// Formats the list of modules which consume an export, to be appended after its name.
+pub(super) fn format_consumers(modules: &[&str]) -> String {
+    if modules.is_empty() {
+        return String::new();
+    }
+    format!(
+        " (consumed by '{}' modules: {})",
+        modules.len(),
+        modules.join(", ")
+    )
+}
+
+/// A table of imported symbols in a `.mod.c` file.
+#[derive(Clone, Copy)]
+enum VersionTable {
+    Versions,
+    ExtNames,
+}
+
+/// Parses the `__versions` and `__version_ext_names` tables in the lines of a `.mod.c` file and
+/// returns names of all imported symbols [1].
+fn parse_versions(path: &Path, lines: &[String]) -> Result<Vec<String>, crate::Error> {
+    let mut imports = Vec::new();
+    let mut maybe_ext_imports = None;
+    let mut maybe_table = None;
+    for (line_idx, line) in lines.iter().enumerate() {
+        let line = line.trim();
+        let table = match maybe_table {
+            Some(table) => table,
+            None => {
+                // The attribute which places a table into its section is spelled differently
+                // across kernel versions, look only for the section name.
+                if line.contains("__versions") && line.ends_with("= {") {
+                    maybe_table = Some(VersionTable::Versions);
+                } else if line.contains("__version_ext_names") && line.ends_with('=') {
+                    maybe_table = Some(VersionTable::ExtNames);
+                    maybe_ext_imports = Some(Vec::new());
+                }
+                continue;
+            }
+        };
+
+        match table {
+            VersionTable::Versions => {
+                if line.starts_with("};") {
+                    maybe_table = None;
+                    continue;
+                }
+
+                // Parse an entry in form `{ <crc>, "<name>" },`.
+                let name = line
+                    .strip_prefix('{')
+                    .and_then(|entry| entry.split_once('"'))
+                    .and_then(|(_, rest)| rest.split_once('"'))
+                    .map(|(name, _)| name)
+                    .ok_or_else(|| {
+                        crate::Error::new_parse(path, Some(line_idx + 1), "Invalid version entry")
+                    })?;
+                imports.push(name.to_string());
+            }
+            VersionTable::ExtNames => {
+                if line.starts_with(';') {
+                    maybe_table = None;
+                    continue;
+                }
+
+                // Parse an entry in form `"<name>\0"`.
+                let name = line
+                    .strip_prefix('"')
+                    .and_then(|entry| entry.strip_suffix("\\0\""))
+                    .ok_or_else(|| {
+                        crate::Error::new_parse(path, Some(line_idx + 1), "Invalid version entry")
+                    })?;
+                if let Some(ext_imports) = maybe_ext_imports.as_mut() {
+                    ext_imports.push(name.to_string());
+                }
+            }
+        }
+    }
+    Ok(maybe_ext_imports.unwrap_or(imports))
+}
+
+/// Reads names of all symbols imported by a kernel module.
+///
+/// The `path` can point to a `.mod.c` file, in which case the imports are read from its
+/// `__versions` or `__version_ext_names` table, or to a `.ko` file, in which case they are read
+/// from its version table in the ELF data.
+pub fn read_module_imports<P: AsRef<Path>>(path: P) -> Result<Vec<String>, crate::Error> {
+    let path = path.as_ref();
+
+    if path.to_str().is_some_and(|path| path.ends_with(".mod.c")) {
+        let file = PathFile::open(path).map_err(|err| {
+            crate::Error::new_io(&format!("Failed to open file '{}'", path.display()), err)
+        })?;
+        let lines = match read_lines(file) {
+            Ok(lines) => lines,
+            Err(err) => return Err(crate::Error::new_io("Failed to read .mod.c data", err)),
+        };
+        return parse_versions(path, &lines);
+    }
+
+    ElfObject::open(path)?.module_imports()
+}
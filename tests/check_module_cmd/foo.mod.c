@@ -0,0 +1,13 @@
+#include <linux/module.h>
+#include <linux/export-internal.h>
+#include <linux/compiler.h>
+
+MODULE_INFO(name, KBUILD_MODNAME);
+
+static const struct modversion_info ____versions[]
+__used __section("__versions") = {
+	{ 0x12345678, "bar" },
+	{ 0x9abcdef0, "module_layout" },
+};
+
+MODULE_INFO(depends, "");
@@ -4,7 +4,7 @@
 use std::ffi::OsStr;
 use std::fs;
 use std::path::Path;
-use std::process::{Command, ExitStatus};
+use std::process::{Command, ExitStatus, Stdio};
 
 struct RunResult {
     status: ExitStatus,
@@ -77,6 +77,533 @@ fn compare_cmd_dash_dash() {
     assert_eq!(result.stderr, "");
 }
 
+#[test]
+fn compare_cmd_consumers() {
+    // Check that the compare command reports modules which consume a changed export.
+    let result = ksymtypes_run([
+        "compare",
+        "tests/compare_cmd/a.symtypes",
+        "tests/compare_cmd/build",
+    ]);
+    assert!(result.status.success());
+    assert_eq!(
+        result.stdout,
+        concat!(
+            "The following '1' exports are different:\n",
+            " foo (consumed by '1' modules: qux)\n",
+            "\n",
+            "because of a changed 'foo':\n",
+            "@@ -1,3 +1,3 @@\n",
+            " void foo (\n",
+            "-\tint a\n",
+            "+\tlong a\n",
+            " )\n", //
+        )
+    );
+    assert_eq!(result.stderr, "");
+}
+
+#[test]
+fn compare_cmd_gendwarfksyms() {
+    // Check that the compare command can compare symtypes produced by genksyms and gendwarfksyms.
+    let result = ksymtypes_run([
+        "compare",
+        "tests/compare_cmd/a.symtypes",
+        "tests/compare_cmd/c.symtypes",
+    ]);
+    assert!(result.status.success());
+    assert_eq!(
+        result.stdout,
+        concat!(
+            "The following '1' exports are different:\n",
+            " foo\n",
+            "\n",
+            "because of a changed 'foo':\n",
+            "@@ -1,3 +1,3 @@\n",
+            " void foo (\n",
+            "-\tint a\n",
+            "+\tlong a\n",
+            " )\n", //
+        )
+    );
+    assert_eq!(result.stderr, "");
+}
+
+#[test]
+fn compare_cmd_gendwarfksyms_base_types() {
+    // Check that the compare command doesn't report base types which are spelled differently by
+    // genksyms and gendwarfksyms as changed.
+    let result = ksymtypes_run([
+        "compare",
+        "tests/compare_cmd/b.symtypes",
+        "tests/compare_cmd/c.symtypes",
+    ]);
+    assert!(result.status.success());
+    assert_eq!(result.stdout, "");
+    assert_eq!(result.stderr, "");
+}
+
+#[test]
+fn compare_cmd_btf() {
+    // Check that the compare command can compare symtypes with BTF of vmlinux and modules.
+    let result = ksymtypes_run([
+        "compare",
+        "tests/compare_cmd/d.symtypes",
+        "tests/compare_cmd/btf",
+    ]);
+    assert!(result.status.success());
+    assert_eq!(
+        result.stdout,
+        concat!(
+            "The following '2' exports are different:\n",
+            " bar\n",
+            " baz\n",
+            "\n",
+            "because of a changed 's#foo':\n",
+            "@@ -1,3 +1,4 @@\n",
+            " struct foo {\n",
+            " \tint a;\n",
+            "+\tint b;\n",
+            " }\n", //
+        )
+    );
+    assert_eq!(result.stderr, "");
+}
+
+#[test]
+fn compare_cmd_btf_module() {
+    // Check that BTF of a module cannot be read without vmlinux.
+    let result = ksymtypes_run([
+        "compare",
+        "tests/compare_cmd/d.symtypes",
+        "tests/compare_cmd/btf/drivers/foo.ko",
+    ]);
+    assert!(!result.status.success());
+    assert_eq!(result.stdout, "");
+    assert_eq!(
+        result.stderr,
+        "Failed to read symtypes from 'tests/compare_cmd/btf/drivers/foo.ko': tests/compare_cmd/btf/drivers/foo.ko: BTF of a kernel module can be loaded only from a directory which contains also vmlinux\n"
+    );
+}
+
+#[test]
+fn compare_cmd_group_by_module() {
+    // Check that the compare command can group changes by modules, as determined by modules.order
+    // and .mod files.
+    let result = ksymtypes_run([
+        "compare",
+        "--group-by=module",
+        "tests/compare_cmd/modules_old",
+        "tests/compare_cmd/modules_new",
+    ]);
+    assert!(result.status.success());
+    assert_eq!(
+        result.stdout,
+        concat!(
+            "Changes in module 'foo':\n",
+            "Export 'bar' has been removed\n",
+            "Export 'qux' has been added\n",
+            "The following '1' exports are different:\n",
+            " foo\n",
+            "\n",
+            "because of a changed 'foo':\n",
+            "@@ -1,3 +1,3 @@\n",
+            " int foo (\n",
+            "-\tint a\n",
+            "+\tlong a\n",
+            " )\n",
+            "\n",
+            "Changes in module 'vmlinux':\n",
+            "The following '1' exports are different:\n",
+            " baz\n",
+            "\n",
+            "because of a changed 'baz':\n",
+            "@@ -1,3 +1,3 @@\n",
+            " int baz (\n",
+            "-\tint a\n",
+            "+\tlong a\n",
+            " )\n", //
+        )
+    );
+    assert_eq!(result.stderr, "");
+}
+
+#[test]
+fn compare_cmd_maintainers() {
+    // Check that the compare command can group changes by subsystems listed in a MAINTAINERS file.
+    let result = ksymtypes_run([
+        "compare",
+        "--maintainers=tests/compare_cmd/MAINTAINERS",
+        "tests/compare_cmd/modules_old",
+        "tests/compare_cmd/modules_new",
+    ]);
+    assert!(result.status.success());
+    assert_eq!(
+        result.stdout,
+        concat!(
+            "Changes in subsystem 'FOO DRIVER':\n",
+            "Maintainer: Jane Doe <jane@example.com>\n",
+            "List: foo@vger.kernel.org\n",
+            "Export 'bar' has been removed\n",
+            "Export 'qux' has been added\n",
+            "The following '1' exports are different:\n",
+            " foo\n",
+            "\n",
+            "because of a changed 'foo':\n",
+            "@@ -1,3 +1,3 @@\n",
+            " int foo (\n",
+            "-\tint a\n",
+            "+\tlong a\n",
+            " )\n",
+            "\n",
+            "Changes in subsystem 'THE REST':\n",
+            "Maintainer: Linus Torvalds <torvalds@linux-foundation.org>\n",
+            "List: linux-kernel@vger.kernel.org\n",
+            "The following '1' exports are different:\n",
+            " baz\n",
+            "\n",
+            "because of a changed 'baz':\n",
+            "@@ -1,3 +1,3 @@\n",
+            " int baz (\n",
+            "-\tint a\n",
+            "+\tlong a\n",
+            " )\n", //
+        )
+    );
+    assert_eq!(result.stderr, "");
+}
+
+#[test]
+fn compare_cmd_source_tree() {
+    // Check that the compare command can locate definitions of changed types in a source tree.
+    let result = ksymtypes_run([
+        "compare",
+        "--source-tree=tests/compare_cmd/source/source",
+        "tests/compare_cmd/source/a.symtypes",
+        "tests/compare_cmd/source/b.symtypes",
+    ]);
+    assert!(result.status.success());
+    assert_eq!(
+        result.stdout,
+        concat!(
+            "The following '1' exports are different:\n",
+            " bar\n",
+            "\n",
+            "because of a changed 's#foo' (include/linux/foo.h:5):\n",
+            "@@ -1,3 +1,3 @@\n",
+            " struct foo {\n",
+            "-\tint a;\n",
+            "+\tlong a;\n",
+            " }\n", //
+        )
+    );
+    assert_eq!(result.stderr, "");
+}
+
+#[test]
+fn compare_cmd_color() {
+    // Check that the compare command can highlight the differences with colors.
+    let result = ksymtypes_run([
+        "compare",
+        "--color=always",
+        "tests/compare_cmd/a.symtypes",
+        "tests/compare_cmd/b.symtypes",
+    ]);
+    assert!(result.status.success());
+    assert_eq!(
+        result.stdout,
+        concat!(
+            "The following '1' exports are different:\n",
+            " foo\n",
+            "\n",
+            "\x1b[1mbecause of a changed 'foo':\x1b[m\n",
+            "\x1b[36m@@ -1,3 +1,3 @@\x1b[m\n",
+            " void foo (\n",
+            "\x1b[31m-\tint a\x1b[m\n",
+            "\x1b[32m+\tlong a\x1b[m\n",
+            " )\n", //
+        )
+    );
+    assert_eq!(result.stderr, "");
+}
+
+#[test]
+fn compare_cmd_color_invalid() {
+    // Check that the compare command rejects an unknown color mode.
+    let result = ksymtypes_run([
+        "compare",
+        "--color=sometimes",
+        "tests/compare_cmd/a.symtypes",
+        "tests/compare_cmd/b.symtypes",
+    ]);
+    assert!(!result.status.success());
+    assert_eq!(result.stdout, "");
+    assert_eq!(
+        result.stderr,
+        "Invalid value for '--color=sometimes': unknown mode 'sometimes'\n"
+    );
+}
+
+#[test]
+fn compare_cmd_broken_pipe() {
+    // Check that the compare command silently stops when its output is closed early.
+    let mut child = Command::new(env!("CARGO_BIN_EXE_ksymtypes"))
+        .args([
+            "compare",
+            "tests/compare_cmd/a.symtypes",
+            "tests/compare_cmd/b.symtypes",
+        ])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to execute ksymtypes");
+    drop(child.stdout.take());
+    let output = child
+        .wait_with_output()
+        .expect("failed to wait for ksymtypes");
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stderr).unwrap(), "");
+}
+
+#[test]
+fn compare_cmd_sarif() {
+    // Check that the compare command can produce SARIF output.
+    let result = ksymtypes_run([
+        "compare",
+        "--format=sarif",
+        "tests/compare_cmd/a.symtypes",
+        "tests/compare_cmd/b.symtypes",
+    ]);
+    assert!(result.status.success());
+    assert!(result.stdout.starts_with(concat!(
+        "{\n",
+        "  \"$schema\": \"https://json.schemastore.org/sarif-2.1.0.json\",\n",
+        "  \"version\": \"2.1.0\",\n", //
+    )));
+    assert!(result.stdout.contains(concat!(
+        "          \"ruleId\": \"type-changed\",\n",
+        "          \"level\": \"error\",\n",
+        "          \"message\": {\n",
+        "            \"text\": \"Type 'foo' has changed, affecting '1' exports: foo\"\n", //
+    )));
+    assert_eq!(result.stderr, "");
+}
+
+#[test]
+fn compare_cmd_junit() {
+    // Check that the compare command can produce JUnit XML output.
+    let result = ksymtypes_run([
+        "compare",
+        "--format=junit",
+        "tests/compare_cmd/a.symtypes",
+        "tests/compare_cmd/b.symtypes",
+    ]);
+    assert!(result.status.success());
+    assert_eq!(
+        result.stdout,
+        concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<testsuites name=\"ksymtypes\" tests=\"1\" failures=\"1\" errors=\"0\" skipped=\"0\">\n",
+            "  <testsuite name=\"kabi\" tests=\"1\" failures=\"1\" errors=\"0\" skipped=\"0\">\n",
+            "    <testcase classname=\"kabi\" name=\"foo\">\n",
+            "      <failure message=\"Export &apos;foo&apos; is different because of a changed &apos;foo&apos;\">because of a changed &apos;foo&apos;:\n",
+            "@@ -1,3 +1,3 @@\n",
+            " void foo (\n",
+            "-\tint a\n",
+            "+\tlong a\n",
+            " )\n",
+            "</failure>\n",
+            "    </testcase>\n",
+            "  </testsuite>\n",
+            "</testsuites>\n", //
+        )
+    );
+    assert_eq!(result.stderr, "");
+}
+
+#[test]
+fn compare_cmd_markdown() {
+    // Check that the compare command can produce Markdown output.
+    let result = ksymtypes_run([
+        "compare",
+        "--format=markdown",
+        "tests/compare_cmd/a.symtypes",
+        "tests/compare_cmd/b.symtypes",
+    ]);
+    assert!(result.status.success());
+    assert_eq!(
+        result.stdout,
+        concat!(
+            "## kABI comparison\n",
+            "\n",
+            "| Change | Count |\n",
+            "| --- | ---: |\n",
+            "| Removed exports | 0 |\n",
+            "| Added exports | 0 |\n",
+            "| Changed exports | 1 |\n",
+            "| Changed export declarations | 1 |\n",
+            "\n",
+            "### Changed types\n",
+            "\n",
+            "<details>\n",
+            "<summary><code>foo</code> affecting '1' exports</summary>\n",
+            "\n",
+            "Affected exports:\n",
+            "\n",
+            "- `foo`\n",
+            "\n",
+            "```diff\n",
+            "@@ -1,3 +1,3 @@\n",
+            " void foo (\n",
+            "-\tint a\n",
+            "+\tlong a\n",
+            " )\n",
+            "```\n",
+            "\n",
+            "</details>\n", //
+        )
+    );
+    assert_eq!(result.stderr, "");
+}
+
+#[test]
+fn compare_cmd_html() {
+    // Check that the compare command can produce HTML output.
+    let result = ksymtypes_run([
+        "compare",
+        "--format=html",
+        "tests/compare_cmd/a.symtypes",
+        "tests/compare_cmd/b.symtypes",
+    ]);
+    assert!(result.status.success());
+    assert!(result.stdout.starts_with("<!DOCTYPE html>\n"));
+    assert!(result.stdout.contains(concat!(
+        "<tr><td>void foo (</td><td>void foo (</td></tr>\n",
+        "<tr><td class=\"removed\">\tint a</td><td class=\"added\">\tlong a</td></tr>\n",
+        "<tr><td>)</td><td>)</td></tr>\n", //
+    )));
+    assert!(result.stdout.ends_with("</html>\n"));
+    assert_eq!(result.stderr, "");
+}
+
+#[test]
+fn compare_cmd_html_source_tree() {
+    // Check that the compare command shows locations of changed types in HTML output.
+    let result = ksymtypes_run([
+        "compare",
+        "--format=html",
+        "--source-tree=tests/compare_cmd/source/source",
+        "tests/compare_cmd/source/a.symtypes",
+        "tests/compare_cmd/source/b.symtypes",
+    ]);
+    assert!(result.status.success());
+    assert!(result
+        .stdout
+        .contains("<p>Defined at: <code>include/linux/foo.h:5</code></p>\n"));
+    assert_eq!(result.stderr, "");
+}
+
+#[test]
+fn compare_cmd_format_grouping() {
+    // Check that the compare command rejects grouping with a format other than text.
+    let result = ksymtypes_run([
+        "compare",
+        "--format=sarif",
+        "--group-by=module",
+        "tests/compare_cmd/a.symtypes",
+        "tests/compare_cmd/b.symtypes",
+    ]);
+    assert!(!result.status.success());
+    assert_eq!(result.stdout, "");
+    assert_eq!(
+        result.stderr,
+        "Option '--group-by' can be used only with the text format\n"
+    );
+
+    let result = ksymtypes_run([
+        "compare",
+        "--format=markdown",
+        "--maintainers=tests/compare_cmd/MAINTAINERS",
+        "tests/compare_cmd/a.symtypes",
+        "tests/compare_cmd/b.symtypes",
+    ]);
+    assert!(!result.status.success());
+    assert_eq!(result.stdout, "");
+    assert_eq!(
+        result.stderr,
+        "Option '--maintainers' can be used only with the text format\n"
+    );
+}
+
+#[test]
+fn compare_cmd_unified() {
+    // Check that the compare command allows specifying the number of context lines and that hunk
+    // headers name the enclosing scope.
+    let result = ksymtypes_run([
+        "compare",
+        "-U0",
+        "tests/compare_cmd/a.symtypes",
+        "tests/compare_cmd/b.symtypes",
+    ]);
+    assert!(result.status.success());
+    assert_eq!(
+        result.stdout,
+        concat!(
+            "The following '1' exports are different:\n",
+            " foo\n",
+            "\n",
+            "because of a changed 'foo':\n",
+            "@@ -2,1 +2,1 @@ void foo (\n",
+            "-\tint a\n",
+            "+\tlong a\n", //
+        )
+    );
+    assert_eq!(result.stderr, "");
+}
+
+#[test]
+fn compare_cmd_unified_invalid() {
+    // Check that the compare command rejects an invalid number of context lines.
+    let result = ksymtypes_run([
+        "compare",
+        "--unified=many",
+        "tests/compare_cmd/a.symtypes",
+        "tests/compare_cmd/b.symtypes",
+    ]);
+    assert!(!result.status.success());
+    assert_eq!(result.stdout, "");
+    assert_eq!(
+        result.stderr,
+        "Invalid value for '--unified=many': invalid digit found in string\n"
+    );
+}
+
+#[test]
+fn compare_cmd_word_diff() {
+    // Check that the compare command can show the changed tokens inline.
+    let result = ksymtypes_run([
+        "compare",
+        "--word-diff",
+        "tests/compare_cmd/a.symtypes",
+        "tests/compare_cmd/b.symtypes",
+    ]);
+    assert!(result.status.success());
+    assert_eq!(
+        result.stdout,
+        concat!(
+            "The following '1' exports are different:\n",
+            " foo\n",
+            "\n",
+            "because of a changed 'foo':\n",
+            "@@ -1,3 +1,3 @@\n",
+            "void foo (\n",
+            "\t[-int-]{+long+} a\n",
+            ")\n", //
+        )
+    );
+    assert_eq!(result.stderr, "");
+}
+
 #[test]
 fn consolidate_cmd() {
     // Check that the consolidate command trivially works.
@@ -104,7 +631,7 @@ fn consolidate_cmd_output() {
     let result = ksymtypes_run([
         AsRef::<OsStr>::as_ref("consolidate"),
         "--output".as_ref(),
-        &output_path.as_ref(),
+        output_path.as_ref(),
         "tests/consolidate_cmd".as_ref(),
     ]);
     assert!(result.status.success());
@@ -122,3 +649,313 @@ fn consolidate_cmd_output() {
         )
     );
 }
+
+#[test]
+fn consolidate_cmd_errors() {
+    // Check that the consolidate command reports errors in all input files.
+    let result = ksymtypes_run(["consolidate", "tests/consolidate_cmd_errors"]);
+    assert!(!result.status.success());
+    assert_eq!(result.stdout, "");
+    assert_eq!(
+        result.stderr,
+        concat!(
+            "Failed to read symtypes from 'tests/consolidate_cmd_errors': Found '2' errors:\n",
+            " b.symtypes:2: Type 's#qux' is not known\n",
+            " c.symtypes:2: Duplicate record 's#foo'\n", //
+        )
+    );
+}
+
+#[test]
+fn consolidate_cmd_lenient() {
+    // Check that the consolidate command in the lenient mode skips input files with errors.
+    let result = ksymtypes_run(["consolidate", "--lenient", "tests/consolidate_cmd_errors"]);
+    assert!(result.status.success());
+    assert_eq!(
+        result.stdout,
+        concat!(
+            "s#foo struct foo { int a ; }\n",
+            "bar int bar ( s#foo )\n",
+            "F#a.symtypes bar\n", //
+        )
+    );
+    assert_eq!(
+        result.stderr,
+        concat!(
+            "Warning: b.symtypes:2: Type 's#qux' is not known\n",
+            "Warning: c.symtypes:2: Duplicate record 's#foo'\n",
+            "Skipped '2' files with errors when reading symtypes from 'tests/consolidate_cmd_errors'\n", //
+        )
+    );
+}
+
+#[test]
+fn tree_cmd() {
+    // Check that the tree command trivially works.
+    let result = ksymtypes_run(["tree", "tests/tree_cmd/a.symtypes", "bar"]);
+    assert!(result.status.success());
+    assert_eq!(
+        result.stdout,
+        concat!(
+            "bar@0\n",
+            "`-s#foo@0 (p)\n", //
+        )
+    );
+    assert_eq!(result.stderr, "");
+}
+
+#[test]
+fn tree_cmd_unknown_export() {
+    // Check that the tree command reports a missing export.
+    let result = ksymtypes_run(["tree", "tests/tree_cmd/a.symtypes", "baz"]);
+    assert!(!result.status.success());
+    assert_eq!(result.stdout, "");
+    assert_eq!(
+        result.stderr,
+        "Failed to write the tree of 'baz': Export 'baz' is not known\n"
+    );
+}
+
+#[test]
+fn stats_cmd() {
+    // Check that the stats command trivially works.
+    let result = ksymtypes_run(["stats", "tests/consolidate_cmd"]);
+    assert!(result.status.success());
+    assert_eq!(
+        result.stdout,
+        concat!(
+            "Files: 2\n",
+            "Exports: 2\n",
+            "Types:\n",
+            " s#: 1\n",
+            " u#: 0\n",
+            " e#: 0\n",
+            " t#: 0\n",
+            " E#: 0\n",
+            "Type variants: 1\n",
+            "Types with the most variants:\n",
+            " s#foo: 1\n",
+            "Largest type definitions:\n",
+            " s#foo@0: 7 tokens\n",
+            "Exports with the biggest type closures:\n",
+            " bar: 1 types\n",
+            " baz: 1 types\n",
+            "Consolidation: 4 records in files, 3 consolidated records, 25.0% saved\n", //
+        )
+    );
+    assert_eq!(result.stderr, "");
+}
+
+#[test]
+fn stats_cmd_json() {
+    // Check that the stats command can produce JSON output.
+    let result = ksymtypes_run(["stats", "--format=json", "tests/consolidate_cmd"]);
+    assert!(result.status.success());
+    assert!(result.stdout.starts_with("{\n  \"files\": 2,\n"));
+    assert!(result
+        .stdout
+        .ends_with("    \"saved_percent\": 25.0\n  }\n}\n"));
+    assert_eq!(result.stderr, "");
+}
+
+#[test]
+fn variants_cmd() {
+    // Check that the variants command trivially works.
+    let result = ksymtypes_run(["variants", "tests/variants_cmd/test.kabi", "s#foo"]);
+    assert!(result.status.success());
+    assert_eq!(
+        result.stdout,
+        concat!(
+            "Type 's#foo' has '2' variants\n",
+            "\n",
+            "Variant '0' is used by the following '2' files:\n",
+            " a/\n",
+            "  test.symtypes\n",
+            " b/\n",
+            "  test3.symtypes\n",
+            "\n",
+            "with the definition:\n",
+            "struct foo {\n",
+            "\tint a;\n",
+            "}\n",
+            "\n",
+            "Variant '1' is used by the following '1' files:\n",
+            " b/\n",
+            "  test2.symtypes\n",
+            "\n",
+            "with the definition:\n",
+            "struct foo {\n",
+            "\tUNKNOWN\n",
+            "}\n",
+            "\n",
+            "and the difference against the most common variant '0':\n",
+            "@@ -1,3 +1,3 @@\n",
+            " struct foo {\n",
+            "-\tint a;\n",
+            "+\tUNKNOWN\n",
+            " }\n", //
+        )
+    );
+    assert_eq!(result.stderr, "");
+}
+
+#[test]
+fn variants_cmd_color() {
+    // Check that the variants command can highlight the differences with colors.
+    let result = ksymtypes_run([
+        "variants",
+        "--color=always",
+        "tests/variants_cmd/test.kabi",
+        "s#foo",
+    ]);
+    assert!(result.status.success());
+    assert!(result.stdout.ends_with(concat!(
+        "\x1b[1mand the difference against the most common variant '0':\x1b[m\n",
+        "\x1b[36m@@ -1,3 +1,3 @@\x1b[m\n",
+        " struct foo {\n",
+        "\x1b[31m-\tint a;\x1b[m\n",
+        "\x1b[32m+\tUNKNOWN\x1b[m\n",
+        " }\n", //
+    )));
+    assert_eq!(result.stderr, "");
+}
+
+#[test]
+fn lint_cmd() {
+    // Check that the lint command reports conflicting types but succeeds.
+    let result = ksymtypes_run(["lint", "tests/lint_cmd/test.kabi"]);
+    assert!(result.status.success());
+    assert_eq!(
+        result.stdout,
+        concat!(
+            "Type 's#foo' has '2' conflicting complete definitions\n",
+            "\n",
+            "Variant '0' is used by the following '1' files:\n",
+            " a/\n",
+            "  test.symtypes\n",
+            "\n",
+            "Variant '1' is used by the following '1' files:\n",
+            " b/\n",
+            "  test2.symtypes\n",
+            "\n",
+            "and the difference against the most common variant '0':\n",
+            "@@ -1,3 +1,3 @@\n",
+            " struct foo {\n",
+            "-\tint a;\n",
+            "+\tlong a;\n",
+            " }\n", //
+        )
+    );
+    assert_eq!(result.stderr, "");
+}
+
+#[test]
+fn lint_cmd_strict() {
+    // Check that the lint command fails in the strict mode when a conflicting type is found.
+    let result = ksymtypes_run(["lint", "--strict", "tests/lint_cmd/test.kabi"]);
+    assert!(!result.status.success());
+    assert_eq!(
+        result.stderr,
+        "Found '1' types with conflicting definitions in 'tests/lint_cmd/test.kabi'\n"
+    );
+}
+
+#[test]
+fn lint_cmd_color() {
+    // Check that the lint command can highlight the differences with colors.
+    let result = ksymtypes_run(["lint", "--color=always", "tests/lint_cmd/test.kabi"]);
+    assert!(result.status.success());
+    assert!(result.stdout.ends_with(concat!(
+        "\x1b[1mand the difference against the most common variant '0':\x1b[m\n",
+        "\x1b[36m@@ -1,3 +1,3 @@\x1b[m\n",
+        " struct foo {\n",
+        "\x1b[31m-\tint a;\x1b[m\n",
+        "\x1b[32m+\tlong a;\x1b[m\n",
+        " }\n", //
+    )));
+    assert_eq!(result.stderr, "");
+}
+
+#[test]
+fn check_exports_cmd() {
+    // Check that the check-exports command reports mismatched exports but succeeds.
+    let result = ksymtypes_run([
+        "check-exports",
+        "tests/check_exports_cmd/a.symtypes",
+        "tests/check_exports_cmd/build",
+    ]);
+    assert!(result.status.success());
+    assert_eq!(
+        result.stdout,
+        concat!(
+            "The following '2' exports are present in binaries but missing in the symtypes corpus:\n",
+            " bar (vmlinux)\n",
+            " qux (drivers/qux.ko)\n",
+            "\n",
+            "The following '1' exports are present in the symtypes corpus but missing in binaries:\n",
+            " baz (tests/check_exports_cmd/a.symtypes)\n", //
+        )
+    );
+    assert_eq!(result.stderr, "");
+}
+
+#[test]
+fn check_exports_cmd_strict() {
+    // Check that the check-exports command fails in the strict mode when a mismatched export is
+    // found.
+    let result = ksymtypes_run([
+        "check-exports",
+        "--strict",
+        "tests/check_exports_cmd/a.symtypes",
+        "tests/check_exports_cmd/build/drivers/qux.ko",
+    ]);
+    assert!(!result.status.success());
+    assert_eq!(
+        result.stderr,
+        "Found '3' mismatched exports between 'tests/check_exports_cmd/a.symtypes' and 'tests/check_exports_cmd/build/drivers/qux.ko'\n"
+    );
+}
+
+#[test]
+fn check_module_cmd() {
+    // Check that the check-module command reports changed imports of a module but succeeds.
+    let result = ksymtypes_run([
+        "check-module",
+        "tests/check_module_cmd/a.symtypes",
+        "tests/check_module_cmd/b.symtypes",
+        "tests/check_module_cmd/foo.mod.c",
+    ]);
+    assert!(result.status.success());
+    assert_eq!(
+        result.stdout,
+        concat!(
+            "The following '1' exports are different:\n",
+            " bar\n",
+            "\n",
+            "because of a changed 'bar':\n",
+            "@@ -1,3 +1,3 @@\n",
+            " int bar (\n",
+            "-\tint a\n",
+            "+\tlong a\n",
+            " )\n", //
+        )
+    );
+    assert_eq!(result.stderr, "");
+}
+
+#[test]
+fn check_module_cmd_strict() {
+    // Check that the check-module command fails in the strict mode when a changed import is found.
+    let result = ksymtypes_run([
+        "check-module",
+        "--strict",
+        "tests/check_module_cmd/a.symtypes",
+        "tests/check_module_cmd/b.symtypes",
+        "tests/check_module_cmd/foo.mod.c",
+    ]);
+    assert!(!result.status.success());
+    assert_eq!(
+        result.stderr,
+        "Found '1' removed or changed imports of 'tests/check_module_cmd/foo.mod.c'\n"
+    );
+}
@@ -0,0 +1 @@
+drivers/foo/foo_main.o drivers/foo/foo_util.o
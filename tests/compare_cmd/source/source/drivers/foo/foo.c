@@ -0,0 +1,9 @@
+// SPDX-License-Identifier: GPL-2.0
+#include <linux/export.h>
+#include <linux/foo.h>
+
+int bar(struct foo *p)
+{
+	return p->a;
+}
+EXPORT_SYMBOL(bar);
@@ -0,0 +1,11 @@
+/* SPDX-License-Identifier: GPL-2.0 */
+#ifndef _LINUX_FOO_H
+#define _LINUX_FOO_H
+
+struct foo {
+	long a;
+};
+
+int bar(struct foo *p);
+
+#endif /* _LINUX_FOO_H */